@@ -178,13 +178,13 @@ pub fn get_filtered_url(filters: Vec<Filter>, page: i32) -> String {
 		match filter.kind {
 			FilterType::Title => {
 				if let Ok(filter) = filter.value.as_string() {
-					title_filter = urlencode(filter.read().to_lowercase());
+					title_filter = search_slug(filter.read().to_lowercase());
 					is_searching = true;
 				}
 			}
 			FilterType::Author => {
 				if let Ok(filter) = filter.value.as_string() {
-					author_filter = urlencode(filter.read().to_lowercase());
+					author_filter = search_slug(filter.read().to_lowercase());
 					is_searching = true;
 				}
 			}
@@ -317,7 +317,10 @@ pub fn parse_incoming_url_chapter_id(url: &str) -> Option<String> {
 
 // HELPER FUNCTIONS
 
-pub fn urlencode(string: String) -> String {
+/// Turns a search term into the slug used in search URLs: lowercase, without
+/// Vietnamese diacritics, with symbols and spaces replaced by `_`. This isn't
+/// percent-encoding, so it isn't `source_support::urlencode`.
+pub fn search_slug(string: String) -> String {
 	let mut str = string.to_lowercase();
 
 	let match_a = [
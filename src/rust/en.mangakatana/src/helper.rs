@@ -1,28 +1,11 @@
 use aidoku::{
 	prelude::format,
-	std::html::Node,
 	std::{String, Vec},
-	MangaContentRating, MangaStatus, MangaViewer,
+	MangaStatus,
 };
+use source_support::CategoryRules;
 
-/// Converts `<br>` into newlines.
-pub fn text_with_newlines(node: Node) -> String {
-	let html = node.html().read();
-	if !String::from(html.trim()).is_empty() {
-		Node::new_fragment(
-			node.html()
-				.read()
-				.replace("<br>", "{{ .LINEBREAK }}")
-				.as_bytes(),
-		)
-		.expect("Failed to create new fragment")
-		.text()
-		.read()
-		.replace("{{ .LINEBREAK }}", "\n")
-	} else {
-		String::new()
-	}
-}
+pub use source_aidoku::{category_parser, text_with_newlines};
 
 /// Returns the status of a manga from a string.
 pub fn get_manga_status(status: String) -> MangaStatus {
@@ -34,42 +17,13 @@ pub fn get_manga_status(status: String) -> MangaStatus {
 	}
 }
 
-/// Returns the content rating of a manga from a vector of categories.
-pub fn get_manga_content_rating(categories: Vec<String>) -> MangaContentRating {
-	let mut rating = MangaContentRating::Safe;
-
-	if !categories.is_empty() {
-		if categories
-			.iter()
-			.any(|e| e == "Ecchi" || e == "Harem" || e == "Adult" || e == "Loli" || e == "Shota")
-		{
-			rating = MangaContentRating::Suggestive;
-		}
-		if categories
-			.iter()
-			.any(|e| e == "Gore" || e == "Sexual violence" || e == "Erotica")
-		{
-			rating = MangaContentRating::Nsfw;
-		}
-	}
-
-	rating
-}
-
-/// Returns the viewer of a manga from a vector of categories.
-pub fn get_manga_viewer(categories: Vec<String>) -> MangaViewer {
-	let mut viewer = MangaViewer::Rtl;
-
-	if !categories.is_empty()
-		&& categories
-			.iter()
-			.any(|e| e == "Manhwa" || e == "Manhua" || e == "Webtoon")
-	{
-		viewer = MangaViewer::Scroll;
-	}
-
-	viewer
-}
+/// Categories that make a manga suggestive, NSFW or a webtoon.
+pub const CATEGORY_RULES: CategoryRules = CategoryRules {
+	nsfw: &["Gore", "Sexual violence", "Erotica"],
+	suggestive: &["Ecchi", "Harem", "Adult", "Loli", "Shota"],
+	scroll: &["Manhwa", "Manhua", "Webtoon"],
+	ltr: &[],
+};
 
 /// Returns the ID of a manga from a URL.
 pub fn get_manga_id(url: String) -> String {
@@ -2,7 +2,7 @@ use aidoku::{
 	helpers::substring::Substring,
 	std::html::Node,
 	std::{String, Vec},
-	Chapter, Manga, MangaPageResult, Page,
+	Chapter, Manga, MangaContentRating, MangaPageResult, MangaViewer, Page,
 };
 
 use crate::helper::*;
@@ -52,8 +52,12 @@ pub fn parse_manga_list(html: Node, base_url: String) -> MangaPageResult {
 			categories.push(genre);
 		}
 
-		let nsfw = get_manga_content_rating(categories.clone());
-		let viewer = get_manga_viewer(categories.clone());
+		let (nsfw, viewer) = category_parser(
+			&CATEGORY_RULES,
+			&categories,
+			MangaContentRating::Safe,
+			MangaViewer::Rtl,
+		);
 
 		manga.push(Manga {
 			id,
@@ -102,8 +106,12 @@ pub fn parse_manga_details(html: Node, manga_url: String) -> Manga {
 		categories.push(genre);
 	}
 
-	let nsfw = get_manga_content_rating(categories.clone());
-	let viewer = get_manga_viewer(categories.clone());
+	let (nsfw, viewer) = category_parser(
+		&CATEGORY_RULES,
+		&categories,
+		MangaContentRating::Safe,
+		MangaViewer::Rtl,
+	);
 
 	Manga {
 		id,
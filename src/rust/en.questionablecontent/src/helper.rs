@@ -1,8 +1,7 @@
 use aidoku::{
-	helpers::{substring::*, uri::encode_uri_component},
-	prelude::*,
+	helpers::substring::*,
 	std::{String, Vec},
-	Manga, MangaContentRating, MangaStatus, MangaViewer,
+	Manga, MangaContentRating, MangaStatus, MangaViewer, Page,
 };
 
 pub fn parse_chapter_and_title(text: String) -> Option<(f32, String)> {
@@ -14,25 +13,12 @@ pub fn parse_chapter_and_title(text: String) -> Option<(f32, String)> {
 	Some((chapter, String::from(title)))
 }
 
-pub fn word_wrap(text: String) -> String {
-	let mut res = String::new();
-	text.replace("\r\n", " ")
-		.replace(['\r', '\n'], " ")
-		.split(' ')
-		.enumerate()
-		.for_each(|(i, w)| {
-			if i > 0 && i % 7 == 0 {
-				res.push('\n');
-			}
-			res.push_str(w);
-			res.push(' ');
-		});
-	res
-}
-
-pub fn newsblip_image_url(text: String) -> String {
-	let text = encode_uri_component(word_wrap(text));
-	format!("https://fakeimg.pl/1500x2126/ffffff/000000/?font=noto&font_size=42&text={text}")
+pub fn newsblip_page(index: i32, text: String) -> Page {
+	Page {
+		index,
+		text: String::from(text.trim()),
+		..Default::default()
+	}
 }
 
 pub fn comic_info() -> Manga {
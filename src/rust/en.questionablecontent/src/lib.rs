@@ -4,7 +4,7 @@ extern crate alloc;
 
 use aidoku::{
 	error::{AidokuError, AidokuErrorKind, Result},
-	helpers::substring::*,
+	helpers::{node::NodeHelpers, substring::*},
 	prelude::*,
 	std::{
 		net::{HttpMethod, Request},
@@ -88,20 +88,17 @@ fn get_page_list(_: String, id: String) -> Result<Vec<Page>> {
 	let blip_node = html.select("#newspost");
 
 	let url = strip_node.attr("abs:src").read();
-	let text = blip_node.text().read();
+	let text = blip_node.text_with_newlines();
 
-	Ok(vec![
-		Page {
-			index: 0,
-			url,
-			..Default::default()
-		},
-		Page {
-			index: 1,
-			url: newsblip_image_url(text),
-			..Default::default()
-		},
-	])
+	let mut pages = vec![Page {
+		index: 0,
+		url,
+		..Default::default()
+	}];
+	if !text.trim().is_empty() {
+		pages.push(newsblip_page(1, text));
+	}
+	Ok(pages)
 }
 
 #[handle_url]
@@ -1,67 +1,23 @@
 use aidoku::{
-	std::defaults::defaults_get, std::html::Node, std::net::HttpMethod, std::net::Request,
-	std::String, std::Vec, Filter, FilterType,
+	error::{AidokuError, AidokuErrorKind, NodeError, Result},
+	prelude::format,
+	std::defaults::defaults_get,
+	std::html::Node,
+	std::net::HttpMethod,
+	std::net::Request,
+	std::String,
+	std::Vec,
+	Filter, FilterType,
 };
 
-use crate::template::MadaraSiteData;
+use crate::template::{MadaraSiteData, RateLimit};
+use source_net::cached_page;
 
 extern crate alloc;
 use alloc::string::ToString;
 
-pub fn urlencode(string: String) -> String {
-	let mut result: Vec<u8> = Vec::with_capacity(string.len() * 3);
-	let hex = "0123456789abcdef".as_bytes();
-	let bytes = string.as_bytes();
-
-	for byte in bytes {
-		let curr = *byte;
-		if curr.is_ascii_lowercase() || curr.is_ascii_uppercase() || curr.is_ascii_digit() {
-			result.push(curr);
-		} else {
-			result.push(b'%');
-			result.push(hex[curr as usize >> 4]);
-			result.push(hex[curr as usize & 15]);
-		}
-	}
-
-	String::from_utf8(result).unwrap_or_default()
-}
-
-pub fn img_url_encode(string: String) -> String {
-	let mut result: Vec<u8> = Vec::with_capacity(string.len() * 3);
-	let hex = "0123456789abcdef".as_bytes();
-	let bytes = string.as_bytes();
-
-	for byte in bytes {
-		let curr = *byte;
-		if curr == b'-' {
-			result.push(b'-');
-		} else if curr == b'?' {
-			result.push(b'?');
-		} else if curr == b'%' {
-			result.push(b'%');
-		} else if curr == b'.' {
-			result.push(b'.');
-		} else if curr == b'_' {
-			result.push(b'_');
-		} else if curr.is_ascii_lowercase() || curr.is_ascii_uppercase() || curr.is_ascii_digit() {
-			result.push(curr);
-		} else {
-			result.push(b'%');
-			if hex[curr as usize >> 4] >= 97 && hex[curr as usize >> 4] <= 122 {
-				result.push(hex[curr as usize >> 4] - 32);
-			} else {
-				result.push(hex[curr as usize >> 4]);
-			}
-			if hex[curr as usize & 15] >= 97 && hex[curr as usize & 15] <= 122 {
-				result.push(hex[curr as usize & 15] - 32);
-			} else {
-				result.push(hex[curr as usize & 15]);
-			}
-		}
-	}
-	String::from_utf8(result).unwrap_or_default()
-}
+pub use source_support::{img_url_encode, urlencode};
+use source_support::{mark_line_breaks, restore_line_breaks};
 
 pub fn get_image_url(obj: Node) -> String {
 	let mut img = obj.attr("data-src").read();
@@ -88,7 +44,11 @@ pub fn get_image_url(obj: Node) -> String {
 	// encoding last part of the url as some scanlations use non-alphanumerical
 	// chars which need to be encoded
 	let img_split = img.split('/').collect::<Vec<&str>>();
-	let last_encoded = img_url_encode(String::from(img_split[img_split.len() - 1]));
+	let last = img_split[img_split.len() - 1];
+	let last_encoded = match last.split_once('?') {
+		Some((name, query)) => img_url_encode(name) + "?" + query,
+		None => img_url_encode(last),
+	};
 
 	let mut encoded_img = String::new();
 
@@ -191,34 +151,131 @@ pub fn add_user_agent_header(mut req: Request, user_agent: &Option<String>) -> R
 	req
 }
 
-pub fn get_int_manga_id(
-	manga_id: String,
-	base_url: String,
-	path: String,
-	user_agent: Option<String>,
-) -> String {
-	let url = base_url + "/" + path.as_str() + "/" + manga_id.as_str();
+/// Whether `html` is a manga page rather than e.g. a Cloudflare challenge or
+/// an error page.
+fn is_manga_page(html: &Node) -> bool {
+	!html
+		.select("div.post-title, div.summary_image, div[id^=manga-chapters-holder]")
+		.array()
+		.is_empty()
+}
 
-	let mut req = Request::new(url.as_str(), HttpMethod::Get);
-	req = add_user_agent_header(req, &user_agent);
+/// Fetches a manga page, reusing it if it was fetched in the last few
+/// minutes, so that the details, the numeric id lookup and the chapter list
+/// fallback share a single request.
+pub fn cache_manga_page(
+	url: &str,
+	user_agent: &Option<String>,
+	rate_limit: Option<&RateLimit>,
+) -> Result<Node> {
+	cached_page(
+		url,
+		|| add_user_agent_header(Request::new(url, HttpMethod::Get), user_agent),
+		rate_limit,
+		is_manga_page,
+	)
+}
+
+/// `value` if it's a numeric post id.
+fn post_id(value: &str) -> Option<String> {
+	let value = value.trim();
+	(!value.is_empty() && value.bytes().all(|b| b.is_ascii_digit())).then(|| String::from(value))
+}
 
-	if let Ok(html) = req.html() {
-		let data_id = html
+/// The digits following `key` in `text`, as in `"manga_id":"123"` or `?p=123`.
+fn digits_after(text: &str, key: &str) -> Option<String> {
+	let rest = &text[text.find(key)? + key.len()..];
+	let rest = rest.trim_start_matches(['"', ':', ' ']);
+	let end = rest
+		.find(|c: char| !c.is_ascii_digit())
+		.unwrap_or(rest.len());
+	post_id(&rest[..end])
+}
+
+/// The numeric post id of a manga, which the admin-ajax chapter endpoint
+/// needs. Themes expose it in different places, so each is tried in turn.
+pub fn get_int_manga_id(manga_id: String, data: &MadaraSiteData) -> Result<String> {
+	let url = format!("{}/{}/{manga_id}", data.base_url, data.source_path);
+	let html = cache_manga_page(&url, &data.user_agent, data.rate_limit.as_ref())?;
+
+	let id = post_id(
+		&html
 			.select("div[id^=manga-chapters-holder]")
 			.first()
 			.attr("data-id")
-			.read();
-		if !data_id.is_empty() {
-			return data_id;
+			.read(),
+	)
+	.or_else(|| post_id(&html.select("[data-post]").first().attr("data-post").read()))
+	.or_else(|| {
+		let script = html.select("script#wp-manga-js-extra").html().read();
+		digits_after(&script, "\"manga_id\"")
+	})
+	.or_else(|| {
+		let shortlink = html.select("link[rel=shortlink]").attr("href").read();
+		digits_after(&shortlink, "p=")
+	})
+	.or_else(|| {
+		let classes = html.select("body").attr("class").read();
+		classes
+			.split_whitespace()
+			.find_map(|class| class.strip_prefix("postid-").and_then(post_id))
+	});
+
+	id.ok_or_else(post_id_not_found)
+}
+
+/// None of the places themes put the post id matched. `AidokuError` can't
+/// carry a message, so the query error is what tells this apart from a page
+/// that didn't load.
+fn post_id_not_found() -> AidokuError {
+	AidokuError {
+		reason: AidokuErrorKind::NodeError(NodeError::QueryError),
+	}
+}
+
+/// The first element matched by the first of the comma separated `selectors`
+/// that matches anything, so that specific selectors can be listed before
+/// their fallbacks.
+pub fn select_first_of(html: &Node, selectors: &str) -> Option<Node> {
+	selectors
+		.split(',')
+		.map(str::trim)
+		.filter(|selector| !selector.is_empty())
+		.find_map(|selector| html.select(selector).array().next()?.as_node().ok())
+}
+
+/// The text of a novel chapter, with paragraphs separated by blank lines.
+/// Scripts and ads placed between the paragraphs are emptied first.
+pub fn get_chapter_text(body: &Node) -> String {
+	for ad in body.select("script, ins, .adsbygoogle").array() {
+		if let Ok(mut ad) = ad.as_node() {
+			let _ = ad.set_html("");
 		}
+	}
 
-		let id_html = html.select("script#wp-manga-js-extra").html().read();
-		let id = &id_html[id_html.find("manga_id").expect("Could not find manga_id") + 11
-			..id_html.find("\"}").expect("Could not find end of manga_id")];
-		String::from(id)
-	} else {
-		String::new()
+	let paragraphs = body
+		.select("p")
+		.array()
+		.filter_map(|p| p.as_node().ok())
+		.map(|p| {
+			let html = mark_line_breaks(p.html().read());
+			let text = Node::new_fragment(html.as_bytes())
+				.map(|node| node.text().read())
+				.unwrap_or_default();
+			restore_line_breaks(text)
+		})
+		.filter(|text| !text.is_empty())
+		.collect::<Vec<String>>();
+	if !paragraphs.is_empty() {
+		return paragraphs.join("\n\n");
 	}
+
+	// Some sites skip the <p> tags and only use line breaks
+	let html = mark_line_breaks(body.html().read());
+	let text = Node::new_fragment(html.as_bytes())
+		.map(|node| node.text().read())
+		.unwrap_or_default();
+	restore_line_breaks(text)
 }
 
 pub fn get_lang_code() -> Option<String> {
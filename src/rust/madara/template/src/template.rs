@@ -1,5 +1,5 @@
 use aidoku::{
-	error::Result,
+	error::{AidokuError, AidokuErrorKind, Result},
 	prelude::*,
 	std::{
 		current_date,
@@ -12,10 +12,17 @@ use aidoku::{
 };
 
 use crate::helper::*;
+use source_net::ThrottledRequest;
+use source_support::{
+	parse_chapter_number, parse_slug_chapter_number, parse_slug_volume_number, parse_volume_number,
+};
 
 extern crate alloc;
-use alloc::string::ToString;
+use alloc::{collections::BTreeSet, string::ToString};
+
+pub use source_net::RateLimit;
 
+#[derive(Clone)]
 pub struct MadaraSiteData {
 	pub base_url: String,
 	pub lang: String,
@@ -49,12 +56,20 @@ pub struct MadaraSiteData {
 	pub user_agent: Option<String>,
 	pub use_ajax_listing: bool,
 
-	pub get_manga_id: fn(String, String, String, Option<String>) -> String,
+	pub get_manga_id: fn(String, &MadaraSiteData) -> Result<String>,
 	pub viewer: fn(&Node, &Vec<String>) -> MangaViewer,
 	pub status: fn(&Node) -> MangaStatus,
 	pub nsfw: fn(&Node, &Vec<String>) -> MangaContentRating,
 
 	pub ignore_class: String,
+	pub show_novels: bool,
+	pub text_selector: String,
+
+	pub scanlator_selector: String,
+	pub chapter_next_selector: String,
+	pub chapter_max_pages: i32,
+
+	pub rate_limit: Option<RateLimit>,
 }
 
 impl Default for MadaraSiteData {
@@ -79,6 +94,18 @@ impl Default for MadaraSiteData {
 			author_selector: String::from("div.author-content a"),
 			// selector for chapter list
 			chapter_selector: String::from("li.wp-manga-chapter"),
+			// selector for the team or branch a chapter belongs to, inside a chapter
+			scanlator_selector: String::from(
+				"span.chapter-team, span.team-name, a.team-name, span.branch-name",
+			),
+			// selector for the "next page" link of a paginated chapter list
+			chapter_next_selector: String::from(
+				"div.c-chapter-pagination a.next, div.listing-chapters_wrap a.next, a.next.page-numbers",
+			),
+			// upper bound on chapter list pages to follow
+			chapter_max_pages: 50,
+			// throttle requests and back off after 429/503 responses, e.g. `Some(RateLimit::new("site", 2, 1.0))`
+			rate_limit: None,
 			// a to get the base id from requests to admin-ajax.php
 			base_id_selector: String::from("h3.h5 > a"),
 			// chapter date format
@@ -184,6 +211,10 @@ impl Default for MadaraSiteData {
 			},
 			// Ignore MangaPageResult manga with this class from a listing. Usually used for novels.
 			ignore_class: String::from(".web-novel"),
+			// Keep the manga matched by `ignore_class` in listings, for novel sites
+			show_novels: false,
+			// divs to read a text chapter from when it has no images, tried in order
+			text_selector: String::from("div.reading-content div.text-left, div.reading-content"),
 			// Localization stuff
 			status_filter_ongoing: String::from("Ongoing"),
 			status_filter_completed: String::from("Completed"),
@@ -216,12 +247,14 @@ pub fn get_search_result(data: MadaraSiteData, url: String) -> Result<MangaPageR
 
 	req = add_user_agent_header(req, &data.user_agent);
 
-	let html = req.html()?;
+	let html = req.html_throttled(data.rate_limit.as_ref())?;
 	let mut manga: Vec<Manga> = Vec::new();
 	let mut has_more = false;
 
 	for item in html.select(data.search_selector.as_str()).array() {
-		let obj = item.as_node().expect("node array");
+		let Ok(obj) = item.as_node() else {
+			continue;
+		};
 
 		let id = obj
 			.select("a")
@@ -280,14 +313,16 @@ pub fn get_series_page(data: MadaraSiteData, listing: &str, page: i32) -> Result
 
 	req = add_user_agent_header(req, &data.user_agent);
 
-	let html = req.html()?;
+	let html = req.html_throttled(data.rate_limit.as_ref())?;
 
 	let mut manga: Vec<Manga> = Vec::new();
 	let mut has_more = false;
 	for item in html.select("div.page-item-detail").array() {
-		let obj = item.as_node().expect("node array");
+		let Ok(obj) = item.as_node() else {
+			continue;
+		};
 
-		if !obj.select(&data.ignore_class).text().read().is_empty() {
+		if !data.show_novels && !obj.select(&data.ignore_class).text().read().is_empty() {
 			continue;
 		}
 
@@ -347,11 +382,7 @@ pub fn get_manga_details(manga_id: String, data: MadaraSiteData) -> Result<Manga
 		format!("{}/{}/{manga_id}", data.base_url, data.source_path)
 	};
 
-	let mut req = Request::get(&url);
-
-	req = add_user_agent_header(req, &data.user_agent);
-
-	let html = req.html()?;
+	let html = cache_manga_page(&url, &data.user_agent, data.rate_limit.as_ref())?;
 
 	// These are useless badges that are added to the title like "HOT", "NEW", etc.
 	let title_badges = html.select("span.manga-title-badges").text().read();
@@ -367,7 +398,9 @@ pub fn get_manga_details(manga_id: String, data: MadaraSiteData) -> Result<Manga
 
 	let mut categories: Vec<String> = Vec::new();
 	for item in html.select(data.genre_selector.as_str()).array() {
-		categories.push(item.as_node().expect("node array").text().read());
+		if let Ok(node) = item.as_node() {
+			categories.push(node.text().read());
+		}
 	}
 
 	let status = (data.status)(&html);
@@ -389,119 +422,178 @@ pub fn get_manga_details(manga_id: String, data: MadaraSiteData) -> Result<Manga
 	})
 }
 
-pub fn get_chapter_list(manga_id: String, data: MadaraSiteData) -> Result<Vec<Chapter>> {
-	let mut url = data.base_url.clone() + "/wp-admin/admin-ajax.php";
-	if data.alt_ajax {
-		url = data.base_url.clone()
-			+ "/" + data.source_path.as_str()
-			+ "/" + manga_id.as_str()
-			+ "/ajax/chapters";
-	}
-
-	let int_id = (data.get_manga_id)(
-		manga_id,
-		data.base_url.clone(),
-		data.source_path.clone(),
-		data.user_agent.clone(),
-	);
-	let body_content = format!("action=manga_get_chapters&manga={}", int_id);
+/// Where a Madara site serves its chapter list from.
+#[derive(Clone, Copy, PartialEq)]
+enum ChapterEndpoint {
+	/// `admin-ajax.php` with `action=manga_get_chapters`, keyed by the post id.
+	AdminAjax,
+	/// `{manga}/ajax/chapters/`, used by newer versions of the theme.
+	MangaAjax,
+	/// The manga page itself, for sites that render the list inline.
+	MangaPage,
+}
 
-	let mut req = Request::new(url.as_str(), HttpMethod::Post)
-		.body(body_content.as_bytes())
-		.header("Referer", &data.base_url)
-		.header("Content-Type", "application/x-www-form-urlencoded");
+fn chapter_list_page(
+	endpoint: ChapterEndpoint,
+	manga_id: &str,
+	int_id: &mut Option<String>,
+	page: i32,
+	data: &MadaraSiteData,
+) -> Result<Node> {
+	let manga_url = data.base_url.clone() + "/" + data.source_path.as_str() + "/" + manga_id;
+	let req = match endpoint {
+		ChapterEndpoint::AdminAjax => {
+			let int_id = match int_id {
+				Some(int_id) => int_id,
+				None => int_id.insert((data.get_manga_id)(String::from(manga_id), data)?),
+			};
+			let mut body_content = format!("action=manga_get_chapters&manga={}", int_id);
+			if page > 1 {
+				body_content.push_str(&format!("&t={}", page));
+			}
+			let url = data.base_url.clone() + "/wp-admin/admin-ajax.php";
+			Request::new(url.as_str(), HttpMethod::Post)
+				.body(body_content.as_bytes())
+				.header("Content-Type", "application/x-www-form-urlencoded")
+		}
+		ChapterEndpoint::MangaAjax => {
+			let mut url = manga_url.trim_end_matches('/').to_string() + "/ajax/chapters/";
+			if page > 1 {
+				url.push_str(&format!("?t={}", page));
+			}
+			Request::new(url.as_str(), HttpMethod::Post)
+		}
+		ChapterEndpoint::MangaPage => {
+			return cache_manga_page(&manga_url, &data.user_agent, data.rate_limit.as_ref());
+		}
+	};
+	add_user_agent_header(req.header("Referer", &data.base_url), &data.user_agent)
+		.html_throttled(data.rate_limit.as_ref())
+}
 
-	req = add_user_agent_header(req, &data.user_agent);
+fn parse_chapter(obj: &Node, data: &MadaraSiteData) -> Chapter {
+	let id = obj
+		.select("a")
+		.attr("href")
+		.read()
+		.replace(&(data.base_url.clone() + "/"), "")
+		.replace(&(data.source_path.clone() + "/"), "");
+
+	let mut title = String::new();
+	let t_tag = obj.select("a").text().read();
+	if let Some(index) = t_tag.find('-') {
+		title.push_str(t_tag[index + 1..].trim());
+	}
 
-	let html = req.html()?;
+	// The anchor text is the most reliable source ("Vol. 3 Ch. 12.5"), the
+	// slug covers sites that only put a title there
+	// ("volume-10/chapitre-062-5/").
+	let chapter = parse_chapter_number("", &t_tag)
+		.or_else(|| parse_slug_chapter_number(&id))
+		.unwrap_or(-1.0);
+	let volume = parse_volume_number("", &t_tag)
+		.or_else(|| parse_slug_volume_number(&id))
+		.unwrap_or(-1.0);
+
+	let date_str = obj.select("span.chapter-release-date > i").text().read();
+	let mut date_updated = StringRef::from(&date_str)
+		.0
+		.as_date(data.date_format.as_str(), Some("en"), None)
+		.unwrap_or(-1.0);
+	if date_updated < -1.0 {
+		date_updated = StringRef::from(&date_str)
+			.0
+			.as_date("MMM d, yy", Some("en"), None)
+			.unwrap_or(-1.0);
+	}
+	if date_updated == -1.0 {
+		date_updated = current_date();
+	}
 
-	let mut chapters: Vec<Chapter> = Vec::new();
-	for item in html.select(&data.chapter_selector).array() {
-		let obj = item.as_node().expect("node array");
+	let scanlator = if data.scanlator_selector.is_empty() {
+		String::new()
+	} else {
+		String::from(
+			obj.select(&data.scanlator_selector)
+				.first()
+				.text()
+				.read()
+				.trim(),
+		)
+	};
 
-		let id = obj
-			.select("a")
-			.attr("href")
-			.read()
-			.replace(&(data.base_url.clone() + "/"), "")
-			.replace(&(data.source_path.clone() + "/"), "");
+	Chapter {
+		id,
+		title,
+		volume,
+		chapter,
+		date_updated,
+		scanlator,
+		url: obj.select("a").attr("href").read(),
+		lang: data.lang.clone(),
+	}
+}
 
-		let mut title = String::new();
-		let t_tag = obj.select("a").text().read();
-		if t_tag.contains('-') {
-			title.push_str(t_tag[t_tag.find('-').unwrap() + 1..].trim());
-		}
+pub fn get_chapter_list(manga_id: String, data: MadaraSiteData) -> Result<Vec<Chapter>> {
+	// Try the endpoint the site is configured for first, then the other
+	// variants, since themes get updated from under us.
+	let endpoints = if data.alt_ajax {
+		[
+			ChapterEndpoint::MangaAjax,
+			ChapterEndpoint::AdminAjax,
+			ChapterEndpoint::MangaPage,
+		]
+	} else {
+		[
+			ChapterEndpoint::AdminAjax,
+			ChapterEndpoint::MangaAjax,
+			ChapterEndpoint::MangaPage,
+		]
+	};
 
-		/*  Chapter number is first occourance of a number in the last element of url
-			when split with "/"
-			e.g.
-			one-piece-color-jk-english/volume-20-showdown-at-alubarna/chapter-177-30-million-vs-81-million/
-			will return 177
-			parasite-chromatique-french/volume-10/chapitre-062-5/
-			will return 62.5
-		*/
-
-		let slash_vec = id.as_str().split('/').collect::<Vec<&str>>();
-
-		let dash_split = slash_vec[slash_vec.len() - 2].split('-');
-		let dash_vec = dash_split.collect::<Vec<&str>>();
-
-		let mut is_decimal = false;
-		let mut chapter = 0.0;
-		for obj in dash_vec {
-			let mut item = {
-				let mut obj = obj;
-				if obj.contains('_') {
-					obj = obj.split('_').next().unwrap_or(obj);
+	let mut int_id = None;
+	let mut error = None;
+	for endpoint in endpoints {
+		let mut chapters: Vec<Chapter> = Vec::new();
+		let mut seen = BTreeSet::new();
+		let mut page = 1;
+		while page <= data.chapter_max_pages {
+			let html = match chapter_list_page(endpoint, &manga_id, &mut int_id, page, &data) {
+				Ok(html) => html,
+				Err(err) => {
+					error.get_or_insert(err);
+					break;
 				}
-				obj.replace('/', "").parse::<f32>().unwrap_or(-1.0)
 			};
-			if item == -1.0 {
-				item = String::from(obj.chars().next().unwrap())
-					.parse::<f32>()
-					.unwrap_or(-1.0);
-			}
-			if item != -1.0 {
-				if is_decimal {
-					chapter += item / 10.0;
-					break;
-				} else {
-					chapter = item;
-					is_decimal = true;
+			let count = chapters.len();
+			for item in html.select(&data.chapter_selector).array() {
+				let Ok(obj) = item.as_node() else {
+					continue;
+				};
+				let chapter = parse_chapter(&obj, &data);
+				if seen.insert(chapter.id.clone()) {
+					chapters.push(chapter);
 				}
 			}
+			// Paginated lists repeat the last page rather than coming up empty
+			// on some sites, so stop as soon as nothing new turns up.
+			if endpoint == ChapterEndpoint::MangaPage
+				|| chapters.len() == count
+				|| data.chapter_next_selector.is_empty()
+				|| html.select(&data.chapter_next_selector).array().is_empty()
+			{
+				break;
+			}
+			page += 1;
 		}
-
-		let date_str = obj.select("span.chapter-release-date > i").text().read();
-		let mut date_updated = StringRef::from(&date_str)
-			.0
-			.as_date(data.date_format.as_str(), Some("en"), None)
-			.unwrap_or(-1.0);
-		if date_updated < -1.0 {
-			date_updated = StringRef::from(&date_str)
-				.0
-				.as_date("MMM d, yy", Some("en"), None)
-				.unwrap_or(-1.0);
-		}
-		if date_updated == -1.0 {
-			date_updated = current_date();
+		if !chapters.is_empty() {
+			return Ok(chapters);
 		}
-
-		let url = obj.select("a").attr("href").read();
-		let lang = data.lang.clone();
-
-		chapters.push(Chapter {
-			id,
-			title,
-			volume: -1.0,
-			chapter,
-			date_updated,
-			scanlator: String::new(),
-			url,
-			lang,
-		});
 	}
-	Ok(chapters)
+	match error {
+		Some(err) => Err(err),
+		None => Ok(Vec::new()),
+	}
 }
 
 pub fn get_page_list(chapter_id: String, data: MadaraSiteData) -> Result<Vec<Page>> {
@@ -510,20 +602,35 @@ pub fn get_page_list(chapter_id: String, data: MadaraSiteData) -> Result<Vec<Pag
 
 	req = add_user_agent_header(req, &data.user_agent);
 
-	let html = req.html()?;
+	let html = req.html_throttled(data.rate_limit.as_ref())?;
 
 	let mut pages: Vec<Page> = Vec::new();
 	for (index, item) in html
 		.select(data.image_selector.as_str())
 		.array()
+		.filter_map(|item| item.as_node().ok())
 		.enumerate()
 	{
 		pages.push(Page {
 			index: index as i32,
-			url: get_image_url(item.as_node().expect("node array")),
+			url: get_image_url(item),
 			..Default::default()
 		});
 	}
+
+	// Novel chapters have no images, only a text body
+	if pages.is_empty() {
+		let text = select_first_of(&html, &data.text_selector)
+			.map(|body| get_chapter_text(&body))
+			.unwrap_or_default();
+		if !text.is_empty() {
+			pages.push(Page {
+				index: 0,
+				text,
+				..Default::default()
+			});
+		}
+	}
 	Ok(pages)
 }
 
@@ -532,13 +639,50 @@ pub fn modify_image_request(base_url: String, request: Request) {
 }
 
 pub fn handle_url(url: String, data: MadaraSiteData) -> Result<DeepLink> {
-	let mut manga_id = String::new();
-	let parse_url = url.as_str().split('/').collect::<Vec<&str>>();
-	if parse_url.len() >= 4 {
-		manga_id.push_str(parse_url[4]);
-	}
+	// www.example.com/{source_path}/manga-id/[volume-id/]chapter-id/
+	let prefix = format!("{}/{}/", data.base_url, data.source_path);
+	let path = match url.strip_prefix(prefix.as_str()) {
+		Some(path) => path,
+		None => url.splitn(5, '/').nth(4).unwrap_or_default(),
+	};
+	let segments = path
+		.split(['?', '#'])
+		.next()
+		.unwrap_or_default()
+		.split('/')
+		.filter(|segment| !segment.is_empty())
+		.collect::<Vec<&str>>();
+	let Some(manga_id) = segments.first().map(|id| String::from(*id)) else {
+		return Err(AidokuError {
+			reason: AidokuErrorKind::Unimplemented,
+		});
+	};
+
+	let manga = get_manga_details(manga_id.clone(), data.clone())?;
+	let chapter = if segments.len() > 1 {
+		let id = segments.join("/");
+		let chapter = get_chapter_list(manga_id, data.clone())
+			.ok()
+			.and_then(|chapters| {
+				chapters
+					.into_iter()
+					.find(|chapter| chapter.id.trim_end_matches('/') == id)
+			})
+			.unwrap_or_else(|| Chapter {
+				chapter: parse_slug_chapter_number(&id).unwrap_or(-1.0),
+				volume: parse_slug_volume_number(&id).unwrap_or(-1.0),
+				id: id + "/",
+				url,
+				lang: data.lang.clone(),
+				..Default::default()
+			});
+		Some(chapter)
+	} else {
+		None
+	};
+
 	Ok(DeepLink {
-		manga: Some(get_manga_details(manga_id, data)?),
-		chapter: None,
+		manga: Some(manga),
+		chapter,
 	})
 }
@@ -82,7 +82,7 @@ fn get_page_list(_manga_id: String, id: String) -> Result<Vec<Page>> {
 	let images = parsed_json.get("images").as_array()?;
 
 	for (index, page) in images.enumerate() {
-		let url = mangastream_template::helper::urlencode(page.as_string()?.read());
+		let url = mangastream_template::helper::encode_uri(page.as_string()?.read());
 		pages.push(Page {
 			index: index as i32,
 			url,
@@ -4,7 +4,7 @@ use aidoku::{
 	Listing, Manga, MangaPageResult, Page,
 };
 
-use mangastream_template::template::MangaStreamSource;
+use mangastream_template::template::{MangaStreamSource, RateLimit};
 
 fn get_instance() -> MangaStreamSource {
 	MangaStreamSource {
@@ -13,6 +13,7 @@ fn get_instance() -> MangaStreamSource {
 		base_url: String::from("https://seitacelestial.com"),
         traverse_pathname: "comics",
         alt_pages: true,
+		rate_limit: Some(RateLimit::new("site", 2, 1.0)),
 		..Default::default()
 	}
 }
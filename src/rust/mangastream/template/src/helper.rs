@@ -2,18 +2,22 @@ use aidoku::{
 	error::{AidokuError, AidokuErrorKind, Result},
 	helpers::substring::Substring,
 	prelude::format,
-	std::{current_date, html::Node},
+	std::{current_date, html::Node, json::parse},
 	std::{defaults::defaults_get, net::Request},
 	std::{String, StringRef, Vec},
 	MangaStatus,
 };
 use core::ptr;
 
-use crate::template::{MangaStreamSource, USER_AGENT};
+use crate::template::{MangaStreamSource, RateLimit, USER_AGENT};
+use source_net::ThrottledRequest;
 
 extern crate hashbrown;
 use hashbrown::HashMap;
 
+use source_support::LINE_BREAK_MARKER;
+pub use source_support::{append_protocol, encode_uri, img_url_encode};
+
 // generate url for listing page
 pub fn get_listing_url(
 	listing: [&str; 3],
@@ -66,55 +70,12 @@ pub fn manga_status(
 	}
 }
 
-//converts integer(i32) to string
-pub fn i32_to_string(mut integer: i32) -> String {
-	if integer == 0 {
-		return String::from("0");
-	}
-	let mut string = String::with_capacity(11);
-	let pos = if integer < 0 {
-		string.insert(0, '-');
-		1
-	} else {
-		0
-	};
-	while integer != 0 {
-		let mut digit = integer % 10;
-		if pos == 1 {
-			digit *= -1;
-		}
-		string.insert(pos, char::from_u32((digit as u32) + ('0' as u32)).unwrap());
-		integer /= 10;
-	}
-	string
-}
-
 /// Converts `<br>` and `\n` into newlines.
 pub fn text_with_newlines(node: Node) -> String {
-	let html = node.html().read();
-	if !String::from(html.trim()).is_empty() {
-		String::from(
-			Node::new_fragment(
-				node.html()
-				.read()
-				// This also replaces `\n` because mangastream sources split their
-				// description text into multiple p tags, and this causes newlines
-				// to be lost if you call `text()` on the node.
-				// So to fix that we replace all newlines with a placeholder, and
-				// then replace the placeholder with a newline after calling `text()`.
-				.replace('\n', "{{ .LINEBREAK }}")
-				.replace("<br>", "{{ .LINEBREAK }}")
-				.as_bytes(),
-			)
-			.expect("Failed to create new fragment")
-			.text()
-			.read()
-			.replace("{{ .LINEBREAK }}", "\n")
-			.trim(),
-		)
-	} else {
-		String::new()
-	}
+	// This also replaces `\n` because mangastream sources split their
+	// description text into multiple p tags, and this causes newlines
+	// to be lost if you call `text()` on the node.
+	source_aidoku::html_text_with_newlines(node.html().read().replace('\n', LINE_BREAK_MARKER))
 }
 
 // return chapter number from string
@@ -196,39 +157,6 @@ pub fn get_date(source: &MangaStreamSource, raw_date: StringRef) -> f64 {
 	}
 }
 
-// encoding non alpha-numeric characters to utf8
-pub fn img_url_encode(string: String) -> String {
-	let mut result: Vec<u8> = Vec::with_capacity(string.len() * 3);
-	let hex = "0123456789abcdef".as_bytes();
-	let bytes = string.as_bytes();
-
-	for byte in bytes {
-		let curr = *byte;
-		if curr == b'-' {
-			result.push(b'-');
-		} else if curr == b'.' {
-			result.push(b'.');
-		} else if curr == b'_' {
-			result.push(b'_');
-		} else if curr.is_ascii_lowercase() || curr.is_ascii_uppercase() || curr.is_ascii_digit() {
-			result.push(curr);
-		} else {
-			result.push(b'%');
-			if hex[curr as usize >> 4] >= 97 && hex[curr as usize >> 4] <= 122 {
-				result.push(hex[curr as usize >> 4] - 32);
-			} else {
-				result.push(hex[curr as usize >> 4]);
-			}
-			if hex[curr as usize & 15] >= 97 && hex[curr as usize & 15] <= 122 {
-				result.push(hex[curr as usize & 15] - 32);
-			} else {
-				result.push(hex[curr as usize & 15]);
-			}
-		}
-	}
-	String::from_utf8(result).unwrap_or_default()
-}
-
 //get the image sources as some images are in base64 format
 pub fn get_image_src(node: Node) -> String {
 	let mut image = String::new();
@@ -269,33 +197,6 @@ pub fn get_image_src(node: Node) -> String {
 	append_protocol(encoded_img)
 }
 
-pub fn append_protocol(url: String) -> String {
-	if url.starts_with("https") || url.starts_with("http") {
-		url
-	} else {
-		format!("{}{}", "https:", url)
-	}
-}
-
-pub fn urlencode<T: AsRef<[u8]>>(url: T) -> String {
-	let bytes = url.as_ref();
-	let hex = "0123456789ABCDEF".as_bytes();
-
-	let mut result: Vec<u8> = Vec::with_capacity(bytes.len() * 3);
-
-	for byte in bytes {
-		let curr = *byte;
-		if curr.is_ascii_alphanumeric() || b";,/?:@&=+$-_.!~*'()#".contains(&curr) {
-			result.push(curr);
-		} else {
-			result.push(b'%');
-			result.push(hex[curr as usize >> 4]);
-			result.push(hex[curr as usize & 15]);
-		}
-	}
-	String::from_utf8(result).unwrap_or_default()
-}
-
 /// This function is used to get the permanent url of a manga or chapter
 ///
 /// This is done by removing the random number near the end of the url
@@ -346,13 +247,8 @@ pub fn get_id_from_url(url: String) -> String {
 	};
 
 	// if there is a post id in the url, return it
-	if url.contains("p=") {
-		return String::from(
-			url.substring_after("p=")
-				.expect("Failed to parse id from url")
-				.substring_before("&")
-				.expect("Failed to parse id from url"),
-		);
+	if let Some(rest) = url.substring_after("p=") {
+		return String::from(rest.substring_before("&").unwrap_or(rest));
 	}
 
 	// this will get the last part of the url
@@ -360,10 +256,7 @@ pub fn get_id_from_url(url: String) -> String {
 	// will return the-world-after-the-fall
 	// example https://flamescans.org/the-world-after-the-fall-chapter-55
 	// will return the-world-after-the-fall-chapter-55
-	let id = url
-		.split('/')
-		.next_back()
-		.expect("Failed to parse id from url");
+	let id = url.split('/').next_back().unwrap_or_default();
 
 	String::from(id)
 }
@@ -379,6 +272,103 @@ pub fn get_lang_code() -> String {
 	String::new()
 }
 
+/// One of the mirrors listed in a chapter's `ts_reader.run({...})` payload.
+pub struct ReaderServer {
+	pub name: String,
+	pub images: Vec<String>,
+}
+
+/// The JSON object starting at `start`, found by matching braces outside of
+/// strings.
+fn json_object_at(text: &str, start: usize) -> Option<&str> {
+	let mut depth = 0;
+	let mut in_string = false;
+	let mut escaped = false;
+	for (i, c) in text[start..].char_indices() {
+		if in_string {
+			match c {
+				_ if escaped => escaped = false,
+				'\\' => escaped = true,
+				'"' => in_string = false,
+				_ => {}
+			}
+			continue;
+		}
+		match c {
+			'"' => in_string = true,
+			'{' => depth += 1,
+			'}' => {
+				depth -= 1;
+				if depth == 0 {
+					return Some(&text[start..start + i + 1]);
+				}
+			}
+			_ => {}
+		}
+	}
+	None
+}
+
+/// Parses the servers out of the `ts_reader.run` call in a chapter page's
+/// scripts.
+pub fn parse_ts_reader(scripts: &str) -> Result<Vec<ReaderServer>> {
+	let payload = scripts
+		.find("ts_reader.run(")
+		.and_then(|call| scripts[call..].find('{').map(|i| call + i))
+		.and_then(|start| json_object_at(scripts, start))
+		.ok_or(AidokuError {
+			reason: AidokuErrorKind::JsonParseError,
+		})?;
+	let json = parse(payload.as_bytes())?.as_object()?;
+
+	let mut servers = Vec::new();
+	for source in json.get("sources").as_array()? {
+		let source = source.as_object()?;
+		let name = source
+			.get("source")
+			.as_string()
+			.map(|name| name.read())
+			.unwrap_or_default();
+		let images = source
+			.get("images")
+			.as_array()?
+			.filter_map(|image| image.as_string().ok())
+			.map(|image| encode_uri(image.read()))
+			.collect();
+		servers.push(ReaderServer { name, images });
+	}
+	Ok(servers)
+}
+
+/// The name of the server picked in settings, empty for the site's own order.
+pub fn get_preferred_server() -> String {
+	defaults_get("preferredServer")
+		.and_then(|value| value.as_string())
+		.map(|value| value.read())
+		.unwrap_or_default()
+}
+
+/// `servers` with the one named `preferred` first and the rest kept in site
+/// order as fallbacks. Names are matched ignoring case and surrounding space.
+/// Servers without images are dropped.
+pub fn order_servers(servers: Vec<ReaderServer>, preferred: &str) -> Vec<ReaderServer> {
+	let preferred = preferred.trim();
+	let mut ordered = Vec::with_capacity(servers.len());
+	let mut rest = Vec::with_capacity(servers.len());
+	for server in servers {
+		if server.images.is_empty() {
+			continue;
+		}
+		if !preferred.is_empty() && server.name.trim().eq_ignore_ascii_case(preferred) {
+			ordered.push(server);
+		} else {
+			rest.push(server);
+		}
+	}
+	ordered.append(&mut rest);
+	ordered
+}
+
 static mut CACHED_MANGA_URL_TO_POSTID_MAPPING: Option<HashMap<String, String>> = None;
 static mut CACHED_MAPPING_AT: f64 = 0.0;
 
@@ -394,6 +384,7 @@ static mut CACHED_MAPPING_AT: f64 = 0.0;
 fn generate_manga_url_to_postid_mapping(
 	url: &str,
 	pathname: &str,
+	rate_limit: Option<&RateLimit>,
 ) -> Result<HashMap<String, String>> {
 	unsafe {
 		// if the mapping was generated less than 10 minutes ago, use the cached mapping
@@ -408,7 +399,7 @@ fn generate_manga_url_to_postid_mapping(
 
 	let html = Request::get(all_manga_listing_url)
 		.header("User-Agent", USER_AGENT)
-		.html()?;
+		.html_throttled(rate_limit)?;
 	let mut mapping = HashMap::new();
 
 	for node in html.select(".soralist .series").array() {
@@ -429,8 +420,14 @@ fn generate_manga_url_to_postid_mapping(
 }
 
 /// Search the `MANGA_URL_TO_POSTID_MAPPING` for the postid from a manga url
-pub fn get_postid_from_manga_url(url: String, base_url: &str, pathname: &str) -> Result<String> {
-	let manga_url_to_postid_mapping = generate_manga_url_to_postid_mapping(base_url, pathname)?;
+pub fn get_postid_from_manga_url(
+	url: String,
+	base_url: &str,
+	pathname: &str,
+	rate_limit: Option<&RateLimit>,
+) -> Result<String> {
+	let manga_url_to_postid_mapping =
+		generate_manga_url_to_postid_mapping(base_url, pathname, rate_limit)?;
 	let id = manga_url_to_postid_mapping.get(&url).ok_or(AidokuError {
 		reason: AidokuErrorKind::Unimplemented, // no better error type available
 	})?;
@@ -438,6 +435,17 @@ pub fn get_postid_from_manga_url(url: String, base_url: &str, pathname: &str) ->
 	Ok(String::from(id))
 }
 
+// The ajax endpoint rate limits even when the rest of the site doesn't, and
+// 10 seconds is the shortest pause after which it doesn't rate limit us again,
+// so a rate limited request is re-sent once after waiting that long. If that
+// fails too, the chapter list fails rather than losing its post ids.
+const CHAPTER_AJAX_RATE_LIMIT: RateLimit = RateLimit {
+	backoff: 10.0,
+	retries: 1,
+	max_wait: 11.0,
+	..RateLimit::new("chapter-ajax", 1, 1.0)
+};
+
 // This requests the chapters via the admin ajax endpoint using post ids and
 // parses out the postid and url for each chapter, and returns it in a hashmap
 //
@@ -448,25 +456,12 @@ pub fn generate_chapter_url_to_postid_mapping(
 ) -> Result<HashMap<String, String>> {
 	let ajax_url = format!("{}/wp-admin/admin-ajax.php", base_url);
 
-	let start = current_date();
-
 	let body = format!("action=get_chapters&id={}", post_id);
 	let html = Request::post(ajax_url)
 		.body(body.as_bytes())
 		.header("Referer", base_url)
 		.header("User-Agent", USER_AGENT)
-		.html()?;
-
-	// Janky retry logic to bypass rate limiting
-	// Retry after 10 seconds if we get rate limited. 10 seconds is the shortest
-	// interval that we can retry without getting rate limited again.
-	if html.select("title").text().read() == "429 Too Many Requests" {
-		loop {
-			if start + 10.0 < current_date() {
-				return generate_chapter_url_to_postid_mapping(post_id, base_url);
-			}
-		}
-	}
+		.html_throttled(Some(&CHAPTER_AJAX_RATE_LIMIT))?;
 
 	let mut mapping = HashMap::new();
 
@@ -1,7 +1,6 @@
 use aidoku::{
 	error::{AidokuError, AidokuErrorKind, Result},
 	prelude::format,
-	std::json::parse,
 	std::net::{HttpMethod, Request},
 	std::{String, Vec},
 	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaViewer,
@@ -9,6 +8,9 @@ use aidoku::{
 };
 
 use crate::helper::*;
+use source_net::ThrottledRequest;
+
+pub use source_net::RateLimit;
 
 pub const USER_AGENT: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) GSA/300.0.598994205 Mobile/15E148 Safari/604";
 
@@ -65,6 +67,8 @@ pub struct MangaStreamSource {
 	pub page_selector: &'static str,
 	pub page_url: &'static str,
 	pub protocol: bool,
+
+	pub rate_limit: Option<RateLimit>,
 }
 impl Default for MangaStreamSource {
 	fn default() -> Self {
@@ -118,7 +122,10 @@ impl Default for MangaStreamSource {
 			alt_pages: false,
 			page_selector: "#readerarea img",
 			page_url: "src",
-			protocol: false
+			protocol: false,
+
+			// throttle requests and back off after 429/503 responses, e.g. `Some(RateLimit::new("site", 2, 1.0))`
+			rate_limit: None,
 		}
 	}
 }
@@ -197,7 +204,7 @@ impl MangaStreamSource {
 		let mut mangas: Vec<Manga> = Vec::new();
 		let html = Request::new(url, HttpMethod::Get)
 			.header("User-Agent", USER_AGENT)
-			.html()?;
+			.html_throttled(self.rate_limit.as_ref())?;
 		for manga in html.select(self.manga_selector).array() {
 			let manga_node = manga.as_node().expect("Failed to get manga as node");
 			let title = manga_node.select(self.manga_title).attr("title").read();
@@ -218,6 +225,7 @@ impl MangaStreamSource {
 					original_url,
 					&self.base_url,
 					self.traverse_pathname,
+					self.rate_limit.as_ref(),
 				)?;
 				url = format!("{}/{}/?p={}", self.base_url, self.traverse_pathname, id);
 			} else {
@@ -266,7 +274,7 @@ impl MangaStreamSource {
 		};
 		let html = Request::new(&url, HttpMethod::Get)
 			.header("User-Agent", USER_AGENT)
-			.html()?;
+			.html_throttled(self.rate_limit.as_ref())?;
 		let mut title = html.select(self.manga_details_title).text().read();
 		for i in self.manga_title_trim.iter() {
 			if title.contains(i) {
@@ -360,7 +368,7 @@ impl MangaStreamSource {
 		let mut chapters: Vec<Chapter> = Vec::new();
 		let html = Request::new(url, HttpMethod::Get)
 			.header("User-Agent", USER_AGENT)
-			.html()?;
+			.html_throttled(self.rate_limit.as_ref())?;
 		for chapter in html.select(self.chapter_selector).array() {
 			let chapter_node = chapter.as_node().expect("Failed to get chapter as node");
 			let raw_title = chapter_node.select(self.chapter_title).text().read();
@@ -440,33 +448,32 @@ impl MangaStreamSource {
 		let html = Request::new(url, HttpMethod::Get)
 			.header("Referer", &self.base_url)
 			.header("User-Agent", USER_AGENT)
-			.html()?;
+			.html_throttled(self.rate_limit.as_ref())?;
 		if self.alt_pages {
-			let raw_text = html.select("script").html().read();
-
-			let trimmed_json = {
-				let mut trimmed_json = &raw_text[raw_text.find(r#":[{"s"#).unwrap_or(0) + 2
-					..raw_text
-						.rfind("}]});")
-						.unwrap_or(raw_text.rfind("}],").unwrap_or(0))
-						+ 1];
-
-				if trimmed_json.contains("Default 2") {
-					trimmed_json = &trimmed_json[..trimmed_json.rfind(r#",{"s"#).unwrap_or(0)];
-				}
-
-				// if "post_id" is present, we've gone too far
-				if trimmed_json.contains("post_id") {
-					trimmed_json = &trimmed_json[..trimmed_json.find("}],").unwrap_or(0) + 1];
-				}
-
-				trimmed_json
-			};
+			let scripts = html.select("script").html().read();
+			let servers = order_servers(parse_ts_reader(&scripts)?, &get_preferred_server());
+
+			// The reader switches mirrors client side when images fail to load;
+			// here the first image of each server is checked instead, and the
+			// last one is used regardless.
+			let last = servers.len().saturating_sub(1);
+			let server = servers.into_iter().enumerate().find_map(|(index, server)| {
+				let reachable = index == last || {
+					let status = Request::new(&server.images[0], HttpMethod::Head)
+						.header("Referer", &self.base_url)
+						.header("User-Agent", USER_AGENT)
+						.status_throttled(self.rate_limit.as_ref());
+					status.is_ok_and(|status| (200..400).contains(&status) || status == 405)
+				};
+				reachable.then_some(server)
+			});
 
-			let json = parse(trimmed_json.as_bytes())?.as_object()?;
-			let images = json.get("images").as_array()?;
-			for (index, page) in images.enumerate() {
-				let page_url = urlencode(page.as_string()?.read());
+			for (index, page_url) in server
+				.map(|s| s.images)
+				.unwrap_or_default()
+				.into_iter()
+				.enumerate()
+			{
 				pages.push(Page {
 					index: index as i32,
 					url: page_url,
@@ -478,9 +485,9 @@ impl MangaStreamSource {
 			for (at, page) in html.select(self.page_selector).array().enumerate() {
 				let page_node = page.as_node().expect("Failed to get page as node");
 				let page_url = if self.protocol {
-					format!("https:{}", urlencode(page_node.attr(self.page_url).read()))
+					format!("https:{}", encode_uri(page_node.attr(self.page_url).read()))
 				} else {
-					urlencode(page_node.attr(self.page_url).read())
+					encode_uri(page_node.attr(self.page_url).read())
 				};
 				// avoid svgs
 				if page_url.starts_with("data") {
@@ -507,10 +514,75 @@ impl MangaStreamSource {
 	}
 
 	pub fn handle_url(&self, url: String) -> Result<DeepLink> {
-		let id = get_id_from_url(url);
+		if url.contains(&format!("/{}/", self.traverse_pathname)) {
+			let id = get_id_from_url(url);
+			return Ok(DeepLink {
+				manga: Self::parse_manga_details(self, id).ok(),
+				chapter: None,
+			});
+		}
+
+		// anything else is a chapter, whose page links back to the series in its
+		// breadcrumb (or the "all chapters are in" box on older themes)
+		let html = Request::new(&url, HttpMethod::Get)
+			.header("User-Agent", USER_AGENT)
+			.html_throttled(self.rate_limit.as_ref())?;
+		let series_url = html
+			.select(".ts-breadcrumb li:nth-child(2) a, div.allc a")
+			.first()
+			.attr("href")
+			.read();
+		if series_url.is_empty() {
+			return Err(AidokuError {
+				reason: AidokuErrorKind::Unimplemented, // no better error type available
+			});
+		}
+
+		let manga_id = if self.use_manga_postids {
+			get_postid_from_manga_url(
+				series_url,
+				&self.base_url,
+				self.traverse_pathname,
+				self.rate_limit.as_ref(),
+			)?
+		} else if self.has_permanent_manga_url {
+			get_id_from_url(get_permanet_url(series_url))
+		} else {
+			get_id_from_url(series_url)
+		};
+
+		let chapter_id = if self.use_chapter_postids && !url.contains("p=") {
+			// the shortlink is the only place the post id shows up on the page
+			let shortlink = html.select("link[rel=shortlink]").attr("href").read();
+			if !shortlink.contains("p=") {
+				return Err(AidokuError {
+					reason: AidokuErrorKind::Unimplemented, // no better error type available
+				});
+			}
+			get_id_from_url(shortlink)
+		} else if self.has_permanent_chapter_url {
+			get_id_from_url(get_permanet_url(url.clone()))
+		} else {
+			// also covers `?p=` links and the random prefix, which only sits in
+			// front of the last path segment
+			get_id_from_url(url.clone())
+		};
+
+		let manga = self.parse_manga_details(manga_id.clone())?;
+		let chapter = self
+			.parse_chapter_list(manga_id)
+			.ok()
+			.and_then(|chapters| chapters.into_iter().find(|c| c.id == chapter_id))
+			.unwrap_or_else(|| Chapter {
+				id: chapter_id,
+				url,
+				lang: String::from(self.language),
+				..Default::default()
+			});
+
 		Ok(DeepLink {
-			manga: Self::parse_manga_details(self, id).ok(),
-			chapter: None,
+			manga: Some(manga),
+			chapter: Some(chapter),
 		})
 	}
 }
@@ -6,7 +6,7 @@ use aidoku::{
 	Chapter, DeepLink, Filter, Manga, MangaContentRating, MangaPageResult, Page,
 };
 use lazy_static::lazy_static;
-use mmrcms_template::template::{cache_manga_page, MMRCMSSource, CACHED_MANGA};
+use mmrcms_template::template::{cache_manga_page, MMRCMSSource};
 
 lazy_static! {
 	static ref INSTANCE: MMRCMSSource<'static> = MMRCMSSource {
@@ -36,8 +36,7 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 #[get_manga_details]
 fn get_manga_details(id: String) -> Result<Manga> {
 	let url = format!("{}/{}/{}", INSTANCE.base_url, INSTANCE.manga_path, id);
-	cache_manga_page(&url);
-	let html = unsafe { CACHED_MANGA.clone().unwrap() };
+	let html = cache_manga_page(&url, INSTANCE.rate_limit.as_ref())?;
 
 	let title = html
 		.select("h1.widget-title")
@@ -126,6 +125,11 @@ fn modify_image_request(request: Request) {
 	INSTANCE.modify_image_request(request)
 }
 
+#[handle_notification]
+fn handle_notification(notification: String) {
+	INSTANCE.handle_notification(notification)
+}
+
 #[handle_url]
 fn handle_url(url: String) -> Result<DeepLink> {
 	INSTANCE.handle_url(url)
@@ -66,6 +66,11 @@ fn modify_image_request(request: Request) {
 	INSTANCE.modify_image_request(request)
 }
 
+#[handle_notification]
+fn handle_notification(notification: String) {
+	INSTANCE.handle_notification(notification)
+}
+
 #[handle_url]
 fn handle_url(url: String) -> Result<DeepLink> {
 	INSTANCE.handle_url(url)
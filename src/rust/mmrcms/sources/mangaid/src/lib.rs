@@ -51,6 +51,11 @@ fn get_page_list(manga_id: String, id: String) -> Result<Vec<Page>> {
 	INSTANCE.get_page_list(manga_id, format!("{id}{cdn}"))
 }
 
+#[handle_notification]
+fn handle_notification(notification: String) {
+	INSTANCE.handle_notification(notification)
+}
+
 #[handle_url]
 fn handle_url(url: String) -> Result<DeepLink> {
 	INSTANCE.handle_url(url)
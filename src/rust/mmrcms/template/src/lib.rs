@@ -1,6 +1,7 @@
 #![no_std]
 #![feature(stmt_expr_attributes)]
 pub mod helper;
+pub mod state;
 pub mod template;
 
 #[macro_export]
@@ -43,6 +44,11 @@ macro_rules! mmrcms {
 			INSTANCE.modify_image_request(request)
 		}
 
+		#[handle_notification]
+		fn handle_notification(notification: String) {
+			INSTANCE.handle_notification(notification)
+		}
+
 		#[handle_url]
 		fn handle_url(url: String) -> Result<DeepLink> {
 			INSTANCE.handle_url(url)
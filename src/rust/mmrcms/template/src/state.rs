@@ -0,0 +1,88 @@
+//! State that outlives a single call into the source: whether the site's
+//! search engine can be trusted, kept in defaults so it survives restarts.
+use aidoku::std::{
+	current_date,
+	defaults::{defaults_get, defaults_set},
+};
+
+/// How long a failed search engine is skipped before it's tried again.
+const SEARCH_ENGINE_RETRY: f64 = 6.0 * 60.0 * 60.0;
+const SEARCH_ENGINE_KEY: &str = "searchEngine.disabledUntil";
+/// The "Searching the manga list" switch in settings, which shows whether the
+/// search engine is being skipped and lets the user stop skipping it.
+pub const FALLBACK_KEY: &str = "searchEngineFallback";
+
+/// Which search the user asked for in the "searchMode" setting.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+	/// Use the search engine, falling back to the manga list when it fails.
+	Auto,
+	/// Always use the search engine, and report its errors.
+	SearchEngine,
+	/// Always search the manga list.
+	MangaList,
+}
+
+impl SearchMode {
+	pub fn get() -> Self {
+		match defaults_get("searchMode")
+			.and_then(|value| value.as_string())
+			.map(|value| value.read())
+			.as_deref()
+		{
+			Ok("engine") => Self::SearchEngine,
+			Ok("list") => Self::MangaList,
+			_ => Self::Auto,
+		}
+	}
+}
+
+/// Whether the search engine failed recently, persisted in defaults.
+pub struct SearchEngineState {
+	/// When the search engine may be tried again, or 0 if it's working.
+	pub disabled_until: f64,
+}
+
+impl SearchEngineState {
+	pub fn load() -> Self {
+		let mut state = Self {
+			disabled_until: defaults_get(SEARCH_ENGINE_KEY)
+				.and_then(|value| value.as_float())
+				.unwrap_or(0.0),
+		};
+		let switched_on = defaults_get(FALLBACK_KEY)
+			.and_then(|value| value.as_bool())
+			.unwrap_or(false);
+		// Turning the switch off in settings, or the pause running out, ends
+		// the fallback.
+		if !switched_on || current_date() >= state.disabled_until {
+			state.record_success();
+		}
+		state
+	}
+
+	pub fn is_usable(&self) -> bool {
+		current_date() >= self.disabled_until
+	}
+
+	/// Skips the search engine for a while. Searches fall back to the manga
+	/// list until then, and the first search afterwards tries it again.
+	pub fn record_failure(&mut self) {
+		self.disabled_until = current_date() + SEARCH_ENGINE_RETRY;
+		defaults_set(SEARCH_ENGINE_KEY, self.disabled_until.into());
+		defaults_set(FALLBACK_KEY, true.into());
+	}
+
+	pub fn record_success(&mut self) {
+		if self.disabled_until != 0.0 {
+			self.disabled_until = 0.0;
+			defaults_set(SEARCH_ENGINE_KEY, 0.0.into());
+		}
+		if defaults_get(FALLBACK_KEY)
+			.and_then(|value| value.as_bool())
+			.unwrap_or(true)
+		{
+			defaults_set(FALLBACK_KEY, false.into());
+		}
+	}
+}
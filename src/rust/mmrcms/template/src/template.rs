@@ -1,4 +1,3 @@
-#![allow(static_mut_refs)]
 use aidoku::{
 	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
@@ -13,31 +12,34 @@ use aidoku::{
 	},
 };
 
-use crate::helper::{append_protocol, extract_f32_from_string};
-
-pub static mut CACHED_MANGA: Option<Node> = None;
-static mut CACHED_MANGA_ID: Option<String> = None;
+use crate::{
+	helper::{append_protocol, extract_f32_from_string},
+	state::{FALLBACK_KEY, SearchEngineState, SearchMode},
+};
+use source_net::{ThrottledRequest, cached_page};
 
-/// Internal attribute to control if the source should fall
-/// back to self searching after failing to use the search
-/// engine first time.
-///
-/// Strikes a balance between control and reliability (and also
-/// not spamming sources with useless requests)
-static mut INTERNAL_USE_SEARCH_ENGINE: bool = true;
+pub use source_net::RateLimit;
 
-pub fn cache_manga_page(url: &str) {
-	unsafe {
-		if CACHED_MANGA.is_some() && CACHED_MANGA_ID.clone().unwrap_or_default() == url {
-			return;
-		}
+/// Whether `html` is a manga page rather than e.g. a Cloudflare challenge or
+/// an error page.
+fn is_manga_page(html: &Node) -> bool {
+	!html
+		.select("h2.widget-title, h1.widget-title, .listmanga-header, div.panel-heading")
+		.array()
+		.is_empty()
+}
 
-		if let Ok(html) = Request::new(url, HttpMethod::Get).html() {
-			decode_cfemail(&html);
-			CACHED_MANGA = Some(html);
-			CACHED_MANGA_ID = Some(String::from(url));
-		}
-	}
+/// Fetches the manga page at `url`, reusing it for a few minutes so details
+/// and chapters don't download it twice.
+pub fn cache_manga_page(url: &str, rate_limit: Option<&RateLimit>) -> Result<Node> {
+	let html = cached_page(
+		url,
+		|| Request::new(url, HttpMethod::Get),
+		rate_limit,
+		is_manga_page,
+	)?;
+	decode_cfemail(&html);
+	Ok(html)
 }
 
 pub struct MMRCMSSource<'a> {
@@ -55,6 +57,9 @@ pub struct MMRCMSSource<'a> {
 	pub tags_mapper: fn(i64) -> String,
 
 	pub use_search_engine: bool,
+
+	/// Throttle requests and back off after 429/503 responses.
+	pub rate_limit: Option<RateLimit>,
 }
 
 #[derive(Default)]
@@ -113,6 +118,7 @@ impl<'a> Default for MMRCMSSource<'a> {
 			}, // 0 is reserved for None
 			tags_mapper: |_| String::new(),
 			use_search_engine: true,
+			rate_limit: None,
 		}
 	}
 }
@@ -129,13 +135,40 @@ impl<'a> MMRCMSSource<'a> {
 		}
 	}
 
+	/// Searches with the site's `/search` endpoint, which only matches titles.
+	fn engine_search(&self, title: &str) -> Result<MangaPageResult> {
+		let url = format!("{}/search?query={}", self.base_url, title);
+		let json = Request::new(&url, HttpMethod::Get)
+			.json_throttled(self.rate_limit.as_ref())?
+			.as_object()?;
+		let suggestions = json.get("suggestions").as_array()?;
+		let mut manga = Vec::with_capacity(suggestions.len());
+		for suggestion in suggestions {
+			if let Ok(suggestion) = suggestion.as_object()
+				&& let Ok(obj) = MMRCMSSearchResult::try_from(suggestion)
+			{
+				manga.push(Manga {
+					cover: self.guess_cover("", &obj.data),
+					url: format!("{}/{}/{}", self.base_url, self.manga_path, obj.data),
+					id: obj.data,
+					title: obj.value,
+					..Default::default()
+				});
+			}
+		}
+		Ok(MangaPageResult {
+			manga,
+			has_more: false,
+		})
+	}
+
 	fn self_search<T: AsRef<str>>(&self, query: T) -> Result<MangaPageResult> {
 		let query = query.as_ref();
 		let html = Request::new(
 			format!("{}/changeMangaList?type=text", self.base_url),
 			HttpMethod::Get,
 		)
-		.html()?;
+		.html_throttled(self.rate_limit.as_ref())?;
 		decode_cfemail(&html);
 		let manga = html
 			.select("ul.manga-list a")
@@ -225,36 +258,32 @@ impl<'a> MMRCMSSource<'a> {
 			}
 		}
 		if !title.is_empty() {
-			if self.use_search_engine && unsafe { INTERNAL_USE_SEARCH_ENGINE } {
-				let url = format!("{}/search?query={}", self.base_url, title);
-				if let Ok(obj) = Request::new(&url, HttpMethod::Get).json()
-					&& let Ok(json) = obj.as_object()
-					&& let Ok(suggestions) = json.get("suggestions").as_array()
-				{
-					let mut manga = Vec::with_capacity(suggestions.len());
-					for suggestion in suggestions {
-						if let Ok(suggestion) = suggestion.as_object()
-							&& let Ok(obj) = MMRCMSSearchResult::try_from(suggestion)
-						{
-							manga.push(Manga {
-								cover: self.guess_cover("", &obj.data),
-								url: format!("{}/{}/{}", self.base_url, self.manga_path, obj.data),
-								id: obj.data,
-								title: obj.value,
-								..Default::default()
-							});
+			if !self.use_search_engine {
+				return self.self_search(title);
+			}
+			match SearchMode::get() {
+				SearchMode::MangaList => self.self_search(title),
+				SearchMode::SearchEngine => {
+					let result = self.engine_search(&title)?;
+					SearchEngineState::load().record_success();
+					Ok(result)
+				}
+				SearchMode::Auto => {
+					let mut state = SearchEngineState::load();
+					if !state.is_usable() {
+						return self.self_search(title);
+					}
+					match self.engine_search(&title) {
+						Ok(result) => {
+							state.record_success();
+							Ok(result)
+						}
+						Err(_) => {
+							state.record_failure();
+							self.self_search(title)
 						}
 					}
-					Ok(MangaPageResult {
-						manga,
-						has_more: false,
-					})
-				} else {
-					unsafe { INTERNAL_USE_SEARCH_ENGINE = false };
-					self.self_search(title)
 				}
-			} else {
-				self.self_search(title)
 			}
 		} else {
 			let url = format!(
@@ -263,7 +292,8 @@ impl<'a> MMRCMSSource<'a> {
 				itoa::Buffer::new().format(page),
 				query.join("&")
 			);
-			let html = Request::new(&url, HttpMethod::Get).html()?;
+			let html =
+				Request::new(&url, HttpMethod::Get).html_throttled(self.rate_limit.as_ref())?;
 			decode_cfemail(&html);
 			let node = html.select("div[class^=col-sm-]");
 			let elems = node.array();
@@ -303,8 +333,7 @@ impl<'a> MMRCMSSource<'a> {
 
 	pub fn get_manga_details(&self, id: String) -> Result<Manga> {
 		let url = format!("{}/{}/{}", self.base_url, self.manga_path, id);
-		cache_manga_page(&url);
-		let html = unsafe { CACHED_MANGA.clone().unwrap() };
+		let html = cache_manga_page(&url, self.rate_limit.as_ref())?;
 		let cover = append_protocol(html.select("img[class^=img-]").attr("abs:src").read());
 		let title = html
 			.select("h2.widget-title, h1.widget-title, .listmanga-header, div.panel-heading")
@@ -368,8 +397,7 @@ impl<'a> MMRCMSSource<'a> {
 
 	pub fn get_chapter_list(&self, id: String) -> Result<Vec<Chapter>> {
 		let url = format!("{}/{}/{}", self.base_url, self.manga_path, id);
-		cache_manga_page(&url);
-		let html = unsafe { CACHED_MANGA.clone().unwrap() };
+		let html = cache_manga_page(&url, self.rate_limit.as_ref())?;
 		let node = html.select("li:has(.chapter-title-rtl)");
 		let elems = node.array();
 		let title = html
@@ -424,7 +452,8 @@ impl<'a> MMRCMSSource<'a> {
 
 	pub fn get_page_list(&self, manga_id: String, id: String) -> Result<Vec<Page>> {
 		let url = format!("{}/{}/{}/{}", self.base_url, self.manga_path, manga_id, id);
-		let html = Request::new(&url, HttpMethod::Get).string()?;
+		let html =
+			Request::new(&url, HttpMethod::Get).string_throttled(self.rate_limit.as_ref())?;
 		let array = json::parse(
 			html.substring_after("var pages = ")
 				.unwrap_or_default()
@@ -461,6 +490,13 @@ impl<'a> MMRCMSSource<'a> {
 		request.header("Referer", self.base_url);
 	}
 
+	pub fn handle_notification(&self, notification: String) {
+		if notification == FALLBACK_KEY {
+			// Loading the state ends the fallback if the switch was turned off.
+			SearchEngineState::load();
+		}
+	}
+
 	pub fn handle_url(&self, url: String) -> Result<DeepLink> {
 		// https://manga.fascans.com/manga/aharensan-wa-hakarenai/11/1
 		// ['https:', '', 'manga.fascans.com', 'manga', 'aharensan-wa-hakarenai', '11',
@@ -1,6 +1,6 @@
 use aidoku::{
-	std::{String, Vec},
-	Manga, MangaContentRating, MangaStatus,
+	std::{String, StringRef, Vec},
+	Chapter, Manga, MangaContentRating, MangaStatus,
 };
 use alloc::borrow::ToOwned;
 use serde::Deserialize;
@@ -183,6 +183,50 @@ pub struct BookDto<'a> {
 	pub metadata: BookMetadataDto,
 }
 
+impl BookDto<'_> {
+	pub fn into_chapter<T: AsRef<str>>(self, base_url: T) -> Chapter {
+		let base_url = base_url.as_ref();
+		let mut date_updated = self
+			.metadata
+			.release_date
+			.as_ref()
+			.map(|v| StringRef::from(v).as_date("yyyy-MM-dd", Some("en_US"), None))
+			.unwrap_or(-1.0);
+		for format in [
+			"yyyy-MM-dd'T'HH:mm:ss",
+			"yyyy-MM-dd'T'HH:mm:ss'Z",
+			"yyyy-MM-dd'T'HH:mm:ss.S",
+		] {
+			if date_updated != -1.0 {
+				break;
+			}
+			date_updated =
+				StringRef::from(self.file_last_modified).as_date(format, Some("en_US"), None);
+		}
+		Chapter {
+			id: self.id.to_owned(),
+			url: [base_url, "/book/", self.id].concat(),
+			title: self.metadata.title,
+			chapter: self.metadata.number_sort,
+			date_updated,
+			..Default::default()
+		}
+	}
+
+	/// Books in a read list can come from any series, so they are surfaced as
+	/// their parent series.
+	pub fn into_series_manga<T: AsRef<str>>(self, base_url: T) -> Manga {
+		let base_url = base_url.as_ref();
+		Manga {
+			url: [base_url, "/series/", self.series_id].concat(),
+			cover: [base_url, "/api/v1/series/", self.series_id, "/thumbnail"].concat(),
+			id: self.series_id.to_owned(),
+			title: self.series_title,
+			..Default::default()
+		}
+	}
+}
+
 #[derive(Default, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase", default)]
 pub struct PageDto<'a> {
@@ -190,3 +234,12 @@ pub struct PageDto<'a> {
 	pub file_name: String,
 	pub media_type: &'a str,
 }
+
+/// Shared shape of `LibraryDto`, `CollectionDto` and `ReadListDto`; only the
+/// fields needed to resolve a name to an id are kept.
+#[derive(Default, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NamedDto<'a> {
+	pub id: &'a str,
+	pub name: String,
+}
@@ -5,28 +5,55 @@ use aidoku::{
 	error::{AidokuError, AidokuErrorKind, Result},
 	helpers::uri::encode_uri,
 	prelude::*,
-	std::{defaults::defaults_get, net::Request, String, StringRef, Vec},
-	Chapter, Filter, FilterType, Listing, Manga, MangaPageResult, Page,
+	std::{current_date, defaults::defaults_get, net::Request, String, Vec},
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaPageResult, Page,
 };
-use alloc::{vec, borrow::ToOwned, string::ToString};
-use dto::{BookDto, PageDto, PageWrapperDto, SeriesDto};
+use alloc::{borrow::ToOwned, string::ToString, vec};
+use dto::{BookDto, NamedDto, PageDto, PageWrapperDto, SeriesDto};
 
-fn get_authorization_header() -> String {
-	let username = defaults_get("username")
+fn get_string_setting(key: &str) -> String {
+	defaults_get(key)
 		.and_then(|v| v.as_string().map(|v| v.read()))
-		.unwrap_or_default();
-	let password = defaults_get("password")
-		.and_then(|v| v.as_string().map(|v| v.read()))
-		.unwrap_or_default();
+		.unwrap_or_default()
+}
 
-	let auth = format!("{username}:{password}");
+/// Returns the header name and value used to authenticate with the server,
+/// depending on the selected authentication mode.
+fn get_authorization_header() -> (&'static str, String) {
+	match get_string_setting("authMode").as_str() {
+		"apiKey" => ("X-API-Key", get_string_setting("apiKey")),
+		"bearer" => (
+			"Authorization",
+			format!("Bearer {}", get_string_setting("apiKey")),
+		),
+		_ => {
+			let username = get_string_setting("username");
+			let password = get_string_setting("password");
 
-	let authb = auth.as_bytes();
-	let mut buf = vec![0; authb.len() * 4 / 3 + 4];
-	let len = base64::encode_config_slice(authb, base64::STANDARD, &mut buf);
-	buf.resize(len, 0);
+			let auth = format!("{username}:{password}");
 
-	format!("Basic {}", String::from_utf8_lossy(&buf))
+			let authb = auth.as_bytes();
+			let mut buf = vec![0; authb.len() * 4 / 3 + 4];
+			let len = base64::encode_config_slice(authb, base64::STANDARD, &mut buf);
+			buf.resize(len, 0);
+
+			(
+				"Authorization",
+				format!("Basic {}", String::from_utf8_lossy(&buf)),
+			)
+		}
+	}
+}
+
+trait Authenticated {
+	fn authenticated(self) -> Self;
+}
+
+impl Authenticated for Request {
+	fn authenticated(self) -> Self {
+		let (name, value) = get_authorization_header();
+		self.header(name, &value)
+	}
 }
 
 fn get_base_url() -> Result<String> {
@@ -35,14 +62,109 @@ fn get_base_url() -> Result<String> {
 		.map(|v| v.read().trim_end_matches('/').to_string())
 }
 
+/// How long the names of libraries, collections and read lists are reused
+/// before they're fetched again.
+const NAMES_LIFETIME: f64 = 10.0 * 60.0;
+
+struct Names {
+	url: String,
+	fetched: f64,
+	entries: Vec<(String, String)>,
+}
+
+static mut NAMES: Vec<Names> = Vec::new();
+
+/// The ids and names listed at `url`, fetched once and reused for a while so
+/// that every page of a filtered list doesn't cost another lookup.
+fn get_names(url: &str) -> Result<Vec<(String, String)>> {
+	let cache = unsafe { &mut *core::ptr::addr_of_mut!(NAMES) };
+	let now = current_date();
+	cache.retain(|names| now - names.fetched < NAMES_LIFETIME);
+	if let Some(names) = cache.iter().find(|names| names.url == url) {
+		return Ok(names.entries.clone());
+	}
+
+	let data = Request::get(encode_uri(url)).authenticated().data();
+	let named = if url.contains("/api/v1/libraries") {
+		serde_json::from_slice::<Vec<NamedDto>>(&data)
+	} else {
+		serde_json::from_slice::<PageWrapperDto<NamedDto>>(&data).map(|v| v.content)
+	}
+	.map_err(|_| AidokuError {
+		reason: AidokuErrorKind::JsonParseError,
+	})?;
+	let entries = named
+		.into_iter()
+		.map(|v| (v.id.to_owned(), v.name))
+		.collect::<Vec<_>>();
+	cache.push(Names {
+		url: String::from(url),
+		fetched: now,
+		entries: entries.clone(),
+	});
+	Ok(entries)
+}
+
+/// Resolves a library, collection or read list typed into a text filter to
+/// its id. Either the id itself or the (case-insensitive) name is accepted.
+fn resolve_id(base_url: &str, path: &str, query: &str) -> Result<Option<String>> {
+	let query = query.trim();
+	let names = get_names(&format!("{base_url}{path}"))?;
+	Ok(names
+		.iter()
+		.find(|(id, _)| id == query)
+		.or_else(|| {
+			names
+				.iter()
+				.find(|(_, name)| name.eq_ignore_ascii_case(query))
+		})
+		.map(|(id, _)| id.to_owned()))
+}
+
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let base_url = get_base_url()?;
-	let mut url = base_url.clone();
-	url.push_str("/api/v1/series?deleted=false&page=");
+	let mut path = String::from("/api/v1/series");
+	let mut read_list = false;
+	let mut url = String::from("?deleted=false&page=");
 	url.push_str(itoa::Buffer::new().format(page - 1));
 	for filter in filters {
 		match filter.kind {
+			FilterType::Text => {
+				let Ok(value) = filter.value.as_string().map(|v| v.read()) else {
+					continue;
+				};
+				if value.trim().is_empty() {
+					continue;
+				}
+				match filter.name.as_str() {
+					"Library" => {
+						let Some(id) = resolve_id(&base_url, "/api/v1/libraries", &value)? else {
+							return Ok(MangaPageResult::default());
+						};
+						url.push_str("&library_id=");
+						url.push_str(&id);
+					}
+					"Collection" => {
+						let Some(id) =
+							resolve_id(&base_url, "/api/v1/collections?unpaged=true", &value)?
+						else {
+							return Ok(MangaPageResult::default());
+						};
+						path = format!("/api/v1/collections/{id}/series");
+					}
+					"Read list" => {
+						let Some(id) =
+							resolve_id(&base_url, "/api/v1/readlists?unpaged=true", &value)?
+						else {
+							return Ok(MangaPageResult::default());
+						};
+						path = format!("/api/v1/readlists/{id}/books");
+						read_list = true;
+					}
+					_ => continue,
+				}
+			}
 			FilterType::Check => {
 				if let Ok(id) = filter.object.get("id").as_string() {
 					url.push_str(&id.read());
@@ -69,7 +191,8 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 					let title = title.read();
 					if title.starts_with("regex:") {
 						url.push_str("&search_regex=");
-						url.push_str(title
+						url.push_str(
+							title
 								.strip_prefix("regex:")
 								.map(|v| v.trim())
 								.unwrap_or_default(),
@@ -86,10 +209,27 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 			_ => continue,
 		}
 	}
+	let url = [&base_url, path.as_str(), url.as_str()].concat();
 
-	let data = Request::get(encode_uri(url))
-		.header("Authorization", &get_authorization_header())
-		.data();
+	let data = Request::get(encode_uri(url)).authenticated().data();
+	if read_list {
+		return serde_json::from_slice(&data)
+			.map(|v: PageWrapperDto<BookDto>| {
+				let mut manga: Vec<Manga> = Vec::new();
+				for book in v.content {
+					if !manga.iter().any(|m| m.id == book.series_id) {
+						manga.push(book.into_series_manga(&base_url));
+					}
+				}
+				MangaPageResult {
+					manga,
+					has_more: !v.last,
+				}
+			})
+			.map_err(|_| AidokuError {
+				reason: AidokuErrorKind::JsonParseError,
+			});
+	}
 	serde_json::from_slice(&data)
 		.map(|v: PageWrapperDto<SeriesDto>| MangaPageResult {
 			manga: v
@@ -121,9 +261,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	url.push_str("?deleted=false&page=");
 	url.push_str(itoa::Buffer::new().format(page - 1));
 
-	let data = Request::get(encode_uri(url))
-		.header("Authorization", &get_authorization_header())
-		.data();
+	let data = Request::get(encode_uri(url)).authenticated().data();
 
 	serde_json::from_slice(&data)
 		.map(|v: PageWrapperDto<SeriesDto>| MangaPageResult {
@@ -143,9 +281,7 @@ fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 fn get_manga_details(id: String) -> Result<Manga> {
 	let base_url = get_base_url()?;
 	let url = format!("{base_url}/api/v1/series/{id}");
-	let data = Request::get(encode_uri(url))
-		.header("Authorization", &get_authorization_header())
-		.data();
+	let data = Request::get(encode_uri(url)).authenticated().data();
 	serde_json::from_slice(&data)
 		.map(|v: SeriesDto| v.into_manga(&base_url))
 		.map_err(|_| AidokuError {
@@ -156,51 +292,15 @@ fn get_manga_details(id: String) -> Result<Manga> {
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let base_url = get_base_url()?;
-	let url = format!("{base_url}/api/v1/series/{id}/books?unpaged=true&media_status=READY&deleted=false");
-	let data = Request::get(encode_uri(url))
-		.header("Authorization", &get_authorization_header())
-		.data();
+	let url = format!(
+		"{base_url}/api/v1/series/{id}/books?unpaged=true&media_status=READY&deleted=false"
+	);
+	let data = Request::get(encode_uri(url)).authenticated().data();
 	serde_json::from_slice(&data)
 		.map(|v: PageWrapperDto<BookDto>| {
 			v.content
-				.iter()
-				.map(|book| {
-					let mut date_updated = book
-						.metadata
-						.release_date
-						.as_ref()
-						.map(|v| StringRef::from(v).as_date("yyyy-MM-dd", Some("en_US"), None))
-						.unwrap_or(-1.0);
-					if date_updated == -1.0 {
-						date_updated = StringRef::from(&book.file_last_modified).as_date(
-							"yyyy-MM-dd'T'HH:mm:ss",
-							Some("en_US"),
-							None,
-						);
-					}
-					if date_updated == -1.0 {
-						date_updated = StringRef::from(&book.file_last_modified).as_date(
-							"yyyy-MM-dd'T'HH:mm:ss'Z",
-							Some("en_US"),
-							None,
-						);
-					}
-					if date_updated == -1.0 {
-						date_updated = StringRef::from(&book.file_last_modified).as_date(
-							"yyyy-MM-dd'T'HH:mm:ss.S",
-							Some("en_US"),
-							None,
-						);
-					}
-					Chapter {
-						id: book.id.to_owned(),
-						url: [&base_url, "/book/", book.id].concat(),
-						title: book.metadata.title.clone(),
-						chapter: book.metadata.number_sort,
-						date_updated,
-						..Default::default()
-					}
-				})
+				.into_iter()
+				.map(|book| book.into_chapter(&base_url))
 				.rev()
 				.collect::<Vec<_>>()
 		})
@@ -211,43 +311,76 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
-	request.header("Authorization", &get_authorization_header());
+	request.authenticated();
 }
 
 #[get_page_list]
 fn get_page_list(_: String, id: String) -> Result<Vec<Page>> {
 	let base_url = get_base_url()?;
 	let url = format!("{base_url}/api/v1/books/{id}/pages");
-	let data = Request::get(encode_uri(&url))
-		.header("Authorization", &get_authorization_header())
-		.data();
-	serde_json::from_slice(&data)
-		.map(|v: Vec<PageDto>| {
-			v.iter()
-				.map(|it| {
-					let page_url = url.clone()
-						+ "/" + itoa::Buffer::new().format(it.number)
-						+ if ![
-							"image/jpeg",
-							"image/png",
-							"image/gif",
-							"image/webp",
-						]
-						.contains(&it.media_type)
-						{
-							"?convert=png"
-						} else {
-							""
-						};
-					Page {
-						index: it.number - 1,
-						url: page_url,
-						..Default::default()
-					}
-				})
-				.collect::<Vec<_>>()
-		})
-		.map_err(|_| AidokuError {
-			reason: AidokuErrorKind::JsonParseError,
+	let data = Request::get(encode_uri(&url)).authenticated().data();
+	let pages: Vec<PageDto> = serde_json::from_slice(&data).map_err(|_| AidokuError {
+		reason: AidokuErrorKind::JsonParseError,
+	})?;
+	Ok(pages
+		.iter()
+		.map(|it| {
+			let page_url = url.clone()
+				+ "/" + itoa::Buffer::new().format(it.number)
+				+ if !["image/jpeg", "image/png", "image/gif", "image/webp"]
+					.contains(&it.media_type)
+				{
+					"?convert=png"
+				} else {
+					""
+				};
+			Page {
+				index: it.number - 1,
+				url: page_url,
+				..Default::default()
+			}
 		})
+		.collect::<Vec<_>>())
+}
+
+#[handle_url]
+fn handle_url(url: String) -> Result<DeepLink> {
+	let base_url = get_base_url()?;
+	// ex: https://demo.komga.org/series/0A5YKXXTXG3BS
+	// ex: https://demo.komga.org/book/0A5YKXXTXG3BT/read?page=1
+	let path = url
+		.strip_prefix(&base_url)
+		.ok_or(AidokuError {
+			reason: AidokuErrorKind::Unimplemented,
+		})?
+		.trim_start_matches('/');
+	let mut segments = path.split(['/', '?', '#']);
+	let kind = segments.next().unwrap_or_default();
+	let id = segments.next().unwrap_or_default();
+	if id.is_empty() {
+		return Err(AidokuError {
+			reason: AidokuErrorKind::Unimplemented,
+		});
+	}
+
+	match kind {
+		"series" => Ok(DeepLink {
+			manga: Some(get_manga_details(String::from(id))?),
+			chapter: None,
+		}),
+		"book" => {
+			let url = format!("{base_url}/api/v1/books/{id}");
+			let data = Request::get(encode_uri(url)).authenticated().data();
+			let book: BookDto = serde_json::from_slice(&data).map_err(|_| AidokuError {
+				reason: AidokuErrorKind::JsonParseError,
+			})?;
+			Ok(DeepLink {
+				manga: get_manga_details(String::from(book.series_id)).ok(),
+				chapter: Some(book.into_chapter(&base_url)),
+			})
+		}
+		_ => Err(AidokuError {
+			reason: AidokuErrorKind::Unimplemented,
+		}),
+	}
 }
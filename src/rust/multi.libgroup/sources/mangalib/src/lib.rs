@@ -12,6 +12,7 @@ static INSTANCE: SocialLibSource = SocialLibSource {
 	site_id: "1",
 	domain: "mangalib.me",
 	nsfw: &aidoku::MangaContentRating::Safe,
+	novel: false,
 };
 
 #[initialize]
@@ -11,6 +11,8 @@ use aidoku::{
 use alloc::{borrow::ToOwned, string::ToString, vec::Vec};
 extern crate alloc;
 
+pub use source_support::extract_f32_from_string;
+
 pub fn search(filters: Vec<Filter>) -> String {
 	let mut query = QueryParameters::new();
 	for filter in filters {
@@ -58,6 +60,27 @@ pub fn search(filters: Vec<Filter>) -> String {
 					"comics" => {
 						query.push("types[]", Some("9"));
 					}
+
+					// Type of ranobe, as listed for RanobeLib (site id 3) by
+					// `{DOMAIN_API}constants?fields[]=types`
+					"japan" => {
+						query.push("types[]", Some("10"));
+					}
+					"korea" => {
+						query.push("types[]", Some("11"));
+					}
+					"china" => {
+						query.push("types[]", Some("12"));
+					}
+					"english" => {
+						query.push("types[]", Some("13"));
+					}
+					"author" => {
+						query.push("types[]", Some("14"));
+					}
+					"fanfic" => {
+						query.push("types[]", Some("15"));
+					}
 					_ => continue,
 				}
 			}
@@ -101,19 +124,6 @@ pub fn id_to_status(id: i64) -> MangaStatus {
 	}
 }
 
-pub fn extract_f32_from_string(title: String, text: String) -> Vec<f32> {
-	text.replace(&title, "")
-		.chars()
-		.filter(|a| (*a >= '0' && *a <= '9') || *a == ' ' || *a == '.' || *a == '+')
-		.collect::<String>()
-		.split(' ')
-		.collect::<Vec<&str>>()
-		.into_iter()
-		.map(|a| a.parse::<f32>().unwrap_or(-1.0))
-		.filter(|a| *a >= 0.0)
-		.collect::<Vec<f32>>()
-}
-
 pub fn display_title() -> String {
 	if defaults_get("display_in_eng")
 		.and_then(|value| value.as_bool())
@@ -1,19 +1,23 @@
 use aidoku::error::Result;
+use aidoku::helpers::node::NodeHelpers;
 use aidoku::prelude::format;
 use aidoku::std::defaults::defaults_get;
+use aidoku::std::html::Node;
 use aidoku::std::String;
-use aidoku::{std::ObjectRef, Manga, MangaPageResult};
+use aidoku::{std::ArrayRef, std::ObjectRef, Manga, MangaPageResult};
 use aidoku::{Chapter, MangaContentRating, MangaViewer, Page};
 use alloc::string::ToString;
 use alloc::vec::Vec;
 
 use crate::helpers::{display_title, extract_f32_from_string, id_to_status};
 use crate::template::CDN;
+use source_support::append_protocol;
 extern crate alloc;
 
 pub fn parse_manga_list(
 	js: ObjectRef,
 	domain: &String,
+	path: &str,
 	nsfw: &MangaContentRating,
 ) -> Result<MangaPageResult> {
 	let has_more = js.get("meta").as_object()?.get("has_next_page").as_bool()?;
@@ -43,7 +47,7 @@ pub fn parse_manga_list(
 			};
 
 			let url = match data_obj.get("slug_url").as_string() {
-				Ok(x) => format!("https://{}/ru/manga/{}", domain, x.read()),
+				Ok(x) => format!("https://{}/ru/{}/{}", domain, path, x.read()),
 				Err(_) => continue,
 			};
 
@@ -73,6 +77,7 @@ pub fn parse_manga_list(
 pub fn parse_manga_details(
 	js: ObjectRef,
 	domain: &str,
+	path: &str,
 	is_nsfw: &MangaContentRating,
 ) -> Result<Manga> {
 	let detail = js.get("data").as_object()?;
@@ -111,8 +116,9 @@ pub fn parse_manga_details(
 	let description = detail.get("summary").as_string()?.read();
 
 	let url = format!(
-		"https://{}/ru/manga/{}",
+		"https://{}/ru/{}/{}",
 		domain,
+		path,
 		detail.get("slug_url").as_string()?.read()
 	);
 
@@ -300,3 +306,170 @@ pub fn parse_page_list(js: ObjectRef, cdn: &CDN) -> Result<Vec<Page>> {
 
 	Ok(chapters)
 }
+
+/// Text pages of a ranobe chapter, split by the images it contains.
+struct TextPages {
+	pages: Vec<Page>,
+	text: String,
+}
+
+impl TextPages {
+	fn push_text(&mut self, text: &str) {
+		self.text.push_str(text);
+	}
+
+	/// Ends a paragraph, heading or other block.
+	fn end_block(&mut self) {
+		let trimmed = self.text.trim_end_matches([' ', '\n']).len();
+		self.text.truncate(trimmed);
+		if !self.text.is_empty() {
+			self.text.push_str("\n\n");
+		}
+	}
+
+	fn flush(&mut self) {
+		self.end_block();
+		let text = String::from(self.text.trim());
+		self.text.clear();
+		if !text.is_empty() {
+			self.pages.push(Page {
+				index: self.pages.len() as i32,
+				text,
+				..Default::default()
+			});
+		}
+	}
+
+	fn push_image(&mut self, url: String) {
+		self.flush();
+		self.pages.push(Page {
+			index: self.pages.len() as i32,
+			url,
+			..Default::default()
+		});
+	}
+
+	fn finish(mut self) -> Vec<Page> {
+		self.flush();
+		self.pages
+	}
+}
+
+/// `url` with the site's domain in front when it's a path.
+fn absolute_url(domain: &str, url: String) -> String {
+	if url.starts_with("http") || url.starts_with("//") {
+		append_protocol(url)
+	} else {
+		append_protocol(format!("{domain}/{}", url.trim_start_matches('/')))
+	}
+}
+
+/// Walks the editor document the API returns for newer chapters: a tree of
+/// `{ type, content, text, attrs }` nodes like "paragraph", "text", "hardBreak"
+/// and "image", whose images refer to the chapter's attachments by name.
+fn push_rich_text(node: &ObjectRef, attachments: &[(String, String)], pages: &mut TextPages) {
+	let kind = node
+		.get("type")
+		.as_string()
+		.map(|x| x.read())
+		.unwrap_or_default();
+	let children = |pages: &mut TextPages| {
+		if let Ok(content) = node.get("content").as_array() {
+			for child in content {
+				if let Ok(child) = child.as_object() {
+					push_rich_text(&child, attachments, pages);
+				}
+			}
+		}
+	};
+	match kind.as_str() {
+		"text" => pages.push_text(&node.get("text").as_string().unwrap_or_default().read()),
+		"hardBreak" => pages.push_text("\n"),
+		"horizontalRule" => {
+			pages.end_block();
+			pages.push_text("***");
+			pages.end_block();
+		}
+		"image" => {
+			let images = node
+				.get("attrs")
+				.as_object()
+				.and_then(|attrs| attrs.get("images").as_array());
+			for image in images.into_iter().flatten() {
+				let Ok(name) = image
+					.as_object()
+					.and_then(|image| image.get("image").as_string())
+					.map(|name| name.read())
+				else {
+					continue;
+				};
+				if let Some((_, url)) = attachments.iter().find(|(n, _)| *n == name) {
+					pages.push_image(url.clone());
+				}
+			}
+		}
+		"paragraph" | "heading" | "blockquote" | "listItem" => {
+			children(pages);
+			pages.end_block();
+		}
+		_ => children(pages),
+	}
+}
+
+/// The elements of chapter HTML that become a text block or an image page.
+const HTML_BLOCKS: &str = "p, img, h1, h2, h3, h4, blockquote";
+
+/// Chapter text as HTML, from older chapters: paragraphs become text and
+/// images become pages of their own.
+fn push_html(html: &str, domain: &str, pages: &mut TextPages) -> Result<()> {
+	let html = Node::new_fragment(html.as_bytes())?;
+	for elem in html.select(HTML_BLOCKS).array() {
+		let Ok(elem) = elem.as_node() else {
+			continue;
+		};
+		let src = elem.attr("src").read();
+		if !src.is_empty() {
+			pages.push_image(absolute_url(domain, src));
+		} else if elem.select(HTML_BLOCKS).array().len() == 1 {
+			// `select` includes the element itself, so this skips blocks that
+			// hold others, like a blockquote of paragraphs or a paragraph
+			// with an image, whose inner blocks come up on their own.
+			pages.push_text(&elem.text_with_newlines());
+			pages.end_block();
+		}
+	}
+	Ok(())
+}
+
+fn parse_attachments(attachments: ArrayRef, domain: &str) -> Vec<(String, String)> {
+	attachments
+		.filter_map(|attachment| {
+			let attachment = attachment.as_object().ok()?;
+			let name = attachment.get("name").as_string().ok()?.read();
+			let url = attachment.get("url").as_string().ok()?.read();
+			Some((name, absolute_url(domain, url)))
+		})
+		.collect()
+}
+
+pub fn parse_text_pages(js: ObjectRef, domain: &str) -> Result<Vec<Page>> {
+	let data = js.get("data").as_object()?;
+	let attachments = data
+		.get("attachments")
+		.as_array()
+		.map(|x| parse_attachments(x, domain))
+		.unwrap_or_default();
+	let mut pages = TextPages {
+		pages: Vec::new(),
+		text: String::new(),
+	};
+
+	if let Ok(html) = data.get("content").as_string() {
+		push_html(&html.read(), domain, &mut pages)?;
+	} else {
+		let doc = data.get("content").as_object()?;
+		push_rich_text(&doc, &attachments, &mut pages);
+	}
+
+	Ok(pages.finish())
+}
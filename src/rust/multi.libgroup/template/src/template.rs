@@ -2,12 +2,7 @@ use aidoku::{
 	error::{AidokuError, AidokuErrorKind, Result},
 	helpers::uri::QueryParameters,
 	prelude::format,
-	std::{
-		current_date,
-		defaults::defaults_get,
-		net::Request,
-		String, Vec,
-	},
+	std::{current_date, defaults::defaults_get, net::Request, String, Vec},
 	Chapter, Filter, Listing, Manga, MangaContentRating, MangaPageResult, Page,
 };
 use alloc::string::ToString;
@@ -22,6 +17,8 @@ pub struct SocialLibSource {
 	pub site_id: &'static str,
 	pub domain: &'static str,
 	pub nsfw: &'static MangaContentRating,
+	/// Ranobe sites list books, whose chapters are rich text instead of images.
+	pub novel: bool,
 }
 
 pub struct CDN {
@@ -34,6 +31,15 @@ static USER_AGENT: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 18_0 like Mac OS X
 static DOMAIN_API: &str = "https://api2.mangalib.me/api/";
 
 impl SocialLibSource {
+	/// {domain}/ru/{path}/{slug}
+	fn path(&self) -> &'static str {
+		if self.novel {
+			"book"
+		} else {
+			"manga"
+		}
+	}
+
 	pub fn refresh_token(&self) -> Result<()> {
 		let timestamp = defaults_get("timestamp")?.as_int()? / 1000;
 		let access_token = defaults_get("access_token")?.as_string()?.read();
@@ -91,7 +97,7 @@ impl SocialLibSource {
 
 		let json = request.json()?.as_object()?;
 
-		parser::parse_manga_list(json, &self.domain.to_string(), self.nsfw)
+		parser::parse_manga_list(json, &self.domain.to_string(), self.path(), self.nsfw)
 	}
 
 	pub fn get_manga_listing(&self, listing: Listing, page: i32) -> Result<MangaPageResult> {
@@ -106,7 +112,7 @@ impl SocialLibSource {
 			let request = self.request_get(&url);
 			let json = request.json()?.as_object()?;
 
-			parser::parse_manga_list(json, &self.domain.to_string(), self.nsfw)
+			parser::parse_manga_list(json, &self.domain.to_string(), self.path(), self.nsfw)
 		} else {
 			Err(AidokuError {
 				reason: aidoku::error::AidokuErrorKind::Unimplemented,
@@ -127,7 +133,7 @@ impl SocialLibSource {
 		let request = self.request_get(&url);
 		let json = request.json()?.as_object()?;
 
-		parser::parse_manga_details(json, self.domain, self.nsfw)
+		parser::parse_manga_details(json, self.domain, self.path(), self.nsfw)
 	}
 
 	pub fn get_chapter_list(&self, id: String) -> Result<Vec<Chapter>> {
@@ -151,6 +157,9 @@ impl SocialLibSource {
 		);
 		let request = self.request_get(&url);
 		let json = request.json()?.as_object()?;
+		if self.novel {
+			return parser::parse_text_pages(json, self.domain);
+		}
 		let cdn = self.get_cdn_domains()?;
 
 		parser::parse_page_list(json, &cdn)
@@ -0,0 +1,118 @@
+use aidoku::{
+	error::{AidokuError, AidokuErrorKind, Result},
+	prelude::format,
+	std::{
+		String, StringRef,
+		defaults::{defaults_get, defaults_set},
+		net::{HttpMethod, Request},
+	},
+};
+
+use crate::helper::{SendRatelimited, current_date, urlencode};
+
+const TOKEN_URL: &str = "https://auth.mangadex.org/realms/mangadex/protocol/openid-connect/token";
+
+fn get_setting(key: &str) -> String {
+	defaults_get(key)
+		.and_then(|v| v.as_string())
+		.map(|v| v.read())
+		.unwrap_or_default()
+}
+
+fn not_logged_in() -> AidokuError {
+	AidokuError {
+		reason: AidokuErrorKind::DefaultNotFound,
+	}
+}
+
+/// Sends a form to the token endpoint and stores the returned tokens.
+fn request_token(grant: &str) -> Result<()> {
+	let client_id = get_setting("clientId");
+	let client_secret = get_setting("clientSecret");
+	if client_id.is_empty() || client_secret.is_empty() {
+		return Err(not_logged_in());
+	}
+	let body = format!(
+		"{grant}&client_id={}&client_secret={}",
+		urlencode(client_id),
+		urlencode(client_secret)
+	);
+	let json = Request::new(TOKEN_URL, HttpMethod::Post)
+		.header("Content-Type", "application/x-www-form-urlencoded")
+		.body(body.as_bytes())
+		.json_rl()
+		.as_object()?;
+
+	let access_token = json.get("access_token").as_string()?;
+	let refresh_token = json.get("refresh_token").as_string()?;
+	let expires_in = json.get("expires_in").as_int().unwrap_or(900);
+	defaults_set("accessToken", access_token.0);
+	defaults_set("refreshToken", refresh_token.0);
+	// Refresh a minute early so a token never expires mid-request.
+	defaults_set(
+		"tokenExpiry",
+		(current_date() as i64 + expires_in - 60).into(),
+	);
+	Ok(())
+}
+
+/// Logs in with the personal API client credentials from settings, or logs
+/// out if any of them are missing.
+pub fn login() -> Result<()> {
+	let username = get_setting("username");
+	let password = get_setting("password");
+	if username.is_empty() || password.is_empty() {
+		logout();
+		return Err(not_logged_in());
+	}
+	let grant = format!(
+		"grant_type=password&username={}&password={}",
+		urlencode(username),
+		urlencode(password)
+	);
+	request_token(&grant).inspect_err(|_| logout())
+}
+
+pub fn logout() {
+	for key in ["accessToken", "refreshToken"] {
+		defaults_set(key, StringRef::from("").0);
+	}
+	defaults_set("tokenExpiry", 0i64.into());
+}
+
+/// Returns a valid access token, refreshing it (or logging in again) when
+/// the stored one has expired.
+pub fn get_access_token() -> Result<String> {
+	let token = get_setting("accessToken");
+	if token.is_empty() {
+		return Err(not_logged_in());
+	}
+	let expiry = defaults_get("tokenExpiry")
+		.and_then(|v| v.as_int())
+		.unwrap_or(0);
+	if (current_date() as i64) < expiry {
+		return Ok(token);
+	}
+
+	let refresh_token = get_setting("refreshToken");
+	let grant = format!(
+		"grant_type=refresh_token&refresh_token={}",
+		urlencode(refresh_token)
+	);
+	if request_token(&grant).is_err() {
+		login()?;
+	}
+	Ok(get_setting("accessToken"))
+}
+
+pub trait Authenticated: Sized {
+	/// Adds the bearer token, failing if the user isn't logged in.
+	fn authenticated(self) -> Result<Self>;
+}
+
+impl Authenticated for Request {
+	fn authenticated(self) -> Result<Self> {
+		let token = get_access_token()?;
+		Ok(self.header("Authorization", &format!("Bearer {token}")))
+	}
+}
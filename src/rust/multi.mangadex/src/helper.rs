@@ -1,24 +1,6 @@
-use aidoku::std::{String, ValueRef, Vec, net::Request};
+use aidoku::std::{String, ValueRef, defaults::defaults_get, net::Request};
 
-pub fn urlencode(string: String) -> String {
-	let mut result: Vec<u8> = Vec::with_capacity(string.len() * 3);
-	let hex = "0123456789abcdef".as_bytes();
-	let bytes = string.as_bytes();
-	let whitelist = "-_.!~*'()".as_bytes();
-
-	for byte in bytes {
-		let curr = *byte;
-		if curr.is_ascii_alphanumeric() || whitelist.contains(&curr) {
-			result.push(curr);
-		} else {
-			result.push(b'%');
-			result.push(hex[curr as usize >> 4]);
-			result.push(hex[curr as usize & 15]);
-		}
-	}
-
-	String::from_utf8(result).unwrap_or_default()
-}
+pub use source_support::urlencode;
 
 #[link(wasm_import_module = "net")]
 unsafe extern "C" {
@@ -92,3 +74,38 @@ impl SendRatelimited for Request {
 		ValueRef::new(rid)
 	}
 }
+
+/// Appends the preferred languages and the blocked groups/uploaders to a
+/// chapter query.
+pub fn push_chapter_filters(url: &mut String) {
+	if let Ok(languages_value) = defaults_get("languages")
+		&& let Ok(languages) = languages_value.as_array()
+	{
+		languages.for_each(|lang| {
+			if let Ok(lang) = lang.as_string() {
+				url.push_str("&translatedLanguage[]=");
+				url.push_str(&lang.read());
+			}
+		})
+	}
+	if let Ok(groups_value) = defaults_get("blockedGroupsArray")
+		&& let Ok(groups) = groups_value.as_array()
+	{
+		groups.for_each(|group| {
+			if let Ok(group) = group.as_string() {
+				url.push_str("&excludedGroups[]=");
+				url.push_str(&group.read());
+			}
+		});
+	}
+	if let Ok(groups_value) = defaults_get("blockedUploadersArray")
+		&& let Ok(groups) = groups_value.as_array()
+	{
+		groups.for_each(|group| {
+			if let Ok(group) = group.as_string() {
+				url.push_str("&excludedUploaders[]=");
+				url.push_str(&group.read());
+			}
+		});
+	}
+}
@@ -1,6 +1,8 @@
 #![no_std]
 extern crate alloc;
+mod auth;
 mod helper;
+mod md_home;
 mod parser;
 use aidoku::{
 	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaPageResult, Page,
@@ -13,6 +15,7 @@ use aidoku::{
 	},
 };
 use alloc::borrow::ToOwned;
+use auth::Authenticated;
 use helper::*;
 
 #[link(wasm_import_module = "net")]
@@ -29,7 +32,7 @@ pub unsafe extern "C" fn initialize() {
 		set_rate_limit_period(1);
 	};
 
-	for key in ["blockedGroups", "blockedUploaders"] {
+	for key in ["blockedGroups", "blockedUploaders", "preferredGroups"] {
 		let arrkey = key.to_owned() + "Array";
 		if let Ok(arr_val) = defaults_get(&arrkey) {
 			if arr_val.as_array().is_err() {
@@ -39,14 +42,144 @@ pub unsafe extern "C" fn initialize() {
 	}
 }
 
+/// Ids of the manga the user has marked with the given reading status.
+fn get_status_manga_ids(status: &str) -> Result<Vec<String>> {
+	let url = String::from("https://api.mangadex.org/manga/status?status=") + status;
+	let json = Request::new(&url, HttpMethod::Get)
+		.authenticated()?
+		.json_rl()
+		.as_object()?;
+	Ok(json
+		.get("statuses")
+		.as_object()?
+		.keys()
+		.filter_map(|id| id.as_string().ok().map(|id| id.read()))
+		.collect())
+}
+
+/// Every content rating, for feeds of manga the user picked themselves.
+const ALL_CONTENT_RATINGS: &str = "&contentRating[]=pornographic\
+	&contentRating[]=erotica\
+	&contentRating[]=suggestive\
+	&contentRating[]=safe";
+
+/// The content ratings the API returns when none are given.
+const DEFAULT_CONTENT_RATINGS: &str = "&contentRating[]=erotica\
+	&contentRating[]=suggestive\
+	&contentRating[]=safe";
+
+/// The user's custom lists, as their id, name and the ids of their manga.
+fn get_custom_lists() -> Result<Vec<(String, String, Vec<String>)>> {
+	let json = Request::new(
+		"https://api.mangadex.org/user/list?limit=100",
+		HttpMethod::Get,
+	)
+	.authenticated()?
+	.json_rl()
+	.as_object()?;
+	Ok(json
+		.get("data")
+		.as_array()?
+		.filter_map(|list| {
+			let list = list.as_object().ok()?;
+			let id = list.get("id").as_string().ok()?.read();
+			let name = list
+				.get("attributes")
+				.as_object()
+				.and_then(|v| v.get("name").as_string())
+				.map(|v| v.read())
+				.unwrap_or_default();
+			let manga_ids = list
+				.get("relationships")
+				.as_array()
+				.ok()?
+				.filter_map(|relationship| {
+					let relationship = relationship.as_object().ok()?;
+					if relationship.get("type").as_string().ok()?.read() == "manga" {
+						relationship.get("id").as_string().ok().map(|v| v.read())
+					} else {
+						None
+					}
+				})
+				.collect::<Vec<_>>();
+			Some((id, name, manga_ids))
+		})
+		.collect())
+}
+
+/// Ids of the manga in one of the user's custom lists, looked up by name or
+/// id.
+fn get_custom_list_manga_ids(name: &str) -> Result<Vec<String>> {
+	let name = name.trim();
+	Ok(get_custom_lists()?
+		.into_iter()
+		.find(|(id, list_name, _)| id == name || list_name.eq_ignore_ascii_case(name))
+		.map(|(_, _, manga_ids)| manga_ids)
+		.unwrap_or_default())
+}
+
+/// Ids of the manga in all of the user's custom lists, in list order.
+fn get_all_custom_list_manga_ids() -> Result<Vec<String>> {
+	let mut ids: Vec<String> = Vec::new();
+	for (_, _, manga_ids) in get_custom_lists()? {
+		for id in manga_ids {
+			if !ids.contains(&id) {
+				ids.push(id);
+			}
+		}
+	}
+	Ok(ids)
+}
+
+/// One page of the given manga, paged here instead of by the API since only
+/// up to 100 ids can be sent at once.
+fn get_manga_by_ids(mut url: String, ids: &[String], offset: i32) -> Result<MangaPageResult> {
+	let start = (offset as usize).min(ids.len());
+	let end = (start + 20).min(ids.len());
+	if start == end {
+		return Ok(MangaPageResult {
+			manga: Vec::new(),
+			has_more: false,
+		});
+	}
+	url.push_str(ALL_CONTENT_RATINGS);
+	for id in &ids[start..end] {
+		url.push_str("&ids[]=");
+		url.push_str(id);
+	}
+	let json = Request::new(&url, HttpMethod::Get).json_rl().as_object()?;
+	let manga = json
+		.get("data")
+		.as_array()?
+		.filter_map(|manga| match manga.as_object() {
+			Ok(obj) => parser::parse_basic_manga(obj).ok(),
+			Err(_) => None,
+		})
+		.collect::<Vec<_>>();
+	Ok(MangaPageResult {
+		manga,
+		has_more: end < ids.len(),
+	})
+}
+
+/// Narrows the set of allowed manga ids down to those also in `other`.
+fn restrict_ids(ids: &mut Option<Vec<String>>, other: Vec<String>) {
+	*ids = Some(match ids.take() {
+		Some(ids) => ids.into_iter().filter(|id| other.contains(id)).collect(),
+		None => other,
+	});
+}
+
 #[get_manga_list]
 fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	let offset = (page - 1) * 20;
 	let mut url = String::from(
 		"https://api.mangadex.org/manga/?includes[]=cover_art\
-		&limit=20\
-		&offset=",
-	) + itoa::Buffer::new().format(offset);
+		&limit=20",
+	);
+	// Set when filtering by the user's library, which the search endpoint
+	// can't do on its own.
+	let mut ids: Option<Vec<String>> = None;
 
 	for filter in filters {
 		match filter.kind {
@@ -62,6 +195,14 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 					url.push_str(&urlencode(value.read()));
 				}
 			}
+			FilterType::Text => {
+				if filter.name == "Custom list"
+					&& let Ok(value) = filter.value.as_string().map(|v| v.read())
+					&& !value.trim().is_empty()
+				{
+					restrict_ids(&mut ids, get_custom_list_manga_ids(&value)?);
+				}
+			}
 			FilterType::Check => {
 				let value = filter.value.as_int().unwrap_or(-1);
 				if value < 0 {
@@ -146,12 +287,30 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 						_ => url.push_str("OR"),
 					}
 				}
+				"Reading status" => {
+					let status = match filter.value.as_int().unwrap_or(-1) {
+						1 => "reading",
+						2 => "plan_to_read",
+						3 => "completed",
+						4 => "on_hold",
+						5 => "re_reading",
+						6 => "dropped",
+						_ => continue,
+					};
+					restrict_ids(&mut ids, get_status_manga_ids(status)?);
+				}
 				_ => continue,
 			},
 			_ => continue,
 		}
 	}
 
+	if let Some(ids) = ids {
+		return get_manga_by_ids(url, &ids, offset);
+	}
+
+	url.push_str("&offset=");
+	url.push_str(itoa::Buffer::new().format(offset));
 	let json = Request::new(&url, HttpMethod::Get).json_rl().as_object()?;
 
 	let data = json.get("data").as_array()?;
@@ -171,112 +330,155 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	})
 }
 
+/// Lists the manga of the chapters returned by a chapter feed endpoint. The
+/// manga are looked up with the same content ratings as the chapters, so
+/// none of them are dropped.
+fn get_chapter_feed_manga(
+	mut url: String,
+	content_ratings: &str,
+	page: i32,
+	authenticated: bool,
+) -> Result<MangaPageResult> {
+	let offset = (page - 1) * 40;
+	url.push_str(content_ratings);
+	url.push_str("&limit=40&offset=");
+	url.push_str(itoa::Buffer::new().format(offset));
+	push_chapter_filters(&mut url);
+
+	let mut request = Request::new(&url, HttpMethod::Get);
+	if authenticated {
+		request = request.authenticated()?;
+	}
+	let mut json = request.json_rl().as_object()?;
+
+	let total = json.get("total").as_int().unwrap_or(0) as i32;
+	let mut data = json.get("data").as_array()?;
+
+	let manga_ids = data
+		.filter_map(|chapter| match chapter.as_object() {
+			Ok(obj) => {
+				if let Ok(relationships) = obj.get("relationships").as_array() {
+					for relationship in relationships {
+						if let Ok(relationship) = relationship.as_object()
+							&& let Ok(relation_type) = relationship.get("type").as_string()
+							&& relation_type.read() == "manga"
+							&& let Ok(id) = relationship.get("id").as_string()
+						{
+							let mut ret = String::from("&ids[]=");
+							ret.push_str(&id.read());
+							return Some(ret);
+						}
+					}
+					None
+				} else {
+					None
+				}
+			}
+			Err(_) => None,
+		})
+		.collect::<String>();
+
+	url = String::from(
+		"https://api.mangadex.org/manga\
+		?includes[]=cover_art\
+		&order[updatedAt]=desc\
+		&limit=40",
+	) + content_ratings
+		+ &manga_ids;
+	json = Request::new(&url, HttpMethod::Get).json_rl().as_object()?;
+	data = json.get("data").as_array()?;
+	let manga = data
+		.filter_map(|manga| match manga.as_object() {
+			Ok(obj) => parser::parse_basic_manga(obj).ok(),
+			Err(_) => None,
+		})
+		.collect::<Vec<_>>();
+
+	Ok(MangaPageResult {
+		manga,
+		has_more: offset + 40 < total,
+	})
+}
+
 #[get_manga_listing]
 fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
 	let mut filters: Vec<Filter> = Vec::with_capacity(1);
 	let mut selection = ObjectRef::new();
 
-	if listing.name == "Popular" {
-		selection.set("index", 2.into());
-		selection.set("ascending", false.into());
-		filters.push(Filter {
-			kind: FilterType::Sort,
-			name: String::from("Sort"),
-			value: selection.0.clone(),
-			object: selection,
-		});
-	} else if listing.name == "Latest" {
-		// get recently published chapters
-		let offset = (page - 1) * 40;
-		let mut url = String::from(
-			"https://api.mangadex.org/chapter\
-			?includes[]=manga\
-			&order[publishAt]=desc\
-			&includeFutureUpdates=0\
-			&limit=40\
-			&offset=",
-		) + itoa::Buffer::new().format(offset);
-		if let Ok(languages_value) = defaults_get("languages") {
-			if let Ok(languages) = languages_value.as_array() {
-				languages.for_each(|lang| {
-					if let Ok(lang) = lang.as_string() {
-						url.push_str("&translatedLanguage[]=");
-						url.push_str(&lang.read());
-					}
-				})
-			}
+	match listing.name.as_str() {
+		"Popular" => {
+			selection.set("index", 2.into());
+			selection.set("ascending", false.into());
+			filters.push(Filter {
+				kind: FilterType::Sort,
+				name: String::from("Sort"),
+				value: selection.0.clone(),
+				object: selection,
+			});
 		}
-		if let Ok(groups_value) = defaults_get("blockedGroupsArray") {
-			if let Ok(groups) = groups_value.as_array() {
-				groups.for_each(|group| {
-					if let Ok(group) = group.as_string() {
-						url.push_str("&excludedGroups[]=");
-						url.push_str(&group.read());
-					}
-				});
-			}
+		"Latest" => {
+			// get recently published chapters
+			return get_chapter_feed_manga(
+				String::from(
+					"https://api.mangadex.org/chapter\
+					?includes[]=manga\
+					&order[publishAt]=desc\
+					&includeFutureUpdates=0",
+				),
+				DEFAULT_CONTENT_RATINGS,
+				page,
+				false,
+			);
 		}
-		if let Ok(groups_value) = defaults_get("blockedUploadersArray") {
-			if let Ok(groups) = groups_value.as_array() {
-				groups.for_each(|group| {
-					if let Ok(group) = group.as_string() {
-						url.push_str("&excludedUploaders[]=");
-						url.push_str(&group.read());
-					}
-				});
-			}
+		"Followed Feed" => {
+			return get_chapter_feed_manga(
+				String::from(
+					"https://api.mangadex.org/user/follows/manga/feed\
+					?order[publishAt]=desc\
+					&includeFutureUpdates=0",
+				),
+				ALL_CONTENT_RATINGS,
+				page,
+				true,
+			);
 		}
-
-		let mut json = Request::new(&url, HttpMethod::Get).json_rl().as_object()?;
-
-		let total = json.get("total").as_int().unwrap_or(0) as i32;
-		let mut data = json.get("data").as_array()?;
-
-		let manga_ids = data
-			.filter_map(|chapter| match chapter.as_object() {
-				Ok(obj) => {
-					if let Ok(relationships) = obj.get("relationships").as_array() {
-						for relationship in relationships {
-							if let Ok(relationship) = relationship.as_object()
-								&& let Ok(relation_type) = relationship.get("type").as_string()
-								&& relation_type.read() == "manga"
-								&& let Ok(id) = relationship.get("id").as_string()
-							{
-								let mut ret = String::from("&ids[]=");
-								ret.push_str(&id.read());
-								return Some(ret);
-							}
-						}
-						None
-					} else {
-						None
-					}
-				}
-				Err(_) => None,
-			})
-			.collect::<String>();
-
-		url = String::from(
-			"https://api.mangadex.org/manga\
-			?includes[]=cover_art\
-			&order[updatedAt]=desc\
-			&contentRating[]=erotica\
-			&contentRating[]=suggestive\
-			&contentRating[]=safe",
-		) + &manga_ids;
-		json = Request::new(&url, HttpMethod::Get).json_rl().as_object()?;
-		data = json.get("data").as_array()?;
-		let manga = data
-			.filter_map(|manga| match manga.as_object() {
-				Ok(obj) => parser::parse_basic_manga(obj).ok(),
-				Err(_) => None,
-			})
-			.collect::<Vec<_>>();
-
-		return Ok(MangaPageResult {
-			manga,
-			has_more: offset + 20 < total,
-		});
+		"Custom Lists" => {
+			return get_manga_by_ids(
+				String::from(
+					"https://api.mangadex.org/manga/?includes[]=cover_art\
+					&limit=20",
+				),
+				&get_all_custom_list_manga_ids()?,
+				(page - 1) * 20,
+			);
+		}
+		"Followed" => {
+			let offset = (page - 1) * 20;
+			let url = String::from(
+				"https://api.mangadex.org/user/follows/manga\
+				?includes[]=cover_art\
+				&limit=20\
+				&offset=",
+			) + itoa::Buffer::new().format(offset);
+			let json = Request::new(&url, HttpMethod::Get)
+				.authenticated()?
+				.json_rl()
+				.as_object()?;
+			let manga = json
+				.get("data")
+				.as_array()?
+				.filter_map(|manga| match manga.as_object() {
+					Ok(obj) => parser::parse_basic_manga(obj).ok(),
+					Err(_) => None,
+				})
+				.collect::<Vec<_>>();
+			let total = json.get("total").as_int().unwrap_or(0) as i32;
+			return Ok(MangaPageResult {
+				manga,
+				has_more: offset + 20 < total,
+			});
+		}
+		_ => {}
 	}
 
 	get_manga_list(filters, page)
@@ -295,6 +497,65 @@ fn get_manga_details(id: String) -> Result<Manga> {
 	parser::parse_full_manga(data)
 }
 
+/// Keeps a single upload per (volume, chapter, language), picking the one
+/// from the most preferred group and then the most recent one. Chapters
+/// without a number are always kept.
+fn collapse_chapters(chapters: Vec<(Chapter, Vec<String>)>) -> Vec<Chapter> {
+	let mut preferred: Vec<String> = Vec::new();
+	if let Ok(groups_value) = defaults_get("preferredGroupsArray")
+		&& let Ok(groups) = groups_value.as_array()
+	{
+		groups.for_each(|group| {
+			if let Ok(group) = group.as_string() {
+				preferred.push(group.read().to_lowercase());
+			}
+		});
+	}
+	let rank = |groups: &[String]| {
+		groups
+			.iter()
+			.filter_map(|group| {
+				let group = group.to_lowercase();
+				preferred.iter().position(|v| *v == group)
+			})
+			.min()
+			.unwrap_or(usize::MAX)
+	};
+
+	let mut result: Vec<(Chapter, usize)> = Vec::with_capacity(chapters.len());
+	for (chapter, groups) in chapters {
+		let chapter_rank = rank(&groups);
+		if chapter.chapter >= 0.0
+			&& let Some(existing) = result.iter_mut().find(|(existing, _)| {
+				existing.chapter == chapter.chapter
+					&& existing.volume == chapter.volume
+					&& existing.lang == chapter.lang
+			}) {
+			if chapter_rank < existing.1
+				|| (chapter_rank == existing.1 && chapter.date_updated > existing.0.date_updated)
+			{
+				*existing = (chapter, chapter_rank);
+			}
+			continue;
+		}
+		result.push((chapter, chapter_rank));
+	}
+	result.into_iter().map(|(chapter, _)| chapter).collect()
+}
+
+fn parse_chapters(data: ArrayRef) -> Vec<(Chapter, Vec<String>)> {
+	data.filter_map(|chapter| match chapter.as_object() {
+		Ok(obj) => {
+			let groups = parser::parse_chapter_groups(&obj);
+			parser::parse_chapter(obj)
+				.ok()
+				.map(|chapter| (chapter, groups))
+		}
+		Err(_) => None,
+	})
+	.collect()
+}
+
 #[get_chapter_list]
 fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 	let mut url = String::from("https://api.mangadex.org/manga/")
@@ -309,48 +570,13 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 		&includes[]=user\
 		&includes[]=scanlation_group";
 
-	if let Ok(languages_value) = defaults_get("languages") {
-		if let Ok(languages) = languages_value.as_array() {
-			languages.for_each(|lang| {
-				if let Ok(lang) = lang.as_string() {
-					url.push_str("&translatedLanguage[]=");
-					url.push_str(&lang.read());
-				}
-			})
-		}
-	}
-	if let Ok(groups_value) = defaults_get("blockedGroupsArray") {
-		if let Ok(groups) = groups_value.as_array() {
-			groups.for_each(|group| {
-				if let Ok(group) = group.as_string() {
-					url.push_str("&excludedGroups[]=");
-					url.push_str(&group.read());
-				}
-			});
-		}
-	}
-	if let Ok(groups_value) = defaults_get("blockedUploadersArray") {
-		if let Ok(groups) = groups_value.as_array() {
-			groups.for_each(|group| {
-				if let Ok(group) = group.as_string() {
-					url.push_str("&excludedUploaders[]=");
-					url.push_str(&group.read());
-				}
-			});
-		}
-	}
+	push_chapter_filters(&mut url);
 	let json = Request::new(&url, HttpMethod::Get).json_rl().as_object()?;
 	let total = json.get("total").as_int().unwrap_or(0);
 	let data = json.get("data").as_array()?;
-	let mut chapters: Vec<Chapter> = Vec::with_capacity(total.try_into().unwrap_or(0));
-	chapters.append(
-		&mut data
-			.filter_map(|chapter| match chapter.as_object() {
-				Ok(obj) => parser::parse_chapter(obj).ok(),
-				Err(_) => None,
-			})
-			.collect::<Vec<_>>(),
-	);
+	let mut chapters: Vec<(Chapter, Vec<String>)> =
+		Vec::with_capacity(total.try_into().unwrap_or(0));
+	chapters.append(&mut parse_chapters(data));
 
 	let mut offset = 500;
 	while offset < total {
@@ -362,40 +588,35 @@ fn get_chapter_list(id: String) -> Result<Vec<Chapter>> {
 
 		if let Ok(json) = json.as_object() {
 			let data = json.get("data").as_array()?;
-			chapters.append(
-				&mut data
-					.filter_map(|chapter| match chapter.as_object() {
-						Ok(obj) => parser::parse_chapter(obj).ok(),
-						Err(_) => None,
-					})
-					.collect::<Vec<_>>(),
-			);
+			chapters.append(&mut parse_chapters(data));
 		}
 		offset += 500;
 	}
-	Ok(chapters)
+
+	let collapse = defaults_get("collapseChapters")
+		.and_then(|v| v.as_bool())
+		.unwrap_or(false);
+	if collapse {
+		Ok(collapse_chapters(chapters))
+	} else {
+		Ok(chapters.into_iter().map(|(chapter, _)| chapter).collect())
+	}
 }
 
 #[get_page_list]
 fn get_page_list(_manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
-	let mut url = String::from("https://api.mangadex.org/at-home/server/") + &chapter_id;
-	if let Ok(port_value) = defaults_get("standardHttpsPort") {
-		if port_value.as_bool().unwrap_or(false) {
-			url.push_str("?forcePort443=true");
-		}
-	}
-	let json = Request::new(&url, HttpMethod::Get).json_rl().as_object()?;
+	let (base_url, chapter) = md_home::get_base_url(&chapter_id)?;
 
-	let chapter = json.get("chapter").as_object()?;
 	let data_saver = match defaults_get("dataSaver") {
 		Ok(data_saver) => data_saver.as_bool().unwrap_or(false),
 		Err(_) => false,
 	};
-	let data = chapter
+	let files = chapter
 		.get(if data_saver { "dataSaver" } else { "data" })
-		.as_array()?;
+		.as_array()?
+		.filter_map(|page| page.as_string().ok().map(|v| v.read()))
+		.collect::<Vec<_>>();
 
-	let base_url = json.get("baseUrl").as_string()?.read();
 	let hash = chapter.get("hash").as_string()?.read();
 	let path = if data_saver {
 		String::from("/data-saver/")
@@ -403,32 +624,35 @@ fn get_page_list(_manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 		String::from("/data/")
 	};
 
-	Ok(data
+	md_home::register_chapter(&hash, &chapter_id);
+
+	Ok(files
+		.iter()
 		.enumerate()
-		.filter_map(|(i, page)| match page.as_string() {
-			Ok(data) => {
-				let data = data.read();
-				let mut url = String::with_capacity(
-					base_url.len() + hash.len() + data.len() + path.len() + 1,
-				);
-				url.push_str(&base_url);
-				url.push_str(&path);
-				url.push_str(&hash);
-				url.push('/');
-				url.push_str(&data);
-
-				Some(Page {
-					index: i as i32,
-					url,
-					base64: String::new(),
-					text: String::new(),
-				})
+		.map(|(i, data)| {
+			let mut url =
+				String::with_capacity(base_url.len() + hash.len() + data.len() + path.len() + 1);
+			url.push_str(&base_url);
+			url.push_str(&path);
+			url.push_str(&hash);
+			url.push('/');
+			url.push_str(data);
+
+			Page {
+				index: i as i32,
+				url,
+				base64: String::new(),
+				text: String::new(),
 			}
-			Err(_) => None,
 		})
 		.collect::<Vec<_>>())
 }
 
+#[modify_image_request]
+fn modify_image_request(request: Request) {
+	md_home::modify_image_request(request);
+}
+
 #[handle_url]
 pub fn handle_url(url: String) -> Result<DeepLink> {
 	let url = &url[21..]; // remove "https://mangadex.org/"
@@ -477,7 +701,10 @@ pub fn handle_url(url: String) -> Result<DeepLink> {
 #[handle_notification]
 fn handle_notification(notification: String) {
 	match notification.as_str() {
-		"blockedGroups" | "blockedUploaders" => {
+		"login" => {
+			auth::login().ok();
+		}
+		"blockedGroups" | "blockedUploaders" | "preferredGroups" => {
 			if let Ok(groups) = defaults_get(&notification) {
 				if let Ok(groups_string) = groups.as_string() {
 					let mut arr = ArrayRef::new();
@@ -0,0 +1,162 @@
+//! MangaDex@Home node fallback.
+//!
+//! The host hands sources each image request before it's sent, but never its
+//! response. A page that failed is requested again when the reader retries
+//! it, so a repeat of a recent request swaps its node for a fresh one from
+//! `/at-home/server` for the rest of the chapter, or for the uploads server
+//! once no node works.
+//!
+//! MangaDex also asks clients to report deliveries to
+//! `api.mangadex.network/report`, with their size, duration and cache status.
+//! None of that can be observed without the response, so nothing is reported.
+use aidoku::{
+	error::Result,
+	prelude::format,
+	std::{
+		ObjectRef, String, ValueRef, Vec,
+		defaults::defaults_get,
+		net::{HttpMethod, Request},
+	},
+};
+
+use crate::helper::{SendRatelimited, current_date};
+
+/// Used when no at-home node can deliver the chapter.
+pub const FALLBACK_BASE_URL: &str = "https://uploads.mangadex.org";
+
+/// How soon a page has to be requested again for its node to be replaced, in
+/// seconds.
+const RETRY_WINDOW: f64 = 2.0 * 60.0;
+/// How many recent image requests, chapters and replaced nodes are kept.
+const RECENT_CAPACITY: usize = 64;
+const CHAPTER_CAPACITY: usize = 8;
+const NODE_CAPACITY: usize = 16;
+
+#[link(wasm_import_module = "net")]
+unsafe extern "C" {
+	#[link_name = "get_url"]
+	fn request_get_url(rd: i32) -> i32;
+	#[link_name = "set_url"]
+	fn request_set_url(rd: i32, value: *const u8, len: usize);
+}
+
+/// Image URLs handed to the host lately, and when.
+static mut RECENT: Vec<(String, f64)> = Vec::new();
+/// Chapter ids by chapter hash, to ask for a fresh node.
+static mut CHAPTERS: Vec<(String, String)> = Vec::new();
+/// Nodes that failed, and the base URL used instead.
+static mut REPLACED: Vec<(String, String)> = Vec::new();
+
+pub fn get_base_url(chapter_id: &str) -> Result<(String, ObjectRef)> {
+	let mut url = String::from("https://api.mangadex.org/at-home/server/") + chapter_id;
+	if let Ok(port_value) = defaults_get("standardHttpsPort")
+		&& port_value.as_bool().unwrap_or(false)
+	{
+		url.push_str("?forcePort443=true");
+	}
+	let json = Request::new(&url, HttpMethod::Get).json_rl().as_object()?;
+	let base_url = json.get("baseUrl").as_string()?.read();
+	Ok((base_url, json.get("chapter").as_object()?))
+}
+
+/// Remembers which chapter a hash belongs to, so that a failing node can be
+/// replaced while it's being read.
+pub fn register_chapter(hash: &str, chapter_id: &str) {
+	let chapters = unsafe { &mut *core::ptr::addr_of_mut!(CHAPTERS) };
+	chapters.retain(|(known, _)| known != hash);
+	if chapters.len() >= CHAPTER_CAPACITY {
+		chapters.remove(0);
+	}
+	chapters.push((String::from(hash), String::from(chapter_id)));
+}
+
+/// Splits an at-home image URL into its base URL and the path after it.
+fn split_url(url: &str) -> Option<(&str, &str)> {
+	let index = url.find("/data/").or_else(|| url.find("/data-saver/"))?;
+	Some(url.split_at(index))
+}
+
+/// The base URL to use for a node, following any replacements.
+fn resolve_node(node: &str) -> String {
+	let replaced = unsafe { &*core::ptr::addr_of!(REPLACED) };
+	let mut node = String::from(node);
+	for _ in 0..replaced.len() {
+		match replaced.iter().find(|(dead, _)| *dead == node) {
+			Some((_, next)) => node = next.clone(),
+			None => break,
+		}
+	}
+	node
+}
+
+/// Whether `url` was requested within the retry window, forgetting it.
+fn take_recent(url: &str, now: f64) -> bool {
+	let recent = unsafe { &mut *core::ptr::addr_of_mut!(RECENT) };
+	recent.retain(|(_, requested)| now - requested < RETRY_WINDOW);
+	match recent.iter().position(|(known, _)| known == url) {
+		Some(index) => {
+			recent.remove(index);
+			true
+		}
+		None => false,
+	}
+}
+
+fn remember(url: String, now: f64) {
+	let recent = unsafe { &mut *core::ptr::addr_of_mut!(RECENT) };
+	if recent.len() >= RECENT_CAPACITY {
+		recent.remove(0);
+	}
+	recent.push((url, now));
+}
+
+/// Replaces a node that failed with a fresh one for the chapter `path`
+/// belongs to, or with the uploads server.
+fn replace_node(dead: &str, path: &str) {
+	let hash = path.split('/').nth(2).unwrap_or_default();
+	let chapters = unsafe { &*core::ptr::addr_of!(CHAPTERS) };
+	let replaced = unsafe { &mut *core::ptr::addr_of_mut!(REPLACED) };
+
+	let fresh = chapters
+		.iter()
+		.find(|(known, _)| known == hash)
+		.and_then(|(_, chapter_id)| get_base_url(chapter_id).ok())
+		.map(|(base_url, _)| base_url)
+		.filter(|base_url| base_url != dead && !replaced.iter().any(|(known, _)| known == base_url))
+		.unwrap_or_else(|| String::from(FALLBACK_BASE_URL));
+
+	if replaced.len() >= NODE_CAPACITY {
+		replaced.remove(0);
+	}
+	replaced.push((String::from(dead), fresh));
+}
+
+pub fn modify_image_request(request: Request) {
+	let Ok(original) = ValueRef::new(unsafe { request_get_url(request.0) })
+		.as_string()
+		.map(|v| v.read())
+	else {
+		return;
+	};
+	let Some((node, path)) = split_url(&original) else {
+		return;
+	};
+	let now = current_date();
+
+	// The URL this page went to last time, with the nodes known to be dead
+	// replaced.
+	let current_node = resolve_node(node);
+	let current = format!("{current_node}{path}");
+	if current != original {
+		take_recent(&original, now);
+	}
+	if take_recent(&current, now) && current_node != FALLBACK_BASE_URL {
+		replace_node(&current_node, path);
+	}
+
+	let url = format!("{}{path}", resolve_node(node));
+	if url != original {
+		unsafe { request_set_url(request.0, url.as_ptr(), url.len()) };
+	}
+	remember(url, now);
+}
@@ -313,3 +313,26 @@ pub fn parse_chapter(chapter_object: ObjectRef) -> Result<Chapter> {
 		lang,
 	})
 }
+
+// Parse the ids and names of a chapter's scanlation groups
+pub fn parse_chapter_groups(chapter_object: &ObjectRef) -> Vec<String> {
+	let mut groups: Vec<String> = Vec::new();
+	if let Ok(relationships) = chapter_object.get("relationships").as_array() {
+		for relationship in relationships {
+			if let Ok(relationship_object) = relationship.as_object()
+				&& let Ok(relation_type) = relationship_object.get("type").as_string()
+				&& relation_type.read() == "scanlation_group"
+			{
+				if let Ok(id) = relationship_object.get("id").as_string() {
+					groups.push(id.read());
+				}
+				if let Ok(attribs) = relationship_object.get("attributes").as_object()
+					&& let Ok(name) = attribs.get("name").as_string()
+				{
+					groups.push(name.read());
+				}
+			}
+		}
+	}
+	groups
+}
@@ -1,7 +1,22 @@
 use aidoku::{
 	error::Result,
-	std::{ArrayRef, String, Vec},
+	prelude::format,
+	std::{current_date, defaults::defaults_get, ArrayRef, ObjectRef, String, Vec},
+	Manga, MangaContentRating, MangaViewer,
 };
+use alloc::string::ToString;
+use source_support::TtlCache;
+
+/// How many tag ids looked up by name are kept. Tag ids never change, so they
+/// stay cached until the source is reloaded.
+const TAG_ID_CAPACITY: usize = 32;
+
+/// Tag ids by `{type}:{lowercased name}`.
+static mut TAG_IDS: TtlCache<i64> = TtlCache::new(f64::INFINITY, TAG_ID_CAPACITY);
+
+fn tag_key(tag_type: &str, name: &str) -> String {
+	format!("{tag_type}:{}", name.to_lowercase())
+}
 
 pub fn get_details_url(id: String) -> String {
 	let mut string = String::from("https://nhentai.net/api/gallery/");
@@ -54,3 +69,94 @@ pub fn find_media_server(big_string: &str) -> Option<&str> {
 
 	None
 }
+
+/// Returns the comma separated tags of the "blockedTags" setting, lowercased
+pub fn get_blocked_tags() -> Vec<String> {
+	defaults_get("blockedTags")
+		.and_then(|value| value.as_string())
+		.map(|value| value.read())
+		.unwrap_or_default()
+		.split(',')
+		.map(|tag| tag.trim().to_lowercase())
+		.filter(|tag| !tag.is_empty())
+		.collect()
+}
+
+pub fn cached_tag_id(tag_type: &str, name: &str) -> Option<i64> {
+	let tag_ids = unsafe { &mut *core::ptr::addr_of_mut!(TAG_IDS) };
+	tag_ids
+		.get(&tag_key(tag_type, name), current_date())
+		.copied()
+}
+
+pub fn cache_tag_id(tag_type: &str, name: &str, id: i64) {
+	let tag_ids = unsafe { &mut *core::ptr::addr_of_mut!(TAG_IDS) };
+	tag_ids.insert(tag_key(tag_type, name), id, current_date());
+}
+
+/// Whether any of `tags`, as names, is blocked.
+pub fn has_blocked_tag_name(tags: &[String], blocked_tags: &[String]) -> bool {
+	tags.iter()
+		.any(|tag| blocked_tags.contains(&tag.to_lowercase()))
+}
+
+/// Returns the tag name or id a tagged listing browses, from its setting
+pub fn get_listing_tag(tag_type: &str) -> Option<String> {
+	defaults_get(&format!("{tag_type}Listing"))
+		.and_then(|value| value.as_string())
+		.map(|value| value.read().trim().to_string())
+		.ok()
+		.filter(|tag| !tag.is_empty())
+}
+
+pub fn has_blocked_tag(gallery: &ObjectRef, blocked_tags: &[String]) -> bool {
+	if blocked_tags.is_empty() {
+		return false;
+	}
+	let Ok(tags) = gallery.get("tags").as_array() else {
+		return false;
+	};
+	tags.filter_map(|tag| tag.as_object().ok())
+		.filter(|tag| {
+			tag.get("type")
+				.as_string()
+				.map(|tag_type| tag_type.read() == "tag")
+				.unwrap_or(false)
+		})
+		.filter_map(|tag| tag.get("name").as_string().ok())
+		.any(|name| blocked_tags.contains(&name.read().to_lowercase()))
+}
+
+/// Parses a gallery object from the API, as returned by the tagged and related
+/// gallery endpoints
+pub fn parse_gallery(gallery: &ObjectRef) -> Result<Manga> {
+	let id = match gallery.get("id").as_int() {
+		Ok(id) => id.to_string(),
+		Err(_) => gallery.get("id").as_string()?.read(),
+	};
+	let media_id = gallery.get("media_id").as_string()?.read();
+
+	let titles = gallery.get("title").as_object()?;
+	let title = ["english", "pretty"]
+		.iter()
+		.filter_map(|key| titles.get(key).as_string().ok())
+		.map(|title| title.read())
+		.find(|title| !title.is_empty())
+		.unwrap_or_default();
+
+	let thumbnail = gallery
+		.get("images")
+		.as_object()?
+		.get("thumbnail")
+		.as_object()?;
+	let file_type = get_file_type(thumbnail.get("t").as_string()?.read());
+
+	Ok(Manga {
+		id,
+		cover: format!("https://t.nhentai.net/galleries/{media_id}/thumb.{file_type}"),
+		title,
+		nsfw: MangaContentRating::Nsfw,
+		viewer: MangaViewer::Rtl,
+		..Default::default()
+	})
+}
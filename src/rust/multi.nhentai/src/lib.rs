@@ -1,9 +1,9 @@
 #![no_std]
 use aidoku::{
 	error::Result, helpers::uri::encode_uri_component, prelude::*, std::defaults::defaults_get,
-	std::net::HttpMethod, std::net::Request, std::ObjectRef, std::String, std::Vec, Chapter,
-	DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult, MangaStatus,
-	MangaViewer, Page,
+	std::net::HttpMethod, std::net::Request, std::ArrayRef, std::ObjectRef, std::String, std::Vec,
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 extern crate alloc;
 use alloc::{string::ToString, vec};
@@ -44,6 +44,9 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		match filter.kind {
 			FilterType::Title => {
 				let title = filter.value.as_string()?.read();
+				if let Some(id) = title.trim().strip_prefix("related:") {
+					return get_related_galleries(id.trim(), page);
+				}
 				if helper::is_number(&title) {
 					sauce_code = Some(title.clone());
 				} else {
@@ -79,9 +82,14 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		}
 	}
 
+	let blocked_tags = helper::get_blocked_tags();
+
 	// if the user searches a code, just return the manga for that id
 	if let Some(sauce_code) = sauce_code {
 		let manga = get_manga_details(sauce_code)?;
+		if helper::has_blocked_tag_name(&manga.categories, &blocked_tags) {
+			return Ok(MangaPageResult::default());
+		}
 
 		return Ok(MangaPageResult {
 			manga: vec![manga],
@@ -89,6 +97,10 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 		});
 	}
 
+	for tag in blocked_tags {
+		query.push_str(&format!(" -tag:\"{tag}\""));
+	}
+
 	let url = format!(
 		"{BASE_URL}/search/?q={}&page={page}&sort={sort}",
 		encode_uri_component(query),
@@ -128,8 +140,107 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 	Ok(MangaPageResult { manga, has_more })
 }
 
+/// Finds the id of a tag by searching for it, since the tagged galleries API
+/// only takes ids. Galleries or tags missing a field are skipped.
+fn get_tag_id(tag_type: &str, name: &str) -> Result<Option<i64>> {
+	if helper::is_number(name) {
+		return Ok(name.parse::<i64>().ok());
+	}
+	if let Some(id) = helper::cached_tag_id(tag_type, name) {
+		return Ok(Some(id));
+	}
+
+	let url = format!(
+		"{BASE_URL}/api/galleries/search?query={}",
+		encode_uri_component(format!("{tag_type}:\"{name}\""))
+	);
+	let json = Request::new(&url, HttpMethod::Get)
+		.header("User-Agent", USER_AGENT)
+		.json()?
+		.as_object()?;
+
+	let id = json
+		.get("result")
+		.as_array()?
+		.filter_map(|gallery| gallery.as_object().ok())
+		.filter_map(|gallery| gallery.get("tags").as_array().ok())
+		.flatten()
+		.filter_map(|tag| tag.as_object().ok())
+		.find(|tag| {
+			let field = |key: &str| tag.get(key).as_string().map(|value| value.read());
+			field("type").is_ok_and(|value| value == tag_type)
+				&& field("name").is_ok_and(|value| value.eq_ignore_ascii_case(name))
+		})
+		.and_then(|tag| tag.get("id").as_int().ok());
+
+	if let Some(id) = id {
+		helper::cache_tag_id(tag_type, name, id);
+	}
+	Ok(id)
+}
+
+fn parse_galleries(galleries: ArrayRef) -> Vec<Manga> {
+	let blocked_tags = helper::get_blocked_tags();
+	galleries
+		.filter_map(|gallery| gallery.as_object().ok())
+		.filter(|gallery| !helper::has_blocked_tag(gallery, &blocked_tags))
+		.filter_map(|gallery| helper::parse_gallery(&gallery).ok())
+		.collect()
+}
+
+/// Galleries with the artist, group, parody or character set for the listing
+/// in settings
+fn get_tagged_listing(tag_type: &str, page: i32) -> Result<MangaPageResult> {
+	let Some(tag) = helper::get_listing_tag(tag_type) else {
+		return Ok(MangaPageResult::default());
+	};
+	let Some(tag_id) = get_tag_id(tag_type, &tag)? else {
+		return Ok(MangaPageResult::default());
+	};
+
+	let url = format!("{BASE_URL}/api/galleries/tagged?tag_id={tag_id}&page={page}&sort=date");
+	let json = Request::new(&url, HttpMethod::Get)
+		.header("User-Agent", USER_AGENT)
+		.json()?
+		.as_object()?;
+
+	let manga = parse_galleries(json.get("result").as_array()?);
+	let num_pages = json.get("num_pages").as_int().unwrap_or(0);
+
+	Ok(MangaPageResult {
+		manga,
+		has_more: i64::from(page) < num_pages,
+	})
+}
+
+/// Galleries related to gallery `id`, searched for with `related:{id}`
+fn get_related_galleries(id: &str, page: i32) -> Result<MangaPageResult> {
+	if page > 1 || id.is_empty() || !helper::is_number(id) {
+		return Ok(MangaPageResult::default());
+	}
+
+	let url = format!("{BASE_URL}/api/gallery/{id}/related");
+	let json = Request::new(&url, HttpMethod::Get)
+		.header("User-Agent", USER_AGENT)
+		.json()?
+		.as_object()?;
+
+	Ok(MangaPageResult {
+		manga: parse_galleries(json.get("result").as_array()?),
+		has_more: false,
+	})
+}
+
 #[get_manga_listing]
 fn get_manga_listing(listing: Listing, page: i32) -> Result<MangaPageResult> {
+	match listing.name.as_str() {
+		"Artist" => return get_tagged_listing("artist", page),
+		"Group" => return get_tagged_listing("group", page),
+		"Parody" => return get_tagged_listing("parody", page),
+		"Character" => return get_tagged_listing("character", page),
+		_ => {}
+	}
+
 	let mut filters: Vec<Filter> = Vec::new();
 	let mut selection = ObjectRef::new();
 
@@ -181,7 +292,7 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		.join(", ");
 	let artist = author.clone();
 
-	let description = format!("#{id}");
+	let description = format!("#{id}\n\nSearch related:{id} for related galleries.");
 
 	let categories = html
 		.select("#tags div:contains(Tags:) a")
@@ -28,59 +28,6 @@ pub fn get_manga_id(url: &str) -> String {
 		.replace(".html", "")
 }
 
-pub fn extract_f32_from_string(chapter_title: &str, name: &str) -> f32 {
-	if is_string_numeric(String::from(chapter_title)) {
-		chapter_title.parse::<f32>().unwrap_or(0.0)
-	} else if chapter_title.contains("vol")
-		|| chapter_title.contains("Том")
-		|| chapter_title.contains("Vol")
-	{
-		let title = chapter_title.to_lowercase();
-		let text: String = if title.contains("volumen") {
-			title
-				.replace(&title[..title.find("volumen").unwrap() + 8], "")
-				.replace("ch.", "")
-		} else if title.contains("vol") {
-			title
-				.replace(&title[..title.find("vol.").unwrap() + 7], "")
-				.replace("ch.", "")
-		} else {
-			title.replace(
-				&chapter_title[..chapter_title.find("Том").unwrap_or(0) + 8],
-				"",
-			)
-		};
-
-		text.chars()
-			.filter(|a| (*a >= '0' && *a <= '9') || *a == ' ' || *a == '.')
-			.collect::<String>()
-			.split(' ')
-			.collect::<Vec<&str>>()
-			.into_iter()
-			.map(|a| a.parse::<f32>().unwrap_or(0.0))
-			.find(|a| *a > 0.0)
-			.unwrap_or(0.0)
-	} else {
-		chapter_title
-			.to_ascii_lowercase()
-			.replace(&name.to_ascii_lowercase(), "")
-			.replace("ch.", "")
-			.replace("Ch.", "")
-			.split('-')
-			.last()
-			.unwrap()
-			.chars()
-			.filter(|a| (*a >= '0' && *a <= '9') || *a == ' ' || *a == '.')
-			.collect::<String>()
-			.split(' ')
-			.collect::<Vec<&str>>()
-			.into_iter()
-			.map(|a| a.parse::<f32>().unwrap_or(0.0))
-			.find(|a| *a > 0.0)
-			.unwrap_or(0.0)
-	}
-}
-
 pub fn status_from_string(status: String) -> MangaStatus {
 	return match status.as_str() {
 		"Ongoing"
@@ -103,15 +50,6 @@ pub fn status_from_string(status: String) -> MangaStatus {
 	};
 }
 
-fn is_string_numeric(str: String) -> bool {
-	for c in str.chars() {
-		if !c.is_numeric() {
-			return false;
-		}
-	}
-	true
-}
-
 pub fn get_date(node: Node, date_format: &str, locale: &str) -> f64 {
 	let time_en = ["min", "minute", "minutes", "hour", "hours"];
 	let time_es = ["minuto", "minutos", "hora", "horas"];
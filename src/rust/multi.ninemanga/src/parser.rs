@@ -5,6 +5,7 @@ use aidoku::{
 };
 
 use crate::helper::*;
+use source_support::parse_chapter_number;
 
 pub struct NineMangaSource {
 	pub base_url: String,
@@ -226,7 +227,7 @@ impl NineMangaSource {
 
 			let url = chapter_node.select("a.chapter_list_a").attr("href").read();
 			let chapter_id = get_manga_id(&url);
-			let chapter_number = extract_f32_from_string(&raw_title, &name);
+			let chapter_number = parse_chapter_number(&name, &raw_title).unwrap_or(0.0);
 			let date_updated = get_date(chapter_node, self.date_format, self.date_locale);
 
 			chapters.push(Chapter {
@@ -3,6 +3,7 @@ use aidoku::{
 	prelude::format,
 	std::defaults::defaults_get,
 	std::net::Request,
+	std::ObjectRef,
 	std::{String, Vec},
 	Filter, FilterType,
 };
@@ -33,6 +34,16 @@ pub fn get_lang_code() -> Option<String> {
 	None
 }
 
+/// Returns whether an episode from the episode list API is locked behind
+/// Daily Pass, Fast Pass or a paywall. The API is undocumented, so these are
+/// the flags it has been seen to use; `parse_page_list` only explains an
+/// episode without images as locked when one of them is set.
+pub fn is_locked_episode(episode: &ObjectRef) -> bool {
+	["locked", "dailyPass", "fastPass", "paid"]
+		.iter()
+		.any(|key| episode.get(key).as_bool().unwrap_or(false))
+}
+
 /// Returns a useragent string
 pub fn get_user_agent(mobile: bool) -> String {
 	if mobile {
@@ -88,7 +99,7 @@ pub fn get_manga_id(url: &str) -> String {
 		};
 
 		if !split_url.is_empty() {
-			let manga_id = split_url[1];
+			let manga_id = split_url[1].split('&').next().unwrap_or_default();
 
 			// Append "canvas" to the id if it's a canvas title
 			if url.contains("canvas") {
@@ -130,7 +141,7 @@ pub fn get_chapter_id(url: &str) -> String {
 		};
 
 		if !split_url.is_empty() {
-			let chapter_id = split_url[1];
+			let chapter_id = split_url[1].split('&').next().unwrap_or_default();
 
 			// Append "canvas" to the id if it's a canvas title
 			if url.contains("canvas") {
@@ -208,14 +219,14 @@ pub fn get_chapter_url(chapter_id: String, manga_id: String, base_url: String) -
 }
 
 /// Returns the search status as a boolean and the search string if there is one
-pub fn check_for_search(filters: Vec<Filter>) -> (String, bool) {
+pub fn check_for_search(filters: &[Filter]) -> (String, bool) {
 	let mut search_string = String::new();
 	let mut search = false;
 
 	for filter in filters {
 		match filter.kind {
 			FilterType::Title => {
-				if let Ok(filter_value) = filter.value.as_string() {
+				if let Ok(filter_value) = filter.value.clone().as_string() {
 					search_string.push_str(
 						encode_uri_component(filter_value.read().to_lowercase()).as_str(),
 					);
@@ -228,3 +239,90 @@ pub fn check_for_search(filters: Vec<Filter>) -> (String, bool) {
 	}
 	(search_string, search)
 }
+
+/// Genre slugs of the "Genre" filter, after "All", with the languages whose
+/// site has the genre, or `None` if every site has it. The slugs themselves
+/// are the same in every language.
+const GENRES: [(&str, Option<&[&str]>); 16] = [
+	("action", None),
+	("comedy", None),
+	("drama", None),
+	("fantasy", None),
+	("heartwarming", None),
+	("historical", None),
+	("horror", None),
+	("mystery", None),
+	("romance", None),
+	("sf", None),
+	("slice_of_life", None),
+	("sports", None),
+	("super_hero", None),
+	("supernatural", None),
+	("thriller", None),
+	// "Informative" is only published in English
+	("tiptoon", Some(&["en"])),
+];
+
+/// The genre picked in the "Genre" filter
+pub enum Genre {
+	All,
+	Slug(&'static str),
+	/// The site for the selected language doesn't have the genre
+	Unavailable,
+}
+
+/// Sort orders of the "Sort" filter
+const SORT_ORDERS: [&str; 3] = ["MANA", "LIKEIT", "UPDATE"];
+
+/// Returns the genre selected in the filters, if it's on the selected
+/// language's site
+pub fn get_genre(filters: &[Filter]) -> Genre {
+	let genre = filters
+		.iter()
+		.find(|filter| matches!(filter.kind, FilterType::Select) && filter.name == "Genre")
+		.and_then(|filter| filter.value.as_int().ok())
+		.and_then(|index| GENRES.get(usize::try_from(index - 1).ok()?));
+	let Some((slug, languages)) = genre else {
+		return Genre::All;
+	};
+	let lang = get_lang_code().unwrap_or(String::from("en"));
+	match languages {
+		Some(languages) if !languages.contains(&lang.as_str()) => Genre::Unavailable,
+		_ => Genre::Slug(slug),
+	}
+}
+
+/// Returns the sort order selected in the filters, by popularity by default
+pub fn get_sort_order(filters: &[Filter]) -> &'static str {
+	filters
+		.iter()
+		.find(|filter| matches!(filter.kind, FilterType::Select) && filter.name == "Sort")
+		.and_then(|filter| filter.value.as_int().ok())
+		.and_then(|index| SORT_ORDERS.get(usize::try_from(index).ok()?).copied())
+		.unwrap_or(SORT_ORDERS[0])
+}
+
+/// Returns the originals schedule page of a weekday or "Completed" listing
+pub fn get_schedule_day(listing: &str) -> Option<&'static str> {
+	match listing {
+		"Monday" => Some("monday"),
+		"Tuesday" => Some("tuesday"),
+		"Wednesday" => Some("wednesday"),
+		"Thursday" => Some("thursday"),
+		"Friday" => Some("friday"),
+		"Saturday" => Some("saturday"),
+		"Sunday" => Some("sunday"),
+		"Completed" => Some("complete"),
+		_ => None,
+	}
+}
+
+/// Returns the page number in a paginator link, or 0 if there isn't one
+pub fn get_page_number(url: &str) -> i32 {
+	// Example Url: /en/search?keyword=tower&page=3
+	url.split("page=")
+		.nth(1)
+		.and_then(|page| page.split('&').next())
+		.and_then(|page| page.parse::<i32>().ok())
+		.unwrap_or(0)
+}
@@ -13,8 +13,8 @@ mod helper;
 mod parser;
 
 #[get_manga_list]
-fn get_manga_list(filters: Vec<Filter>, _page: i32) -> Result<MangaPageResult> {
-	parser::parse_manga_list(get_base_url(false), filters)
+fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
+	parser::parse_manga_list(get_base_url(false), filters, page)
 }
 
 #[get_manga_listing]
@@ -1,37 +1,19 @@
 use aidoku::{
-	error::Result,
+	error::{AidokuError, AidokuErrorKind, NodeError, Result},
 	prelude::*,
-	std::{defaults::defaults_get, net::Request, String, Vec},
+	std::{defaults::defaults_get, html::Node, net::Request, ArrayRef, String, Vec},
 	Chapter, DeepLink, Filter, Listing, Manga, MangaPageResult, MangaStatus, MangaViewer, Page,
 };
 
 use crate::helper::*;
 
-pub fn parse_manga_list(base_url: String, filters: Vec<Filter>) -> Result<MangaPageResult> {
-	let (query, search) = check_for_search(filters);
-
-	let url = {
-		if search {
-			format!("{}/search?keyword={}", base_url, query)
-		} else {
-			// This is to handle parse_manga_listing as it passes in full a url,
-			// not just the base
-			if base_url.contains("genre") {
-				base_url
-			} else {
-				format!("{}/genre", base_url)
-			}
-		}
-	};
-
-	let html = request(&url, false).html()?;
+/// Title cards on search, genre and originals schedule pages
+const TITLE_LIST_SELECTOR: &str = "#content div.webtoon_list_wrap ul > li > a";
 
+fn parse_title_list(html: &Node) -> Vec<Manga> {
 	let mut mangas: Vec<Manga> = Vec::new();
 
-	for manga in html
-		.select("#content > div.webtoon_list_wrap ul > li > a")
-		.array()
-	{
+	for manga in html.select(TITLE_LIST_SELECTOR).array() {
 		let manga_node = manga.as_node().expect("Failed to get manga node");
 		let url = manga_node.attr("href").read();
 		let id = get_manga_id(&url);
@@ -48,8 +30,66 @@ pub fn parse_manga_list(base_url: String, filters: Vec<Filter>) -> Result<MangaP
 		});
 	}
 
+	mangas
+}
+
+pub fn parse_manga_list(
+	base_url: String,
+	filters: Vec<Filter>,
+	page: i32,
+) -> Result<MangaPageResult> {
+	let (query, search) = check_for_search(&filters);
+
+	if search {
+		return parse_search_list(base_url, query, page);
+	}
+
+	let sort_order = get_sort_order(&filters);
+	let url = match get_genre(&filters) {
+		Genre::All => format!("{}/genre?sortOrder={}", base_url, sort_order),
+		Genre::Slug(genre) => format!("{}/genres/{}?sortOrder={}", base_url, genre, sort_order),
+		Genre::Unavailable => {
+			return Ok(MangaPageResult {
+				manga: Vec::new(),
+				has_more: false,
+			})
+		}
+	};
+
+	parse_unpaged_list(url, page)
+}
+
+pub fn parse_search_list(base_url: String, query: String, page: i32) -> Result<MangaPageResult> {
+	let url = format!("{}/search?keyword={}&page={}", base_url, query, page);
+
+	let html = request(&url, false).html()?;
+
+	// The paginator links a handful of pages around the current one
+	let has_more = html
+		.select("#content div.paginate > a")
+		.array()
+		.filter_map(|link| link.as_node().ok())
+		.any(|link| get_page_number(&link.attr("href").read()) > page);
+
 	Ok(MangaPageResult {
-		manga: mangas,
+		manga: parse_title_list(&html),
+		has_more,
+	})
+}
+
+/// Genre and schedule pages list all of their titles at once, so only the
+/// first page has results
+pub fn parse_unpaged_list(url: String, page: i32) -> Result<MangaPageResult> {
+	if page > 1 {
+		return Ok(MangaPageResult {
+			..Default::default()
+		});
+	}
+
+	let html = request(&url, false).html()?;
+
+	Ok(MangaPageResult {
+		manga: parse_title_list(&html),
 		has_more: false,
 	})
 }
@@ -107,6 +147,10 @@ pub fn parse_manga_listing(
 	listing: Listing,
 	page: i32,
 ) -> Result<MangaPageResult> {
+	if let Some(day) = get_schedule_day(&listing.name) {
+		return parse_unpaged_list(format!("{}/originals/{}", base_url, day), page);
+	}
+
 	let url = {
 		match listing.name.as_str() {
 			"Latest" => format!("{}/genre?sortOrder=UPDATE", base_url),
@@ -124,7 +168,7 @@ pub fn parse_manga_listing(
 	if url.contains("canvas") {
 		parse_canvas_list(url, page)
 	} else {
-		parse_manga_list(url, Vec::new())
+		parse_unpaged_list(url, page)
 	}
 }
 
@@ -217,7 +261,8 @@ pub fn parse_manga_details(base_url: String, manga_id: String) -> Result<Manga>
 	})
 }
 
-pub fn parse_chapter_list(manga_id: String) -> Result<Vec<Chapter>> {
+/// The title's episodes from the mobile API, oldest first.
+fn get_episode_list(manga_id: &str) -> Result<ArrayRef> {
 	let base_url = get_base_url_no_lang(true);
 	let api_url = if let Some(canvas_id) = manga_id.strip_suffix("-canvas") {
 		format!(
@@ -232,21 +277,33 @@ pub fn parse_chapter_list(manga_id: String) -> Result<Vec<Chapter>> {
 	};
 
 	let json = request(&api_url, true).json()?;
-	let episode_list = json
-		.as_object()?
+	json.as_object()?
 		.get("result")
 		.as_object()?
 		.get("episodeList")
-		.as_array()?;
+		.as_array()
+}
+
+pub fn parse_chapter_list(manga_id: String) -> Result<Vec<Chapter>> {
+	let base_url = get_base_url_no_lang(true);
+	let episode_list = get_episode_list(&manga_id)?;
 
 	let lang = get_lang_code().unwrap_or(String::from("en"));
 
+	let show_locked = defaults_get("lockedEpisodes")
+		.and_then(|value| value.as_bool())
+		.unwrap_or(true);
+
 	let mut chapters: Vec<Chapter> = Vec::new();
 
 	for episode in episode_list.rev() {
 		let Ok(object) = episode.as_object() else {
 			continue;
 		};
+		let locked = is_locked_episode(&object);
+		if locked && !show_locked {
+			continue;
+		}
 		let url = format!(
 			"{}{}",
 			base_url,
@@ -325,7 +382,12 @@ pub fn parse_chapter_list(manga_id: String) -> Result<Vec<Chapter>> {
 				title.remove(0);
 			}
 
-			title.join(" ")
+			let title = title.join(" ");
+			if locked {
+				format!("🔒 {}", title)
+			} else {
+				title
+			}
 		};
 
 		let chapter = object.get("episodeNo").as_float().unwrap_or(-1.0) as f32;
@@ -350,12 +412,14 @@ pub fn parse_chapter_list(manga_id: String) -> Result<Vec<Chapter>> {
 	Ok(chapters)
 }
 
+const LOCKED_EPISODE_MESSAGE: &str = "This episode has no images. It's probably locked behind Daily Pass, Fast Pass or coins; unlock it on WEBTOON, then reload it here.\n\n";
+
 pub fn parse_page_list(
 	base_url: String,
 	manga_id: String,
 	chapter_id: String,
 ) -> Result<Vec<Page>> {
-	let url = get_chapter_url(chapter_id, manga_id, base_url);
+	let url = get_chapter_url(chapter_id.clone(), manga_id.clone(), base_url);
 
 	let html = request(&url, false).html()?;
 
@@ -381,19 +445,65 @@ pub fn parse_page_list(
 		});
 	}
 
+	// Locked episodes load a purchase or Daily Pass prompt instead of images.
+	// Errors can't carry a message, so the reader is told on a text page. Any
+	// other episode without images failed to parse.
+	if pages.is_empty() {
+		if !is_episode_locked(&manga_id, &chapter_id)? {
+			return Err(AidokuError {
+				reason: AidokuErrorKind::NodeError(NodeError::ParseError),
+			});
+		}
+		pages.push(Page {
+			index: 0,
+			text: format!("{}{}", LOCKED_EPISODE_MESSAGE, url),
+			..Default::default()
+		});
+	}
+
 	Ok(pages)
 }
 
+/// Whether the episode list flags the episode as locked.
+fn is_episode_locked(manga_id: &str, chapter_id: &str) -> Result<bool> {
+	Ok(get_episode_list(manga_id)?.any(|episode| {
+		episode.as_object().is_ok_and(|episode| {
+			is_locked_episode(&episode)
+				&& episode
+					.get("viewerLink")
+					.as_string()
+					.is_ok_and(|link| get_chapter_id(&link.read()) == chapter_id)
+		})
+	}))
+}
+
 pub fn modify_image_request(base_url: String, request: Request) {
 	request.header("Referer", &base_url);
 }
 
 pub fn handle_url(base_url: String, url: String) -> Result<DeepLink> {
 	let manga_id = get_manga_id(&url);
+	let chapter_id = get_chapter_id(&url);
 	let parsed = parse_manga_details(base_url, manga_id);
 
+	let chapter = if chapter_id.is_empty() {
+		None
+	} else {
+		let chapter = chapter_id
+			.trim_end_matches("-canvas")
+			.parse::<f32>()
+			.unwrap_or(-1.0);
+		Some(Chapter {
+			id: chapter_id,
+			chapter,
+			url,
+			lang: get_lang_code().unwrap_or(String::from("en")),
+			..Default::default()
+		})
+	};
+
 	Ok(DeepLink {
 		manga: parsed.ok(),
-		..Default::default()
+		chapter,
 	})
 }
@@ -0,0 +1,49 @@
+//! Explanations from explainxkcd, the wiki that explains every xkcd comic.
+//! Its pages are named after comic numbers, so only translations that keep
+//! xkcd's numbering can link to them.
+use aidoku::{
+	prelude::format,
+	std::{
+		defaults::defaults_get,
+		html::Node,
+		net::{HttpMethod, Request},
+		String, Vec,
+	},
+};
+
+pub fn enabled() -> bool {
+	defaults_get("explanation")
+		.and_then(|v| v.as_bool())
+		.unwrap_or(false)
+}
+
+/// The paragraphs of the "Explanation" section for comic `number`, the first
+/// section of every comic's page.
+pub fn explanation(number: &str) -> Option<String> {
+	let url = format!(
+		"https://www.explainxkcd.com/wiki/api.php?action=parse&format=json&formatversion=2&redirects=1&prop=text&section=1&page={number}"
+	);
+	let html = Request::new(url, HttpMethod::Get)
+		.json()
+		.and_then(|json| json.as_object())
+		.and_then(|json| json.get("parse").as_object())
+		.and_then(|parse| parse.get("text").as_string())
+		.ok()?
+		.read();
+	let node = Node::new_fragment(html.as_bytes()).ok()?;
+	if node.select("#Explanation").array().is_empty() {
+		return None;
+	}
+	let paragraphs = node
+		.select(".mw-parser-output > p, .mw-parser-output > dl > dd, .mw-parser-output > ul > li")
+		.array()
+		.filter_map(|elem| elem.as_node().ok())
+		.map(|node| node.text().read())
+		.filter(|text| !text.trim().is_empty())
+		.collect::<Vec<_>>();
+	if paragraphs.is_empty() {
+		None
+	} else {
+		Some(paragraphs.join("\n\n"))
+	}
+}
@@ -1,35 +1,18 @@
-use super::THUMBNAIL_URL;
+use super::Language;
 use aidoku::{
 	error::Result,
-	prelude::format,
 	std::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Manga, MangaContentRating, MangaStatus, MangaViewer, Page,
+	Chapter,
 };
 
-pub fn comic_info() -> Manga {
-	Manga {
-		id: String::from("multi.xkcd.es"),
-		cover: String::from(THUMBNAIL_URL),
-		title: String::from("xkcd en español"),
-		author: String::from("Randall Munroe"),
-		artist: String::from("Randall Munroe"),
-		description: String::from("Un webcómic sobre romance, sarcasmo, mates y lenguaje."),
-		url: String::from("https://es.xkcd.com"),
-		categories: Vec::new(),
-		status: MangaStatus::Ongoing,
-		nsfw: MangaContentRating::Safe,
-		viewer: MangaViewer::Ltr,
-	}
-}
-
-pub fn get_chapter_list() -> Result<Vec<Chapter>> {
-	let html = Request::new("https://es.xkcd.com/archive", HttpMethod::Get).html()?;
-	let count = html.select("#archive-ul > ul > li > a").array().len();
+pub fn get_chapter_list(lang: &Language) -> Result<Vec<Chapter>> {
+	let html = Request::new(lang.archive_url(), HttpMethod::Get).html()?;
+	let count = html.select(lang.archive_links).array().len();
 	Ok(html
-		.select("#archive-ul > ul > li > a")
+		.select(lang.archive_links)
 		.array()
 		.enumerate()
 		.filter_map(|(idx, elem)| {
@@ -59,21 +42,10 @@ pub fn get_chapter_list() -> Result<Vec<Chapter>> {
 						date_updated: -1.0,
 						scanlator: String::new(),
 						url,
-						lang: String::from("es"),
+						lang: String::from(lang.code),
 					}
 				})
 				.ok()
 		})
 		.collect::<Vec<_>>())
 }
-
-pub fn get_page_list(id: String) -> Result<Vec<Page>> {
-	super::get_page_list(
-        format!("https://es.xkcd.com/strips/{id}/"),
-        String::from("#middleContent .strip"),
-        // DeepL translated, sorry.
-        false,
-        format!("Para experimentar la versión interactiva de este cómic,\nábralo en un navegador: https://es.xkcd.com/strips/{id}/"),
-        super::ImageVariant::Latin,
-    )
-}
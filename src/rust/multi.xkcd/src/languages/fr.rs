@@ -1,43 +1,22 @@
-use super::{word_wrap, ToImageUrl, THUMBNAIL_URL};
-use crate::helper::extract_f32_from_string;
+use super::{alt_text_page, Language};
 use aidoku::{
 	error::Result,
-	prelude::*,
 	std::{
 		html::Node,
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Manga, MangaContentRating, MangaStatus, MangaViewer, Page,
+	Chapter, Page,
 };
 use alloc::vec;
+use source_support::first_f32_from_string;
 
-pub fn comic_info() -> Manga {
-	Manga {
-		id: String::from("multi.xkcd.fr"),
-		cover: String::from(THUMBNAIL_URL),
-		title: String::from("xkcd en français"),
-		author: String::from("Randall Munroe"),
-		artist: String::from("Randall Munroe"),
-		description: String::from(
-			"Un webcomic sarcastique qui parle de romance, de maths et de langage.",
-		),
-		url: String::from("https://xkcd.lapin.org"),
-		categories: Vec::new(),
-		status: MangaStatus::Ongoing,
-		nsfw: MangaContentRating::Safe,
-		viewer: MangaViewer::Ltr,
-	}
-}
-
-pub fn get_chapter_list() -> Result<Vec<Chapter>> {
-	let data = Request::new("https://xkcd.lapin.org/tous-episodes.php", HttpMethod::Get).data();
-	let html = Node::new_with_uri(
-		String::from_utf8_lossy(&data).as_ref(),
-		"https://xkcd.lapin.org/tous-episodes.php",
-	)?;
+pub fn get_chapter_list(lang: &Language) -> Result<Vec<Chapter>> {
+	let url = lang.archive_url();
+	let data = Request::new(&url, HttpMethod::Get).data();
+	let html = Node::new_with_uri(String::from_utf8_lossy(&data).as_ref(), url)?;
 	Ok(html
-		.select("#content a:not(:last-of-type)")
+		.select(lang.archive_links)
 		.array()
 		.rev()
 		.filter_map(|elem| {
@@ -49,11 +28,11 @@ pub fn get_chapter_list() -> Result<Vec<Chapter>> {
 						id: chapter.clone(),
 						title: node.text().read(),
 						volume: -1.0,
-						chapter: extract_f32_from_string(chapter)[0],
+						chapter: first_f32_from_string("", &chapter).unwrap_or(-1.0),
 						date_updated: -1.0,
 						scanlator: String::new(),
 						url,
-						lang: String::from("fr"),
+						lang: String::from(lang.code),
 					}
 				})
 				.ok()
@@ -61,16 +40,10 @@ pub fn get_chapter_list() -> Result<Vec<Chapter>> {
 		.collect::<Vec<_>>())
 }
 
-pub fn get_page_list(id: String) -> Result<Vec<Page>> {
-	let data = Request::new(
-		format!("https://xkcd.lapin.org/index.php?number={id}"),
-		HttpMethod::Get,
-	)
-	.data();
-	let html = Node::new_with_uri(
-		String::from_utf8_lossy(&data).as_ref(),
-		format!("https://xkcd.lapin.org/index.php?number={id}"),
-	)?;
+pub fn get_page_list(lang: &Language, id: &str) -> Result<Vec<Page>> {
+	let url = lang.comic_url(id);
+	let data = Request::new(&url, HttpMethod::Get).data();
+	let html = Node::new_with_uri(String::from_utf8_lossy(&data).as_ref(), url)?;
 	let title = html
 		.select("#col1 h2")
 		.array()
@@ -79,7 +52,7 @@ pub fn get_page_list(id: String) -> Result<Vec<Page>> {
 		.map(|v| v.text().read())
 		.unwrap_or_default();
 
-	let image_node = html.select("#col1 img[title]");
+	let image_node = html.select(lang.image);
 	let image_url = image_node.attr("abs:src").read();
 	let alt = image_node.attr("alt").read();
 	Ok(vec![
@@ -88,10 +61,6 @@ pub fn get_page_list(id: String) -> Result<Vec<Page>> {
 			url: image_url,
 			..Default::default()
 		},
-		Page {
-			index: 1,
-			url: word_wrap(title, alt).to_image_url(super::ImageVariant::Latin),
-			..Default::default()
-		},
+		alt_text_page(1, title, alt),
 	])
 }
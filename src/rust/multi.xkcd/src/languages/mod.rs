@@ -1,91 +1,280 @@
 use aidoku::{
 	error::Result,
-	helpers::uri::encode_uri_component,
 	prelude::format,
 	std::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Page,
+	Chapter, Manga, MangaContentRating, MangaStatus, MangaViewer, Page,
 };
-use alloc::{borrow::ToOwned, vec};
+use alloc::{borrow::ToOwned, string::ToString, vec};
+use source_support::first_f32_from_string;
 
-pub mod en;
 pub mod es;
 pub mod fr;
-pub mod ko;
 pub mod ru;
 pub mod zh;
 
-static THUMBNAIL_URL: &str = "https://fakeimg.pl/550x780/ffffff/6e7b91/?font=museo&text=xkcd";
+static THUMBNAIL_URL: &str = "https://xkcd.com/s/0b7742.png";
 
-#[derive(PartialEq, Eq)]
-enum ImageVariant {
-	Latin,
-	Cjk,
+/// A translation of xkcd. Sites laid out like xkcd.com only need an entry in
+/// [`LANGUAGES`]; the rest bring their own chapter or page list functions.
+pub struct Language {
+	pub code: &'static str,
+	pub title: &'static str,
+	pub author: &'static str,
+	pub description: &'static str,
+	/// Site root, without a trailing slash.
+	pub url: &'static str,
+	/// Path of a comic, `{id}` being its chapter id.
+	pub comic_path: &'static str,
+	/// Page listing every comic, and the selector for the links on it.
+	pub archive_path: &'static str,
+	pub archive_links: &'static str,
+	/// The comic image, titled with its alt text.
+	pub image: &'static str,
+	/// Whether interactive comics have no image, rather than several.
+	pub interactive_if_empty: bool,
+	/// Shown for interactive comics, followed by the comic's URL.
+	pub interactive_message: &'static str,
+	/// Whether chapter ids are xkcd's own comic numbers.
+	pub numbered: bool,
+	pub get_chapter_list: fn(&Language) -> Result<Vec<Chapter>>,
+	pub get_page_list: fn(&Language, &str) -> Result<Vec<Page>>,
 }
 
-trait ToImageUrl {
-	fn to_image_url(&self, variant: ImageVariant) -> String;
+/// Every translation the source offers. The app reads the language picker and
+/// the sites it opens links from out of `res/source.json`, whose `languages`
+/// and `urls` list the same codes and sites.
+pub static LANGUAGES: &[Language] = &[
+	Language {
+		code: "en",
+		title: "xkcd",
+		author: "Randall Munroe",
+		description: "A webcomic of romance, sarcasm, math and language.",
+		url: "https://xkcd.com",
+		comic_path: "/{id}",
+		archive_path: "/archive",
+		archive_links: "#middleContainer > a",
+		image: "#comic img",
+		interactive_if_empty: false,
+		interactive_message:
+			"To experience the interactive version of this comic,\nopen it in a browser: ",
+		numbered: true,
+		get_chapter_list: archive_chapters,
+		get_page_list: comic_pages,
+	},
+	Language {
+		code: "es",
+		title: "xkcd en español",
+		author: "Randall Munroe",
+		description: "Un webcómic sobre romance, sarcasmo, mates y lenguaje.",
+		url: "https://es.xkcd.com",
+		comic_path: "/strips/{id}/",
+		archive_path: "/archive",
+		archive_links: "#archive-ul > ul > li > a",
+		image: "#middleContent .strip",
+		interactive_if_empty: false,
+		// DeepL translated, sorry.
+		interactive_message:
+			"Para experimentar la versión interactiva de este cómic,\nábralo en un navegador: ",
+		numbered: false,
+		get_chapter_list: es::get_chapter_list,
+		get_page_list: comic_pages,
+	},
+	Language {
+		code: "fr",
+		title: "xkcd en français",
+		author: "Randall Munroe",
+		description: "Un webcomic sarcastique qui parle de romance, de maths et de langage.",
+		url: "https://xkcd.lapin.org",
+		comic_path: "/index.php?number={id}",
+		archive_path: "/tous-episodes.php",
+		archive_links: "#content a:not(:last-of-type)",
+		image: "#col1 img[title]",
+		interactive_if_empty: false,
+		interactive_message: "",
+		numbered: false,
+		get_chapter_list: fr::get_chapter_list,
+		get_page_list: fr::get_page_list,
+	},
+	Language {
+		code: "ko",
+		title: "한국어로 xkcd",
+		author: "랜들 먼로",
+		description: "사랑, 풍자, 수학, 그리고 언어에 관한 웹 만화.",
+		url: "https://xkcdko.com",
+		comic_path: "/{id}",
+		archive_path: "/archive",
+		archive_links: "#comicList > ol > li > a",
+		image: "#comic img",
+		interactive_if_empty: true,
+		// Google translated, sorry
+		interactive_message: "이 만화의 대화형 버전을 경험하려면\n브라우저에서 엽니다. ",
+		numbered: true,
+		get_chapter_list: archive_chapters,
+		get_page_list: comic_pages,
+	},
+	Language {
+		code: "ru",
+		title: "xkcd на русском языке",
+		author: "Рэндел Манро",
+		description: "о романтике, сарказме, математике и языке",
+		url: "https://xkcd.ru",
+		comic_path: "/{id}/",
+		archive_path: "/img",
+		archive_links: ".main > a",
+		image: ".main img[alt]",
+		interactive_if_empty: false,
+		interactive_message: "",
+		numbered: true,
+		get_chapter_list: ru::get_chapter_list,
+		get_page_list: ru::get_page_list,
+	},
+	Language {
+		code: "zh",
+		title: "xkcd 中文翻譯",
+		author: "兰德尔·门罗",
+		description: "這裡翻譯某個關於浪漫、諷刺、數學、以及語言的漫畫",
+		url: "https://xkcd.tw",
+		comic_path: "/{id}",
+		archive_path: "/api/strips.json",
+		archive_links: "",
+		image: "#content > img:not([id])",
+		interactive_if_empty: true,
+		// Google translated, sorry
+		interactive_message: "要體驗本漫畫的互動版\n請在瀏覽器中打開: ",
+		numbered: true,
+		get_chapter_list: zh::get_chapter_list,
+		get_page_list: comic_pages,
+	},
+];
+
+pub fn by_code(code: &str) -> Option<&'static Language> {
+	LANGUAGES.iter().find(|lang| lang.code == code)
 }
 
-impl<S> ToImageUrl for S
-where
-	S: AsRef<str>,
-{
-	fn to_image_url(&self, variant: ImageVariant) -> String {
-		let text = encode_uri_component(self.as_ref());
-		match variant {
-            ImageVariant::Latin => format!("https://fakeimg.pl/1500x2126/ffffff/000000/?font=noto&font_size=42&text={text}"),
-            ImageVariant::Cjk => format!("https://placehold.jp/42/ffffff/000000/1500x2126.png?css=%7B%22padding%22%3A%22%200%20300px%22%2C%22text-align%22%3A%22left%22%7D&text={text}"),
-        }
-	}
+pub fn by_id(id: &str) -> Option<&'static Language> {
+	id.strip_prefix("multi.xkcd.").and_then(by_code)
 }
 
-fn word_wrap<T: AsRef<str>>(title: T, alt: T) -> String {
-	let title = title.as_ref();
-	let alt = alt.as_ref();
-	let mut ret = String::new();
-	title.split(' ').enumerate().for_each(|(idx, val)| {
-		if idx != 0 && idx % 7 == 0 {
-			ret.push('\n');
+/// The language whose site `url` is on.
+pub fn by_url(url: &str) -> Option<&'static Language> {
+	let host = url.split('/').find(|val| val.contains('.'))?;
+	let host = host.strip_prefix("www.").unwrap_or(host);
+	LANGUAGES
+		.iter()
+		.find(|lang| lang.url.split("//").nth(1) == Some(host))
+}
+
+impl Language {
+	pub fn archive_url(&self) -> String {
+		format!("{}{}", self.url, self.archive_path)
+	}
+
+	pub fn comic_url(&self, id: &str) -> String {
+		format!("{}{}", self.url, self.comic_path.replace("{id}", id))
+	}
+
+	pub fn comic_info(&self) -> Manga {
+		Manga {
+			id: format!("multi.xkcd.{}", self.code),
+			cover: String::from(THUMBNAIL_URL),
+			title: String::from(self.title),
+			author: String::from(self.author),
+			artist: String::from(self.author),
+			description: String::from(self.description),
+			url: String::from(self.url),
+			categories: Vec::new(),
+			status: MangaStatus::Ongoing,
+			nsfw: MangaContentRating::Safe,
+			viewer: MangaViewer::Ltr,
 		}
-		ret.push_str(val);
-		ret.push(' ');
-	});
-	ret.push_str("\n\n");
-
-	let mut char_count = 0;
-	alt.replace("\r\n", " ").split(' ').for_each(|val| {
-		if char_count > 25 {
-			ret.push('\n');
-			char_count = 0;
+	}
+
+	/// The chapter a comic URL on this language's site points to.
+	pub fn chapter_from_url(&self, url: &str) -> Option<Chapter> {
+		let (prefix, suffix) = self.comic_path.split_once("{id}")?;
+		let path = url.split_once(self.url.split("//").nth(1)?)?.1;
+		let id = path.strip_prefix(prefix)?;
+		let id = id.strip_suffix(suffix).unwrap_or(id).trim_end_matches('/');
+		if id.is_empty() || id.contains('/') || (self.numbered && id.parse::<i32>().is_err()) {
+			return None;
 		}
-		ret.push_str(val);
-		ret.push(' ');
-		char_count += val.len() + 1;
-	});
+		Some(Chapter {
+			id: String::from(id),
+			chapter: id.parse::<f32>().unwrap_or(-1.0),
+			url: String::from(url),
+			lang: String::from(self.code),
+			..Default::default()
+		})
+	}
+}
 
-	ret
+fn text_page<T: AsRef<str>>(index: i32, text: T) -> Page {
+	Page {
+		index,
+		text: String::from(text.as_ref().trim()),
+		..Default::default()
+	}
+}
+
+/// A text page with the comic's title over its alt text.
+fn alt_text_page<T: AsRef<str>>(index: i32, title: T, alt: T) -> Page {
+	text_page(
+		index,
+		format!("{}\n\n{}", title.as_ref().trim(), alt.as_ref().trim()),
+	)
 }
 
-fn get_page_list<T: AsRef<str>>(
-	url: T,
-	selector: T,
-	interactive_if_empty: bool,
-	open_in_browser_message: T,
-	variant: ImageVariant,
-) -> Result<Vec<Page>> {
-	let html = Request::new(url, HttpMethod::Get).html()?;
-	let node = html.select(selector);
-	if (!interactive_if_empty && node.first().next().is_some())
-		|| (interactive_if_empty && node.array().is_empty())
+/// A text page with the explainxkcd explanation for comic `number`.
+pub fn explanation_page(index: i32, number: &str) -> Option<Page> {
+	let text = crate::explain::explanation(number)?;
+	Some(text_page(index, format!("Explanation\n\n{text}")))
+}
+
+/// Chapters from an archive page whose links end in the comic number, with
+/// the date in their title if there is one.
+fn archive_chapters(lang: &Language) -> Result<Vec<Chapter>> {
+	let html = Request::new(lang.archive_url(), HttpMethod::Get).html()?;
+	Ok(html
+		.select(lang.archive_links)
+		.array()
+		.filter_map(|elem| {
+			let node = elem.as_node().ok()?;
+			let chapter = first_f32_from_string("", node.attr("href").read())?;
+			let date_updated = node
+				.attr("title")
+				.0
+				.as_date("yyyy-M-d", None, None)
+				.unwrap_or(-1.0);
+			Some(Chapter {
+				id: chapter.to_string(),
+				title: node.text().read(),
+				volume: -1.0,
+				chapter,
+				date_updated,
+				scanlator: String::new(),
+				url: node.attr("abs:href").read(),
+				lang: String::from(lang.code),
+			})
+		})
+		.collect::<Vec<_>>())
+}
+
+/// The comic image and its alt text, or a message to open interactive comics
+/// in a browser.
+fn comic_pages(lang: &Language, id: &str) -> Result<Vec<Page>> {
+	let url = lang.comic_url(id);
+	let html = Request::new(&url, HttpMethod::Get).html()?;
+	let node = html.select(lang.image);
+	if (!lang.interactive_if_empty && node.first().next().is_some())
+		|| (lang.interactive_if_empty && node.array().is_empty())
 	{
-		Ok(vec![Page {
-			index: 0,
-			url: open_in_browser_message.to_image_url(variant),
-			..Default::default()
-		}])
+		Ok(vec![text_page(
+			0,
+			format!("{}{}", lang.interactive_message, url),
+		)])
 	} else {
 		let url = if node.has_attr("srcset") {
 			let raw = node.attr("abs:srcset").read();
@@ -93,18 +282,13 @@ fn get_page_list<T: AsRef<str>>(
 		} else {
 			node.attr("abs:src").read()
 		};
-		let alt = word_wrap(node.attr("alt").read(), node.attr("title").read());
 		Ok(vec![
 			Page {
 				index: 0,
 				url,
 				..Default::default()
 			},
-			Page {
-				index: 1,
-				url: alt.to_image_url(variant),
-				..Default::default()
-			},
+			alt_text_page(1, node.attr("alt").read(), node.attr("title").read()),
 		])
 	}
 }
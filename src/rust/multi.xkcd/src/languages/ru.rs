@@ -1,42 +1,26 @@
-use super::{word_wrap, ToImageUrl, THUMBNAIL_URL};
-use crate::helper::extract_f32_from_string;
+use super::{alt_text_page, Language};
 use aidoku::{
 	error::Result,
-	prelude::format,
 	std::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, Manga, MangaContentRating, MangaStatus, MangaViewer, Page,
+	Chapter, Page,
 };
 use alloc::{string::ToString, vec};
+use source_support::first_f32_from_string;
 
-pub fn comic_info() -> Manga {
-	Manga {
-		id: String::from("multi.xkcd.ru"),
-		cover: String::from(THUMBNAIL_URL),
-		title: String::from("xkcd на русском языке"),
-		author: String::from("Рэндел Манро"),
-		artist: String::from("Рэндел Манро"),
-		description: String::from("о романтике, сарказме, математике и языке"),
-		url: String::from("https://xkcd.ru"),
-		categories: Vec::new(),
-		status: MangaStatus::Ongoing,
-		nsfw: MangaContentRating::Safe,
-		viewer: MangaViewer::Ltr,
-	}
-}
-
-pub fn get_chapter_list() -> Result<Vec<Chapter>> {
-	let html = Request::new("https://xkcd.ru/img", HttpMethod::Get).html()?;
+pub fn get_chapter_list(lang: &Language) -> Result<Vec<Chapter>> {
+	let html = Request::new(lang.archive_url(), HttpMethod::Get).html()?;
 	Ok(html
-		.select(".main > a")
+		.select(lang.archive_links)
 		.array()
 		.filter_map(|elem| {
 			elem.as_node()
 				.map(|node| {
 					let url = node.attr("abs:href").read();
-					let chapter = extract_f32_from_string(node.attr("href").read())[0];
+					let chapter =
+						first_f32_from_string("", node.attr("href").read()).unwrap_or(-1.0);
 					let title = node.select("img").attr("alt").read();
 					Chapter {
 						id: chapter.to_string(),
@@ -46,7 +30,7 @@ pub fn get_chapter_list() -> Result<Vec<Chapter>> {
 						date_updated: -1.0,
 						scanlator: String::new(),
 						url,
-						lang: String::from("ru"),
+						lang: String::from(lang.code),
 					}
 				})
 				.ok()
@@ -54,10 +38,11 @@ pub fn get_chapter_list() -> Result<Vec<Chapter>> {
 		.collect::<Vec<_>>())
 }
 
-pub fn get_page_list(id: String) -> Result<Vec<Page>> {
-	let html = Request::new(format!("https://xkcd.ru/{id}/"), HttpMethod::Get).html()?;
-	let image_url = html.select(".main img[alt]").attr("abs:src").read();
-	let title = html.select(".main img[alt]").attr("alt").read();
+pub fn get_page_list(lang: &Language, id: &str) -> Result<Vec<Page>> {
+	let html = Request::new(lang.comic_url(id), HttpMethod::Get).html()?;
+	let image = html.select(lang.image);
+	let image_url = image.attr("abs:src").read();
+	let title = image.attr("alt").read();
 	let alt = html.select(".main .comics_text").text().read();
 	Ok(vec![
 		Page {
@@ -65,10 +50,6 @@ pub fn get_page_list(id: String) -> Result<Vec<Page>> {
 			url: image_url,
 			..Default::default()
 		},
-		Page {
-			index: 1,
-			url: word_wrap(title, alt).to_image_url(super::ImageVariant::Latin),
-			..Default::default()
-		},
+		alt_text_page(1, title, alt),
 	])
 }
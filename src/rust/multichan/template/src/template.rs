@@ -7,7 +7,8 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaPageResult, MangaStatus, Page,
+	Chapter, DeepLink, Filter, FilterType, Listing, Manga, MangaContentRating, MangaPageResult,
+	MangaStatus, MangaViewer, Page,
 };
 
 use crate::helper::*;
@@ -62,7 +63,12 @@ impl MangaChanSource {
 						.text()
 						.read(),
 				);
-				let (nsfw, viewer) = category_parser(&categories);
+				let (nsfw, viewer) = category_parser(
+					&CATEGORY_RULES,
+					&categories,
+					MangaContentRating::Safe,
+					MangaViewer::Rtl,
+				);
 				let status_str = manga_node
 					.select("div.manga_row3:contains(Статус (томов)) div.item2")
 					.text()
@@ -228,7 +234,12 @@ impl MangaChanSource {
 		} else {
 			MangaStatus::Unknown
 		};
-		let (nsfw, viewer) = category_parser(&categories);
+		let (nsfw, viewer) = category_parser(
+			&CATEGORY_RULES,
+			&categories,
+			MangaContentRating::Safe,
+			MangaViewer::Rtl,
+		);
 		Ok(Manga {
 			id,
 			cover,
@@ -185,7 +185,12 @@ impl MyMangaSource {
 				String::from(node.text().read().trim())
 			})
 			.collect::<Vec<_>>();
-		let (nsfw, viewer) = category_parser(&categories);
+		let (nsfw, viewer) = category_parser(
+			&CATEGORY_RULES,
+			&categories,
+			MangaContentRating::Safe,
+			MangaViewer::Rtl,
+		);
 		Ok(Manga {
 			id,
 			cover,
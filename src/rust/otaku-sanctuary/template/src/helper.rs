@@ -1,38 +1,12 @@
-use aidoku::{
-	std::{current_date, defaults::defaults_get, html::Node, String, StringRef, Vec},
-	MangaContentRating, MangaViewer,
-};
+use aidoku::std::{current_date, defaults::defaults_get, String, StringRef, Vec};
 use alloc::string::ToString;
+use source_support::CategoryRules;
 
-pub fn extract_f32_from_string(title: String, text: String) -> f32 {
-	text.replace(&title, "")
-		.chars()
-		.filter(|a| (*a >= '0' && *a <= '9') || *a == ' ' || *a == '.')
-		.collect::<String>()
-		.split(' ')
-		.collect::<Vec<&str>>()
-		.into_iter()
-		.map(|a| a.parse::<f32>().unwrap_or(0.0))
-		.find(|a| *a > 0.0)
-		.unwrap_or(0.0)
-}
+pub use source_aidoku::{category_parser, text_with_newlines};
+pub use source_support::urlencode;
 
-pub fn urlencode(string: String) -> String {
-	let mut result: Vec<u8> = Vec::with_capacity(string.len() * 3);
-	let hex = "0123456789ABCDEF".as_bytes();
-	let bytes = string.as_bytes();
-
-	for byte in bytes {
-		let curr = *byte;
-		if curr.is_ascii_lowercase() || curr.is_ascii_uppercase() || curr.is_ascii_digit() {
-			result.push(curr);
-		} else {
-			result.push(b'%');
-			result.push(hex[curr as usize >> 4]);
-			result.push(hex[curr as usize & 15]);
-		}
-	}
-	String::from_utf8(result).unwrap_or_default()
+pub fn extract_f32_from_string(title: String, text: String) -> f32 {
+	source_support::first_f32_from_string(title, text).unwrap_or(0.0)
 }
 
 pub fn get_lang_code() -> String {
@@ -47,43 +21,10 @@ pub fn get_lang_code() -> String {
 	code
 }
 
-pub fn text_with_newlines(node: Node) -> String {
-	let html = node.html().read();
-	if !String::from(html.trim()).is_empty() {
-		if let Ok(node) = Node::new_fragment(
-			node.html()
-				.read()
-				.replace("<br>", "{{ .LINEBREAK }}")
-				.as_bytes(),
-		) {
-			node.text().read().replace("{{ .LINEBREAK }}", "\n")
-		} else {
-			String::new()
-		}
-	} else {
-		String::new()
-	}
-}
-
-pub fn category_parser(categories: &Vec<String>) -> (MangaContentRating, MangaViewer) {
-	let mut nsfw = MangaContentRating::Safe;
-	let mut viewer = MangaViewer::Rtl;
-	for category in categories {
-		match category.as_str() {
-			"Adult" | "Smut" | "Mature" | "18+" => nsfw = MangaContentRating::Nsfw,
-			"Ecchi" | "16+" => {
-				nsfw = match nsfw {
-					MangaContentRating::Nsfw => MangaContentRating::Nsfw,
-					_ => MangaContentRating::Suggestive,
-				}
-			}
-			"Webtoon" | "Manhwa" | "Manhua" => viewer = MangaViewer::Scroll,
-			"VnComic" => viewer = MangaViewer::Ltr,
-			_ => continue,
-		}
-	}
-	(nsfw, viewer)
-}
+pub const CATEGORY_RULES: CategoryRules = CategoryRules {
+	ltr: &["VnComic"],
+	..CategoryRules::DEFAULT
+};
 
 pub fn capitalize_first_letter(name: String) -> String {
 	let preprocess = name.chars().collect::<Vec<_>>();
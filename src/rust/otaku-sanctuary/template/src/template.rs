@@ -264,7 +264,12 @@ impl OtakuSanctuarySource {
 				"VnComic" => MangaViewer::Ltr,
 				_ => MangaViewer::Rtl,
 			};
-			let (mut nsfw, _) = category_parser(&categories);
+			let (mut nsfw, _) = category_parser(
+				&CATEGORY_RULES,
+				&categories,
+				MangaContentRating::Safe,
+				MangaViewer::Rtl,
+			);
 			if html.select("div.alert:contains(18 tuổi)").array().len() > 0 {
 				nsfw = MangaContentRating::Nsfw;
 			}
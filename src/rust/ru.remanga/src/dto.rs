@@ -17,6 +17,13 @@ pub struct FetchMangaInfo {
 	pub branches: Vec<String>,
 }
 
+pub struct Branch {
+	pub id: String,
+	/// The branch's publishers (translation teams), joined with ", ".
+	pub name: String,
+	pub count_chapters: i64,
+}
+
 pub struct ChapterContainer {
 	pub index: i64,
 	pub item: Chapter,
@@ -1,6 +1,6 @@
 use crate::constants::{BASE_API_URL, BASE_URL, CHAPTER_PAGE_SIZE, PAGE_SIZE, USER_AGENT};
-use crate::dto::{BaseMangaItem, FetchMangaInfo};
-use crate::parser::{parse_branches, parse_chapters, parse_manga_fetch_info};
+use crate::dto::{BaseMangaItem, Branch, FetchMangaInfo};
+use crate::parser::{join_branch_ids, parse_branches, parse_chapters, parse_manga_fetch_info};
 use aidoku::helpers::uri::QueryParameters;
 use aidoku::std::defaults::defaults_get;
 use aidoku::std::net::Request;
@@ -19,17 +19,52 @@ macro_rules! debug {
 pub(crate) use debug;*/
 
 pub fn get_manga_title(item: &BaseMangaItem) -> String {
-	let main_title = defaults_get("main_title")
-		.and_then(|value| value.as_bool())
-		.unwrap_or(false);
-
-	if main_title {
+	if get_bool_setting("main_title") {
 		item.main_name.clone()
 	} else {
 		item.secondary_name.clone()
 	}
 }
 
+fn get_bool_setting(key: &str) -> bool {
+	defaults_get(key)
+		.and_then(|value| value.as_bool())
+		.unwrap_or(false)
+}
+
+/// The team name from the "preferred_branch" setting, lowercased.
+fn get_preferred_branch() -> String {
+	defaults_get("preferred_branch")
+		.and_then(|value| value.as_string())
+		.map(|value| value.read().trim().to_lowercase())
+		.unwrap_or_default()
+}
+
+/// Orders the preferred branch first, so its chapters come first among
+/// chapters with the same index. In single branch mode only that branch is
+/// kept, or the one with the most chapters when no branch matches.
+fn select_branches(mut branches: Vec<Branch>) -> Vec<Branch> {
+	let preferred = get_preferred_branch();
+	let is_preferred =
+		|branch: &Branch| !preferred.is_empty() && branch.name.to_lowercase().contains(&preferred);
+	branches.sort_by_key(|branch| !is_preferred(branch));
+
+	if get_bool_setting("single_branch") && branches.len() > 1 {
+		let index = if is_preferred(&branches[0]) {
+			0
+		} else {
+			branches
+				.iter()
+				.enumerate()
+				.max_by_key(|(_, branch)| branch.count_chapters)
+				.map(|(index, _)| index)
+				.unwrap_or(0)
+		};
+		return Vec::from([branches.swap_remove(index)]);
+	}
+	branches
+}
+
 pub fn build_url_to_title(dir: &String) -> String {
 	format!("{BASE_URL}/manga/{dir}/")
 }
@@ -185,30 +220,47 @@ fn build_url_with_query(base_url: String, url: String, query: QueryParameters) -
 	format!("{base_url}{url}?{query}")
 }
 
-pub fn fetch_manga_info(dir: String) -> Result<FetchMangaInfo> {
-	fetch_json(build_api_title_url(dir.clone()))
+fn fetch_branches(dir: String) -> Result<Vec<Branch>> {
+	fetch_json(build_api_title_url(dir))
 		.and_then(|obj| obj.get("branches").as_array())
 		.and_then(parse_branches)
-		.and_then(|branches| parse_manga_fetch_info(format!("{branches}:{dir}")))
+}
+
+pub fn fetch_manga_info(dir: String) -> Result<FetchMangaInfo> {
+	fetch_branches(dir.clone()).and_then(|branches| {
+		parse_manga_fetch_info(format!("{}:{dir}", join_branch_ids(&branches)))
+	})
 }
 
 pub fn fetch_all_chapters(id: String) -> Result<Vec<Chapter>> {
 	parse_manga_fetch_info(id).and_then(|info| {
 		let mut chapters = Vec::new();
 
-		if info.branches.is_empty() {
+		// the title has the branches' names, fall back to the ids saved in the
+		// manga id without them
+		let branches = fetch_branches(info.dir.clone()).unwrap_or_else(|_| {
+			info.branches
+				.iter()
+				.map(|id| Branch {
+					id: id.clone(),
+					name: String::new(),
+					count_chapters: 0,
+				})
+				.collect()
+		});
+		if branches.is_empty() {
 			return Ok(Vec::new());
 		}
 
-		for branch in info.branches {
+		let show_paid = get_bool_setting("show_paid");
+		for branch in select_branches(branches) {
 			let mut page = 1;
 			loop {
-				let obj = fetch_json(build_api_chapters_url(branch.clone(), page))?;
+				let obj = fetch_json(build_api_chapters_url(branch.id.clone(), page))?;
 
-				let list = obj
-					.get("results")
-					.as_array()
-					.and_then(|results| parse_chapters(info.dir.clone(), results))?;
+				let list = obj.get("results").as_array().and_then(|results| {
+					parse_chapters(info.dir.clone(), &branch, results, show_paid)
+				})?;
 				if list.is_empty() {
 					break;
 				}
@@ -1,4 +1,4 @@
-use crate::dto::{BaseMangaItem, ChapterContainer, FetchMangaInfo};
+use crate::dto::{BaseMangaItem, Branch, ChapterContainer, FetchMangaInfo};
 use crate::helper::{
 	build_url_to_chapter, build_url_to_cover, build_url_to_title, get_manga_title,
 };
@@ -117,16 +117,35 @@ fn parse_names_list(list: ArrayRef) -> Result<Vec<String>> {
 	Ok(result)
 }
 
-pub fn parse_branches(branches: ArrayRef) -> Result<String> {
+pub fn parse_branches(branches: ArrayRef) -> Result<Vec<Branch>> {
 	let mut result = Vec::new();
 	for branch_ref in branches {
 		let branch_obj = branch_ref.as_object()?;
 
 		let id = branch_obj.get("id").as_int()?.to_string();
-		result.push(id);
+		let name = branch_obj
+			.get("publishers")
+			.as_array()
+			.and_then(parse_names_list)
+			.map(|x| x.join(", "))
+			.unwrap_or_default();
+		let count_chapters = branch_obj.get("count_chapters").as_int().unwrap_or(0);
+		result.push(Branch {
+			id,
+			name,
+			count_chapters,
+		});
 	}
 
-	Ok(result.join(","))
+	Ok(result)
+}
+
+pub fn join_branch_ids(branches: &[Branch]) -> String {
+	branches
+		.iter()
+		.map(|branch| branch.id.as_str())
+		.collect::<Vec<_>>()
+		.join(",")
 }
 
 pub fn parse_manga_item(manga_obj: ObjectRef) -> Result<Manga> {
@@ -174,7 +193,7 @@ pub fn parse_manga_item(manga_obj: ObjectRef) -> Result<Manga> {
 		.get("branches")
 		.as_array()
 		.and_then(parse_branches)?;
-	let id = format!("{}:{}", branches, manga_base.dir);
+	let id = format!("{}:{}", join_branch_ids(&branches), manga_base.dir);
 
 	Ok(Manga {
 		id,
@@ -213,15 +232,27 @@ pub fn parse_manga_list(results: ArrayRef) -> Result<MangaPageResult> {
 	Ok(MangaPageResult { has_more, manga })
 }
 
-fn parse_chapter(dir: &String, chapter_obj: ObjectRef) -> Result<ChapterContainer> {
+fn parse_chapter(
+	dir: &String,
+	branch: &Branch,
+	chapter_obj: ObjectRef,
+	locked: bool,
+) -> Result<ChapterContainer> {
 	let index = chapter_obj.get("index").as_int()?;
 
 	let id = chapter_obj.get("id").as_int()?.to_string();
-	let title = chapter_obj
+	let mut title = chapter_obj
 		.get("name")
 		.as_string()
 		.unwrap_or_default()
 		.read();
+	if locked {
+		title = if title.is_empty() {
+			String::from("🔒")
+		} else {
+			format!("🔒 {title}")
+		};
+	}
 	let volume = chapter_obj.get("tome").as_int().unwrap_or(0) as f32;
 	let chapter = chapter_obj
 		.get("chapter")
@@ -238,7 +269,9 @@ fn parse_chapter(dir: &String, chapter_obj: ObjectRef) -> Result<ChapterContaine
 		.as_array()
 		.and_then(parse_names_list)
 		.map(|x| x.join(", "))
-		.unwrap_or_default();
+		.ok()
+		.filter(|x| !x.is_empty())
+		.unwrap_or_else(|| branch.name.clone());
 	let url = build_url_to_chapter(&id, dir);
 
 	let item = Chapter {
@@ -255,7 +288,14 @@ fn parse_chapter(dir: &String, chapter_obj: ObjectRef) -> Result<ChapterContaine
 	Ok(ChapterContainer { index, item })
 }
 
-pub fn parse_chapters(dir: String, results: ArrayRef) -> Result<Vec<ChapterContainer>> {
+/// Paid chapters that can't be read right now are left out, or listed with a
+/// lock in their title when `show_paid` is set.
+pub fn parse_chapters(
+	dir: String,
+	branch: &Branch,
+	results: ArrayRef,
+	show_paid: bool,
+) -> Result<Vec<ChapterContainer>> {
 	let mut chapters = Vec::new();
 
 	for result in results {
@@ -267,15 +307,14 @@ pub fn parse_chapters(dir: String, results: ArrayRef) -> Result<Vec<ChapterConta
 		}
 
 		let is_paid = obj.get("is_paid").as_bool().unwrap_or(false);
-		if is_paid {
-			let is_bought = obj.get("is_bought").as_bool().unwrap_or(false);
-			let is_free_today = obj.get("is_free_today").as_bool().unwrap_or(false);
-			if !is_bought && !is_free_today {
-				continue; // skip paid chapters that is not available to fetch rn
-			}
+		let is_bought = obj.get("is_bought").as_bool().unwrap_or(false);
+		let is_free_today = obj.get("is_free_today").as_bool().unwrap_or(false);
+		let locked = is_paid && !is_bought && !is_free_today;
+		if locked && !show_paid {
+			continue; // skip paid chapters that is not available to fetch rn
 		}
 
-		let chapter = parse_chapter(&dir, obj)?;
+		let chapter = parse_chapter(&dir, branch, obj, locked)?;
 		chapters.push(chapter);
 	}
 
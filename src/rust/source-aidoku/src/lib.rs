@@ -0,0 +1,68 @@
+//! The [`source_support`] helpers that need aidoku's types, so that sources
+//! don't each keep their own adapter for them.
+#![no_std]
+extern crate alloc;
+
+use aidoku::{
+	std::{html::Node, String},
+	MangaContentRating, MangaViewer,
+};
+use source_support::{CategoryRules, Layout, Rating};
+
+/// The text of `node` with its `<br>`s kept as newlines.
+pub fn text_with_newlines(node: Node) -> String {
+	html_text_with_newlines(node.html().read())
+}
+
+/// The text of an HTML fragment with its `<br>`s kept as newlines.
+pub fn html_text_with_newlines<T: AsRef<str>>(html: T) -> String {
+	source_support::text_with_newlines(html, |html| {
+		Node::new_fragment(html.as_bytes())
+			.ok()
+			.map(|node| node.text().read())
+	})
+}
+
+pub fn to_rating(rating: MangaContentRating) -> Rating {
+	match rating {
+		MangaContentRating::Safe => Rating::Safe,
+		MangaContentRating::Suggestive => Rating::Suggestive,
+		MangaContentRating::Nsfw => Rating::Nsfw,
+	}
+}
+
+pub fn to_content_rating(rating: Rating) -> MangaContentRating {
+	match rating {
+		Rating::Safe => MangaContentRating::Safe,
+		Rating::Suggestive => MangaContentRating::Suggestive,
+		Rating::Nsfw => MangaContentRating::Nsfw,
+	}
+}
+
+pub fn to_viewer(layout: Layout) -> MangaViewer {
+	match layout {
+		Layout::Rtl => MangaViewer::Rtl,
+		Layout::Ltr => MangaViewer::Ltr,
+		Layout::Scroll => MangaViewer::Scroll,
+	}
+}
+
+/// The content rating and viewer `rules` give `categories`. They can raise
+/// `default_nsfw` but never lower it, and `default_viewer` is kept when no
+/// layout matches.
+pub fn category_parser<I, S>(
+	rules: &CategoryRules,
+	categories: I,
+	default_nsfw: MangaContentRating,
+	default_viewer: MangaViewer,
+) -> (MangaContentRating, MangaViewer)
+where
+	I: IntoIterator<Item = S>,
+	S: AsRef<str>,
+{
+	let (rating, layout) = rules.classify(categories);
+	(
+		to_content_rating(to_rating(default_nsfw).raised_by(rating)),
+		layout.map_or(default_viewer, to_viewer),
+	)
+}
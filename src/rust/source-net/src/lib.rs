@@ -0,0 +1,263 @@
+//! Requests that respect a source's [`RateLimit`].
+//!
+//! Every request takes a token from its limit's bucket, kept in the source's
+//! defaults under `throttle.{name}.*`. A 429 or 503 from the site pauses the
+//! bucket for its `Retry-After`, or for a backoff that doubles with every such
+//! response in a row. A request that finds the bucket empty, or is answered
+//! with 429 or 503, is re-sent once the bucket has a token again, as long as
+//! that's within the limit's retry budget; otherwise it fails with
+//! [`rate_limited`] rather than passing an empty page on as a result.
+#![no_std]
+extern crate alloc;
+
+use aidoku::{
+	error::{AidokuError, AidokuErrorKind, NodeError, Result},
+	prelude::format,
+	std::{
+		current_date,
+		defaults::{defaults_get, defaults_set},
+		html::Node,
+		json,
+		net::Request,
+		String, StringRef, ValueRef, Vec,
+	},
+};
+use source_support::{parse_retry_after, TokenBucket, TtlCache};
+
+pub use source_support::RateLimit;
+
+#[link(wasm_import_module = "net")]
+extern "C" {
+	#[link_name = "send"]
+	fn request_send(rd: i32);
+	#[link_name = "close"]
+	fn request_close(rd: i32);
+	#[link_name = "get_url"]
+	fn request_get_url(rd: i32) -> i32;
+	#[link_name = "get_data_size"]
+	fn request_get_data_size(rd: i32) -> i32;
+	#[link_name = "get_data"]
+	fn request_get_data(rd: i32, buffer: *mut u8, size: usize);
+	#[link_name = "get_header"]
+	fn request_get_header(rd: i32, field: *const u8, len: usize) -> i32;
+	#[link_name = "get_status_code"]
+	fn request_get_status_code(rd: i32) -> i32;
+}
+
+fn key(limit: &RateLimit, field: &str) -> String {
+	format!("throttle.{}.{field}", limit.name)
+}
+
+fn stored(limit: &RateLimit, field: &str) -> Option<f64> {
+	defaults_get(&key(limit, field))
+		.and_then(|value| value.as_float())
+		.ok()
+}
+
+fn load_bucket(limit: &RateLimit, now: f64) -> TokenBucket {
+	match (stored(limit, "tokens"), stored(limit, "updated")) {
+		(Some(tokens), Some(updated)) => TokenBucket { tokens, updated },
+		_ => TokenBucket::full(limit, now),
+	}
+}
+
+fn store_bucket(limit: &RateLimit, bucket: &TokenBucket) {
+	defaults_set(&key(limit, "tokens"), bucket.tokens.into());
+	defaults_set(&key(limit, "updated"), bucket.updated.into());
+}
+
+/// The error for a request that was throttled more often than its limit
+/// allows retries. The host has no error kind for network failures, so this
+/// is reported like a page that couldn't be parsed.
+pub fn rate_limited() -> AidokuError {
+	AidokuError {
+		reason: AidokuErrorKind::NodeError(NodeError::ParseError),
+	}
+}
+
+/// Takes a token from the limit's bucket, or returns how long until it has
+/// one.
+fn take_token(limit: &RateLimit) -> core::result::Result<(), f64> {
+	let now = current_date();
+	let mut bucket = load_bucket(limit, now);
+	let taken = bucket.try_take(limit, now);
+	store_bucket(limit, &bucket);
+	if taken {
+		Ok(())
+	} else {
+		Err(bucket.wait(limit, now))
+	}
+}
+
+/// Pauses the limit's bucket after the site answered 429 or 503, returning
+/// how long until it has a token again.
+fn back_off(limit: &RateLimit, retry_after: Option<f64>) -> f64 {
+	let now = current_date();
+	let failures = stored(limit, "failures").unwrap_or(0.0);
+	let pause = limit.pause(failures as u32, retry_after);
+
+	let mut bucket = load_bucket(limit, now);
+	bucket.pause(pause, now);
+	store_bucket(limit, &bucket);
+	defaults_set(&key(limit, "failures"), (failures + 1.0).into());
+	bucket.wait(limit, now)
+}
+
+/// Sources have no way to sleep, so waiting means spinning on the clock. The
+/// wait is bounded by the limit's `max_wait`.
+fn wait(seconds: f64) {
+	let until = current_date() + seconds;
+	while current_date() < until {
+		core::hint::spin_loop();
+	}
+}
+
+fn header(request: &Request, field: &str) -> Option<String> {
+	let rid = unsafe { request_get_header(request.0, field.as_ptr(), field.len()) };
+	ValueRef::new(rid)
+		.as_string()
+		.ok()
+		.map(|value| value.read())
+}
+
+/// Seconds until the time a `Retry-After` header names, in either form.
+fn retry_after(request: &Request) -> Option<f64> {
+	let value = header(request, "Retry-After")?;
+	parse_retry_after(&value).or_else(|| {
+		StringRef::from(&value)
+			.0
+			.as_date("EEE, dd MMM yyyy HH:mm:ss zzz", Some("en_US"), Some("GMT"))
+			.ok()
+			.map(|date| date - current_date())
+	})
+}
+
+pub trait ThrottledRequest {
+	/// Sends the request through `limit`, re-sending it while it's throttled
+	/// and the retry budget allows, and returns the status code.
+	fn send_throttled(&self, limit: &RateLimit) -> Result<i32>;
+	/// The response body, sent through `limit` when there is one.
+	fn data_throttled(self, limit: Option<&RateLimit>) -> Result<Vec<u8>>;
+	fn string_throttled(self, limit: Option<&RateLimit>) -> Result<String>;
+	fn html_throttled(self, limit: Option<&RateLimit>) -> Result<Node>;
+	fn json_throttled(self, limit: Option<&RateLimit>) -> Result<ValueRef>;
+	/// Sends the request and returns its status code without reading the body.
+	fn status_throttled(self, limit: Option<&RateLimit>) -> Result<i32>;
+}
+
+impl ThrottledRequest for Request {
+	fn send_throttled(&self, limit: &RateLimit) -> Result<i32> {
+		let mut attempt = 0;
+		loop {
+			let throttled = match take_token(limit) {
+				Err(until_token) => until_token,
+				Ok(()) => {
+					// The host sends the request again from its url, headers
+					// and body every time `send` is called.
+					unsafe { request_send(self.0) };
+					let status = unsafe { request_get_status_code(self.0) };
+					if !RateLimit::should_back_off(status) {
+						if stored(limit, "failures").is_some_and(|failures| failures > 0.0) {
+							defaults_set(&key(limit, "failures"), 0f64.into());
+						}
+						return Ok(status);
+					}
+					back_off(limit, retry_after(self))
+				}
+			};
+			let delay = limit
+				.retry_delay(attempt, throttled)
+				.ok_or_else(rate_limited)?;
+			wait(delay);
+			attempt += 1;
+		}
+	}
+
+	fn data_throttled(self, limit: Option<&RateLimit>) -> Result<Vec<u8>> {
+		let Some(limit) = limit else {
+			return Ok(self.data());
+		};
+		if let Err(error) = self.send_throttled(limit) {
+			unsafe { request_close(self.0) };
+			return Err(error);
+		}
+		let size = unsafe { request_get_data_size(self.0) };
+		let mut buffer = Vec::with_capacity(size.max(0) as usize);
+		if size > 0 {
+			unsafe {
+				request_get_data(self.0, buffer.as_mut_ptr(), size as usize);
+				buffer.set_len(size as usize);
+			}
+		}
+		unsafe { request_close(self.0) };
+		Ok(buffer)
+	}
+
+	fn string_throttled(self, limit: Option<&RateLimit>) -> Result<String> {
+		if limit.is_none() {
+			return self.string();
+		}
+		Ok(String::from_utf8_lossy(&self.data_throttled(limit)?).into())
+	}
+
+	fn html_throttled(self, limit: Option<&RateLimit>) -> Result<Node> {
+		if limit.is_none() {
+			return self.html();
+		}
+		let url = ValueRef::new(unsafe { request_get_url(self.0) })
+			.as_string()
+			.map(|url| url.read())
+			.unwrap_or_default();
+		let data = self.data_throttled(limit)?;
+		Node::new_with_uri(String::from_utf8_lossy(&data).as_ref(), &url)
+	}
+
+	fn json_throttled(self, limit: Option<&RateLimit>) -> Result<ValueRef> {
+		if limit.is_none() {
+			return self.json();
+		}
+		json::parse(self.data_throttled(limit)?)
+	}
+
+	fn status_throttled(self, limit: Option<&RateLimit>) -> Result<i32> {
+		let status = match limit {
+			Some(limit) => self.send_throttled(limit),
+			None => {
+				self.send();
+				Ok(unsafe { request_get_status_code(self.0) })
+			}
+		};
+		unsafe { request_close(self.0) };
+		status
+	}
+}
+
+/// How long a fetched page is reused, and how many are kept.
+const PAGE_LIFETIME: f64 = 5.0 * 60.0;
+const PAGE_CAPACITY: usize = 4;
+
+static mut PAGE_CACHE: TtlCache<Vec<u8>> = TtlCache::new(PAGE_LIFETIME, PAGE_CAPACITY);
+
+/// The page at `url`, reused if it was fetched in the last few minutes, so
+/// that e.g. a manga's details and chapter list share a single request.
+/// `request` is only built on a miss. A page is only kept when `is_page`
+/// accepts it, so that challenge and error pages are fetched again.
+pub fn cached_page<F: FnOnce() -> Request>(
+	url: &str,
+	request: F,
+	rate_limit: Option<&RateLimit>,
+	is_page: fn(&Node) -> bool,
+) -> Result<Node> {
+	let cache = unsafe { &mut *core::ptr::addr_of_mut!(PAGE_CACHE) };
+	let now = current_date();
+	if let Some(data) = cache.get(url, now) {
+		return Node::new_with_uri(String::from_utf8_lossy(data).as_ref(), url);
+	}
+
+	let data = request().data_throttled(rate_limit)?;
+	let html = Node::new_with_uri(String::from_utf8_lossy(&data).as_ref(), url)?;
+	if is_page(&html) {
+		cache.insert(url, data, now);
+	}
+	Ok(html)
+}
@@ -0,0 +1,74 @@
+//! A small cache with an expiry and a capacity, for values worth keeping
+//! across calls into a source. Sources keep it in a `static mut`, so it's
+//! constructible in a `const`.
+use alloc::{string::String, vec::Vec};
+
+pub struct TtlCache<V> {
+	/// Seconds an entry is kept for.
+	lifetime: f64,
+	/// Entries kept at most; the oldest goes first.
+	capacity: usize,
+	entries: Vec<(String, f64, V)>,
+}
+
+impl<V> TtlCache<V> {
+	pub const fn new(lifetime: f64, capacity: usize) -> Self {
+		Self {
+			lifetime,
+			capacity,
+			entries: Vec::new(),
+		}
+	}
+
+	/// The value stored for `key`, unless it has expired.
+	pub fn get(&mut self, key: &str, now: f64) -> Option<&V> {
+		let lifetime = self.lifetime;
+		self.entries
+			.retain(|(_, stored, _)| now - stored < lifetime);
+		self.entries
+			.iter()
+			.find(|(k, _, _)| k == key)
+			.map(|(_, _, value)| value)
+	}
+
+	pub fn insert<K: Into<String>>(&mut self, key: K, value: V, now: f64) {
+		let key = key.into();
+		self.entries.retain(|(k, _, _)| *k != key);
+		if self.capacity == 0 {
+			return;
+		}
+		if self.entries.len() >= self.capacity {
+			self.entries.remove(0);
+		}
+		self.entries.push((key, now, value));
+	}
+
+	pub fn clear(&mut self) {
+		self.entries.clear();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn entries_expire() {
+		let mut cache = TtlCache::new(10.0, 4);
+		cache.insert("a", 1, 100.0);
+		assert_eq!(cache.get("a", 109.0), Some(&1));
+		assert_eq!(cache.get("a", 110.0), None);
+	}
+
+	#[test]
+	fn oldest_entry_makes_room() {
+		let mut cache = TtlCache::new(10.0, 2);
+		cache.insert("a", 1, 100.0);
+		cache.insert("b", 2, 100.0);
+		cache.insert("a", 3, 101.0);
+		cache.insert("c", 4, 102.0);
+		assert_eq!(cache.get("b", 102.0), None);
+		assert_eq!(cache.get("a", 102.0), Some(&3));
+		assert_eq!(cache.get("c", 102.0), Some(&4));
+	}
+}
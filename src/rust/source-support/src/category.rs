@@ -0,0 +1,106 @@
+/// Content rating implied by a manga's categories.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rating {
+	Safe,
+	Suggestive,
+	Nsfw,
+}
+
+impl Rating {
+	/// This rating, or `matched` if that's stricter. Used to keep a source's
+	/// own rating when its categories suggest a looser one.
+	pub fn raised_by(self, matched: Option<Rating>) -> Rating {
+		matched.map_or(self, |matched| matched.max(self))
+	}
+}
+
+/// Reader layout implied by a manga's categories.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+	Rtl,
+	Ltr,
+	Scroll,
+}
+
+/// Category names that imply a content rating or layout. Names are compared
+/// case-insensitively.
+pub struct CategoryRules<'a> {
+	pub nsfw: &'a [&'a str],
+	pub suggestive: &'a [&'a str],
+	pub scroll: &'a [&'a str],
+	pub ltr: &'a [&'a str],
+}
+
+impl CategoryRules<'_> {
+	/// Rules used by most English and Vietnamese sites.
+	pub const DEFAULT: CategoryRules<'static> = CategoryRules {
+		nsfw: &["Adult", "Smut", "Mature", "18+"],
+		suggestive: &["Ecchi", "16+"],
+		scroll: &["Webtoon", "Manhwa", "Manhua"],
+		ltr: &[],
+	};
+
+	/// Returns the strongest rating and the layout matched by `categories`,
+	/// or `None` for whichever nothing matched. When several layouts match,
+	/// the last category matched wins.
+	pub fn classify<I, S>(&self, categories: I) -> (Option<Rating>, Option<Layout>)
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>,
+	{
+		let matches = |names: &[&str], category: &str| {
+			names.iter().any(|name| name.eq_ignore_ascii_case(category))
+		};
+		let mut rating = None;
+		let mut layout = None;
+		for category in categories {
+			let category = category.as_ref().trim();
+			if matches(self.nsfw, category) {
+				rating = rating.max(Some(Rating::Nsfw));
+			} else if matches(self.suggestive, category) {
+				rating = rating.max(Some(Rating::Suggestive));
+			}
+			if matches(self.scroll, category) {
+				layout = Some(Layout::Scroll);
+			} else if matches(self.ltr, category) {
+				layout = Some(Layout::Ltr);
+			}
+		}
+		(rating, layout)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn picks_strongest_rating() {
+		let rules = CategoryRules::DEFAULT;
+		assert_eq!(rules.classify(["Action"]), (None, None));
+		assert_eq!(rules.classify(["ecchi"]).0, Some(Rating::Suggestive));
+		assert_eq!(rules.classify(["Smut", "Ecchi"]).0, Some(Rating::Nsfw));
+		assert_eq!(rules.classify(["Ecchi", "Mature"]).0, Some(Rating::Nsfw));
+	}
+
+	#[test]
+	fn raising_never_lowers_rating() {
+		let rules = CategoryRules::DEFAULT;
+		let rating =
+			|default: Rating, categories: &[&str]| default.raised_by(rules.classify(categories).0);
+		assert_eq!(rating(Rating::Nsfw, &["Ecchi"]), Rating::Nsfw);
+		assert_eq!(rating(Rating::Safe, &["Ecchi"]), Rating::Suggestive);
+		assert_eq!(rating(Rating::Suggestive, &["Action"]), Rating::Suggestive);
+	}
+
+	#[test]
+	fn last_layout_wins() {
+		let rules = CategoryRules {
+			ltr: &["Novel"],
+			..CategoryRules::DEFAULT
+		};
+		assert_eq!(rules.classify(["Novel"]).1, Some(Layout::Ltr));
+		assert_eq!(rules.classify(["Novel", "Manhwa"]).1, Some(Layout::Scroll));
+		assert_eq!(rules.classify(["Manhwa", "Novel"]).1, Some(Layout::Ltr));
+	}
+}
@@ -0,0 +1,523 @@
+//! Cookie challenges served by anti-bot interstitials (Vinahost and others):
+//! a short page whose script sets `document.cookie` and reloads. Instead of a
+//! JavaScript engine, only the string expressions these scripts are known to
+//! build their cookies from are evaluated: literals, `+`, variables,
+//! `String.fromCharCode`, `atob`, `unescape`/`decodeURIComponent` and
+//! `split`/`reverse`/`join`/`slice`/`substring`/`replace` chains.
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+/// Interstitials are tiny; a real page that happens to set a cookie isn't.
+const MAX_CHALLENGE_SIZE: usize = 16 * 1024;
+
+/// Whether `html` is a cookie challenge rather than the page that was asked
+/// for.
+pub fn is_cookie_challenge<T: AsRef<str>>(html: T) -> bool {
+	let html = html.as_ref();
+	html.len() <= MAX_CHALLENGE_SIZE
+		&& html.contains("document.cookie")
+		&& [
+			"location.reload",
+			"location.href",
+			"location.replace",
+			"location =",
+		]
+		.iter()
+		.any(|redirect| html.contains(redirect))
+}
+
+/// Runs the scripts of a challenge page and returns the cookies they set, as a
+/// `Cookie` header value like `"D1N=abc; path_token=xyz"`.
+pub fn solve_cookie_challenge<T: AsRef<str>>(html: T) -> Option<String> {
+	let mut cookies: Vec<(String, String)> = Vec::new();
+	for script in scripts(html.as_ref()) {
+		for cookie in Interpreter::new(&tokenize(script)).run() {
+			let pair = cookie.split(';').next().unwrap_or_default();
+			let Some((name, value)) = pair.split_once('=') else {
+				continue;
+			};
+			let (name, value) = (name.trim(), value.trim());
+			if name.is_empty() {
+				continue;
+			}
+			cookies.retain(|(existing, _)| existing != name);
+			cookies.push((name.into(), value.into()));
+		}
+	}
+	if cookies.is_empty() {
+		return None;
+	}
+	let pairs = cookies
+		.iter()
+		.map(|(name, value)| [name.as_str(), "=", value.as_str()].concat())
+		.collect::<Vec<_>>();
+	Some(pairs.join("; "))
+}
+
+/// The contents of every inline `<script>` element.
+fn scripts(html: &str) -> Vec<&str> {
+	let lower = html.to_ascii_lowercase();
+	let mut scripts = Vec::new();
+	let mut at = 0;
+	while let Some(open) = lower[at..].find("<script").map(|i| i + at) {
+		let Some(start) = lower[open..].find('>').map(|i| i + open + 1) else {
+			break;
+		};
+		let end = lower[start..]
+			.find("</script")
+			.map_or(html.len(), |i| i + start);
+		scripts.push(&html[start..end]);
+		at = end;
+	}
+	scripts
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+	Str(String),
+	Num(f64),
+	Ident(String),
+	Punct(char),
+}
+
+fn read_string(chars: &[char], quote: char, i: &mut usize) -> String {
+	let mut value = String::new();
+	while let Some(&c) = chars.get(*i) {
+		*i += 1;
+		match c {
+			c if c == quote => break,
+			'\\' => {
+				let Some(&escaped) = chars.get(*i) else {
+					break;
+				};
+				*i += 1;
+				let hex = |len: usize, i: &mut usize| {
+					let digits = chars.get(*i..*i + len)?.iter().collect::<String>();
+					let code = u32::from_str_radix(&digits, 16).ok()?;
+					*i += len;
+					char::from_u32(code)
+				};
+				match escaped {
+					'n' => value.push('\n'),
+					't' => value.push('\t'),
+					'r' => value.push('\r'),
+					'x' => value.extend(hex(2, i)),
+					'u' => value.extend(hex(4, i)),
+					c => value.push(c),
+				}
+			}
+			c => value.push(c),
+		}
+	}
+	value
+}
+
+fn tokenize(script: &str) -> Vec<Token> {
+	let chars = script.chars().collect::<Vec<_>>();
+	let mut tokens = Vec::new();
+	let mut i = 0;
+	while let Some(&c) = chars.get(i) {
+		let next = chars.get(i + 1).copied();
+		if c.is_whitespace() {
+			i += 1;
+		} else if c == '/' && next == Some('/') {
+			while chars.get(i).is_some_and(|&c| c != '\n') {
+				i += 1;
+			}
+		} else if c == '/' && next == Some('*') {
+			i += 2;
+			while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+				i += 1;
+			}
+			i += 2;
+		} else if c == '"' || c == '\'' || c == '`' {
+			i += 1;
+			tokens.push(Token::Str(read_string(&chars, c, &mut i)));
+		} else if c.is_ascii_digit() {
+			let start = i;
+			while chars
+				.get(i)
+				.is_some_and(|c| c.is_ascii_alphanumeric() || *c == '.')
+			{
+				i += 1;
+			}
+			let text = chars[start..i].iter().collect::<String>();
+			let value = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+				Some(hex) => u64::from_str_radix(hex, 16).ok().map(|n| n as f64),
+				None => text.parse().ok(),
+			};
+			tokens.push(value.map_or(Token::Ident(text), Token::Num));
+		} else if c.is_alphabetic() || c == '_' || c == '$' {
+			let start = i;
+			while chars
+				.get(i)
+				.is_some_and(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+			{
+				i += 1;
+			}
+			tokens.push(Token::Ident(chars[start..i].iter().collect()));
+		} else {
+			tokens.push(Token::Punct(c));
+			i += 1;
+		}
+	}
+	tokens
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+	Str(String),
+	Num(f64),
+	List(Vec<String>),
+}
+
+impl Value {
+	fn into_string(self) -> String {
+		match self {
+			Value::Str(value) => value,
+			Value::Num(value) if value == (value as i64) as f64 => {
+				alloc::format!("{}", value as i64)
+			}
+			Value::Num(value) => alloc::format!("{value}"),
+			Value::List(values) => values.join(","),
+		}
+	}
+
+	fn as_index(&self) -> Option<usize> {
+		match self {
+			Value::Num(value) if *value >= 0.0 => Some(*value as usize),
+			_ => None,
+		}
+	}
+}
+
+fn base64_decode(input: &str) -> Option<String> {
+	let mut bytes = Vec::with_capacity(input.len() * 3 / 4);
+	let (mut buffer, mut bits) = (0u32, 0);
+	for c in input
+		.bytes()
+		.filter(|c| !c.is_ascii_whitespace() && *c != b'=')
+	{
+		let value = match c {
+			b'A'..=b'Z' => c - b'A',
+			b'a'..=b'z' => c - b'a' + 26,
+			b'0'..=b'9' => c - b'0' + 52,
+			b'+' | b'-' => 62,
+			b'/' | b'_' => 63,
+			_ => return None,
+		};
+		buffer = (buffer << 6) | value as u32;
+		bits += 6;
+		if bits >= 8 {
+			bits -= 8;
+			bytes.push((buffer >> bits) as u8);
+		}
+	}
+	String::from_utf8(bytes).ok()
+}
+
+fn percent_decode(input: &str) -> String {
+	let bytes = input.as_bytes();
+	let mut decoded = Vec::with_capacity(bytes.len());
+	let mut i = 0;
+	while i < bytes.len() {
+		let hex = bytes
+			.get(i + 1..i + 3)
+			.and_then(|hex| core::str::from_utf8(hex).ok())
+			.and_then(|hex| u8::from_str_radix(hex, 16).ok());
+		match (bytes[i], hex) {
+			(b'%', Some(byte)) => {
+				decoded.push(byte);
+				i += 3;
+			}
+			(byte, _) => {
+				decoded.push(byte);
+				i += 1;
+			}
+		}
+	}
+	String::from_utf8_lossy(&decoded).into()
+}
+
+struct Interpreter<'a> {
+	tokens: &'a [Token],
+	at: usize,
+	variables: BTreeMap<String, Value>,
+}
+
+impl<'a> Interpreter<'a> {
+	fn new(tokens: &'a [Token]) -> Self {
+		Self {
+			tokens,
+			at: 0,
+			variables: BTreeMap::new(),
+		}
+	}
+
+	fn peek(&self, offset: usize) -> Option<&Token> {
+		self.tokens.get(self.at + offset)
+	}
+
+	fn is_punct(&self, offset: usize, c: char) -> bool {
+		self.peek(offset) == Some(&Token::Punct(c))
+	}
+
+	fn is_ident(&self, offset: usize, name: &str) -> bool {
+		matches!(self.peek(offset), Some(Token::Ident(ident)) if ident == name)
+	}
+
+	fn eat_punct(&mut self, c: char) -> Option<()> {
+		if self.is_punct(0, c) {
+			self.at += 1;
+			Some(())
+		} else {
+			None
+		}
+	}
+
+	fn ident(&mut self) -> Option<String> {
+		match self.peek(0) {
+			Some(Token::Ident(name)) => {
+				let name = name.clone();
+				self.at += 1;
+				Some(name)
+			}
+			_ => None,
+		}
+	}
+
+	/// Every value assigned to `document.cookie`, in order. Statements that
+	/// can't be evaluated are skipped.
+	fn run(mut self) -> Vec<String> {
+		let mut cookies = Vec::new();
+		while self.at < self.tokens.len() {
+			let start = self.at;
+			if self.is_ident(0, "document")
+				&& self.is_punct(1, '.')
+				&& self.is_ident(2, "cookie")
+				&& self.is_punct(3, '=')
+				&& !self.is_punct(4, '=')
+			{
+				self.at += 4;
+				if let Some(value) = self.expression() {
+					cookies.push(value.into_string());
+				}
+			} else if ["var", "let", "const"]
+				.iter()
+				.any(|keyword| self.is_ident(0, keyword))
+			{
+				self.at += 1;
+				self.assignments();
+			} else if matches!(self.peek(0), Some(Token::Ident(_)))
+				&& self.is_punct(1, '=')
+				&& !self.is_punct(2, '=')
+			{
+				self.assignments();
+			}
+			if self.at == start {
+				self.at += 1;
+			}
+		}
+		cookies
+	}
+
+	/// `a = expr, b = expr` after a declaration keyword.
+	fn assignments(&mut self) {
+		loop {
+			let Some(name) = self.ident() else {
+				return;
+			};
+			if self.eat_punct('=').is_none() {
+				return;
+			}
+			match self.expression() {
+				Some(value) => {
+					self.variables.insert(name, value);
+				}
+				None => {
+					self.variables.remove(&name);
+					return;
+				}
+			}
+			if self.eat_punct(',').is_none() {
+				return;
+			}
+		}
+	}
+
+	fn expression(&mut self) -> Option<Value> {
+		let mut value = self.postfix()?;
+		while self.is_punct(0, '+') && !self.is_punct(1, '+') && !self.is_punct(1, '=') {
+			self.at += 1;
+			let rhs = self.postfix()?;
+			value = match (value, rhs) {
+				(Value::Num(a), Value::Num(b)) => Value::Num(a + b),
+				(a, b) => Value::Str(a.into_string() + &b.into_string()),
+			};
+		}
+		Some(value)
+	}
+
+	fn arguments(&mut self) -> Option<Vec<Value>> {
+		self.eat_punct('(')?;
+		let mut arguments = Vec::new();
+		if self.eat_punct(')').is_some() {
+			return Some(arguments);
+		}
+		loop {
+			arguments.push(self.expression()?);
+			if self.eat_punct(')').is_some() {
+				return Some(arguments);
+			}
+			self.eat_punct(',')?;
+		}
+	}
+
+	fn postfix(&mut self) -> Option<Value> {
+		let mut value = self.primary()?;
+		while self.is_punct(0, '.') {
+			self.at += 1;
+			let method = self.ident()?;
+			let arguments = self.arguments()?;
+			let argument = |index: usize| arguments.get(index).cloned();
+			value = match (method.as_str(), value) {
+				("toString", value) => Value::Str(value.into_string()),
+				("split", value) => {
+					let value = value.into_string();
+					let separator = argument(0).map(Value::into_string).unwrap_or_default();
+					Value::List(if separator.is_empty() {
+						value.chars().map(String::from).collect()
+					} else {
+						value.split(separator.as_str()).map(String::from).collect()
+					})
+				}
+				("reverse", Value::List(mut values)) => {
+					values.reverse();
+					Value::List(values)
+				}
+				("join", Value::List(values)) => {
+					let separator =
+						argument(0).map_or_else(|| String::from(","), Value::into_string);
+					Value::Str(values.join(&separator))
+				}
+				("slice" | "substring" | "substr", value) => {
+					let chars = value.into_string().chars().collect::<Vec<_>>();
+					let start = argument(0)?.as_index()?.min(chars.len());
+					let end = match (method.as_str(), argument(1)) {
+						(_, None) => chars.len(),
+						("substr", Some(length)) => start + length.as_index()?,
+						(_, Some(end)) => end.as_index()?,
+					}
+					.clamp(start, chars.len());
+					Value::Str(chars[start..end].iter().collect())
+				}
+				("replace", value) => {
+					let (from, to) = (argument(0)?.into_string(), argument(1)?.into_string());
+					Value::Str(value.into_string().replacen(&from, &to, 1))
+				}
+				_ => return None,
+			};
+		}
+		Some(value)
+	}
+
+	fn primary(&mut self) -> Option<Value> {
+		match self.peek(0)?.clone() {
+			Token::Str(value) => {
+				self.at += 1;
+				Some(Value::Str(value))
+			}
+			Token::Num(value) => {
+				self.at += 1;
+				Some(Value::Num(value))
+			}
+			Token::Punct('(') => {
+				self.at += 1;
+				let value = self.expression()?;
+				self.eat_punct(')')?;
+				Some(value)
+			}
+			Token::Ident(name) => {
+				self.at += 1;
+				match name.as_str() {
+					"String" if self.is_punct(0, '.') && self.is_ident(1, "fromCharCode") => {
+						self.at += 2;
+						self.arguments()?
+							.into_iter()
+							.map(|code| match code {
+								Value::Num(code) => char::from_u32(code as u32),
+								_ => None,
+							})
+							.collect::<Option<String>>()
+							.map(Value::Str)
+					}
+					"atob" => {
+						let argument = self.arguments()?.into_iter().next()?;
+						base64_decode(&argument.into_string()).map(Value::Str)
+					}
+					"unescape" | "decodeURIComponent" | "decodeURI" => {
+						let argument = self.arguments()?.into_iter().next()?;
+						Some(Value::Str(percent_decode(&argument.into_string())))
+					}
+					_ => self.variables.get(&name).cloned(),
+				}
+			}
+			Token::Punct(_) => None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const VINAHOST: &str = r#"<html><body><script>document.cookie="D1N=a8e5"+"1f2c"+"9b7d";window.location.reload(true);</script></body></html>"#;
+
+	#[test]
+	fn detects_challenges() {
+		assert!(is_cookie_challenge(VINAHOST));
+		assert!(!is_cookie_challenge(
+			"<html><script>document.cookie='theme=dark'</script><div class='items'></div></html>"
+		));
+	}
+
+	#[test]
+	fn solves_concatenated_cookies() {
+		assert_eq!(
+			solve_cookie_challenge(VINAHOST).as_deref(),
+			Some("D1N=a8e51f2c9b7d")
+		);
+		let html = "<script type=\"text/javascript\">\n\
+			var a = 'tok', b = \"en\";\n\
+			document.cookie = 'visit=' + a + b + 42 + '; path=/; max-age=86400';\n\
+			document.cookie = \"js=1\";\n\
+			location.href = location.href;</script>";
+		assert_eq!(
+			solve_cookie_challenge(html).as_deref(),
+			Some("visit=token42; js=1")
+		);
+	}
+
+	#[test]
+	fn solves_encoded_cookies() {
+		let html = "<script>\
+			var k = String.fromCharCode(0x44, 49, 78);\
+			var v = atob('YWJjMTIz').split('').reverse().join('');\
+			document.cookie = k + '=' + v + unescape('%2D') + 'xyz'.substr(1, 1);\
+			window.location.replace('/');</script>";
+		assert_eq!(
+			solve_cookie_challenge(html).as_deref(),
+			Some("D1N=321cba-y")
+		);
+	}
+
+	#[test]
+	fn ignores_scripts_it_cant_evaluate() {
+		let html = "<script>document.cookie = 'a=' + compute();\
+			document.cookie = 'b=2';location.reload()</script>";
+		assert_eq!(solve_cookie_challenge(html).as_deref(), Some("b=2"));
+		assert_eq!(
+			solve_cookie_challenge("<script>location.reload()</script>"),
+			None
+		);
+	}
+}
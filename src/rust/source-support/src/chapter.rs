@@ -0,0 +1,315 @@
+//! Chapter and volume numbers from chapter names and URL slugs.
+//!
+//! Follows the rules of Tachiyomi's `ChapterRecognition` (which batoto's
+//! `chapter-recognition` dependency ports): the manga title is removed, volume
+//! and season numbers are skipped, a chapter keyword wins over the first
+//! number, and "a"–"i" or "extra"/"omake"/"special" suffixes become decimals.
+//! Unlike it, a name whose only number belongs to a volume or to a side story
+//! ("Vol. 3", "Side Story 2") has no chapter number.
+use alloc::{string::String, vec::Vec};
+
+const VOLUME_KEYWORDS: &[&str] = &["volumen", "volume", "tome", "tomo", "том", "vol", "v"];
+/// Numbered like volumes without being one; their numbers are skipped too.
+const IGNORED_KEYWORDS: &[&str] = &["version", "season", "ver", "s"];
+const CHAPTER_KEYWORDS: &[&str] = &[
+	"chapitre",
+	"chapter",
+	"capítulo",
+	"capitulo",
+	"chương",
+	"chuong",
+	"bölüm",
+	"bolum",
+	"глава",
+	"episode",
+	"chap",
+	"cap",
+	"ch",
+	"ep",
+];
+const SIDE_STORY_MARKERS: &[&str] = &["side story", "extra", "special", "omake", "bonus", "gaiden"];
+const SUFFIXES: &[(&str, f32)] = &[("extra", 0.99), ("omake", 0.98), ("special", 0.97)];
+
+struct Number {
+	start: usize,
+	/// End of the digits and decimals, before any suffix.
+	end: usize,
+	value: f32,
+	suffix: f32,
+}
+
+/// Lowercases `text`, removes `manga_title` from it and turns `,` and `-` into
+/// decimal points, gluing suffixes like " extra" to the number before them.
+fn normalize(manga_title: &str, text: &str) -> Vec<char> {
+	let mut text = text.to_lowercase();
+	let manga_title = manga_title.trim().to_lowercase();
+	if !manga_title.is_empty() {
+		text = text.replace(&manga_title, "");
+	}
+	let mut text = text.replace([',', '-'], ".");
+	for (suffix, _) in SUFFIXES {
+		text = text.replace(&[" ", suffix].concat(), suffix);
+	}
+	text.chars().collect()
+}
+
+fn starts_with(chars: &[char], at: usize, word: &str) -> bool {
+	let mut rest = chars.iter().skip(at);
+	word.chars().all(|c| rest.next() == Some(&c))
+}
+
+fn is_word_start(chars: &[char], at: usize) -> bool {
+	at == 0 || !chars[at - 1].is_alphanumeric()
+}
+
+fn contains_word(chars: &[char], word: &str) -> bool {
+	let len = word.chars().count();
+	(0..chars.len()).any(|i| {
+		is_word_start(chars, i)
+			&& starts_with(chars, i, word)
+			&& !chars.get(i + len).is_some_and(|c| c.is_alphabetic())
+	})
+}
+
+fn suffix_value(suffix: &str) -> f32 {
+	if let Some((_, value)) = SUFFIXES.iter().find(|(s, _)| suffix.contains(s)) {
+		return *value;
+	}
+	let mut chars = suffix.trim_start_matches('.').chars();
+	match (chars.next(), chars.next()) {
+		(Some(c @ 'a'..='i'), None) => (c as u32 - 'a' as u32 + 1) as f32 / 10.0,
+		_ => 0.0,
+	}
+}
+
+/// Reads `123`, `123.45` and an optional letter suffix like `123a` or
+/// `123.extra` starting at `start`.
+fn scan_number(chars: &[char], start: usize) -> Option<Number> {
+	let mut end = start;
+	while chars.get(end).is_some_and(|c| c.is_ascii_digit()) {
+		end += 1;
+	}
+	if end == start {
+		return None;
+	}
+	if chars.get(end) == Some(&'.') && chars.get(end + 1).is_some_and(|c| c.is_ascii_digit()) {
+		end += 1;
+		while chars.get(end).is_some_and(|c| c.is_ascii_digit()) {
+			end += 1;
+		}
+	}
+	let value = chars[start..end].iter().collect::<String>().parse().ok()?;
+
+	let mut suffix_end = end;
+	if chars.get(suffix_end) == Some(&'.') {
+		suffix_end += 1;
+	}
+	let letters = suffix_end;
+	while chars
+		.get(suffix_end)
+		.is_some_and(|c| c.is_ascii_alphabetic())
+	{
+		suffix_end += 1;
+	}
+	let suffix = if suffix_end > letters {
+		suffix_value(&chars[end..suffix_end].iter().collect::<String>())
+	} else {
+		0.0
+	};
+	Some(Number {
+		start,
+		end,
+		value,
+		suffix,
+	})
+}
+
+/// The number following one of `keywords` at `at`, as in "vol. 3" or "ch12".
+fn keyword_number(chars: &[char], at: usize, keywords: &[&str]) -> Option<Number> {
+	if !is_word_start(chars, at) {
+		return None;
+	}
+	let keyword = keywords.iter().find(|k| starts_with(chars, at, k))?;
+	let mut i = at + keyword.chars().count();
+	while chars
+		.get(i)
+		.is_some_and(|c| matches!(c, '.' | ' ' | ':' | '#' | '_'))
+	{
+		i += 1;
+	}
+	scan_number(chars, i).map(|number| Number {
+		start: at,
+		..number
+	})
+}
+
+fn find_keyword_number(chars: &[char], keywords: &[&str]) -> Option<Number> {
+	(0..chars.len()).find_map(|i| keyword_number(chars, i, keywords))
+}
+
+/// Blanks out volume and season numbers so they aren't taken for chapters.
+fn without_volumes(mut chars: Vec<char>) -> Vec<char> {
+	let keywords = [VOLUME_KEYWORDS, IGNORED_KEYWORDS].concat();
+	let mut i = 0;
+	while i < chars.len() {
+		match keyword_number(&chars, i, &keywords) {
+			Some(number) => {
+				chars[number.start..number.end].fill(' ');
+				i = number.end;
+			}
+			None => i += 1,
+		}
+	}
+	chars
+}
+
+fn first_number(chars: &[char]) -> Option<Number> {
+	(0..chars.len())
+		.filter(|&i| i == 0 || !chars[i - 1].is_ascii_digit())
+		.find_map(|i| scan_number(chars, i))
+}
+
+fn is_side_story(chars: &[char]) -> bool {
+	SIDE_STORY_MARKERS
+		.iter()
+		.any(|marker| contains_word(chars, marker))
+}
+
+/// The chapter number in `text` (usually a chapter's name), ignoring anything
+/// from `manga_title`. "Vol. 3 Ch. 12.5" gives 12.5 and "Chapter 10 Extra"
+/// gives 10.99.
+pub fn parse_chapter_number<T: AsRef<str>, U: AsRef<str>>(manga_title: T, text: U) -> Option<f32> {
+	let chars = without_volumes(normalize(manga_title.as_ref(), text.as_ref()));
+	let number = find_keyword_number(&chars, CHAPTER_KEYWORDS).or_else(|| first_number(&chars))?;
+	if is_side_story(&chars[..number.start]) {
+		return None;
+	}
+	Some(number.value + number.suffix)
+}
+
+/// The volume number in `text`, as in "Vol. 3 Ch. 12" or "Tome 2".
+pub fn parse_volume_number<T: AsRef<str>, U: AsRef<str>>(manga_title: T, text: U) -> Option<f32> {
+	let chars = normalize(manga_title.as_ref(), text.as_ref());
+	find_keyword_number(&chars, VOLUME_KEYWORDS).map(|number| number.value)
+}
+
+/// Slug segments with their words separated by spaces. Two trailing numbers in
+/// the last segment are a decimal, so "chapitre-062-5" reads "chapitre 062.5";
+/// anything after a `_` in a word is dropped, as in "chapter-12_2".
+fn slug_segments(slug: &str) -> Vec<String> {
+	let segments = slug
+		.split('/')
+		.filter(|s| !s.is_empty())
+		.collect::<Vec<_>>();
+	let last = segments.len().saturating_sub(1);
+	segments
+		.iter()
+		.enumerate()
+		.map(|(index, segment)| {
+			let mut words = segment
+				.split('-')
+				.map(|word| String::from(word.split('_').next().unwrap_or(word)))
+				.collect::<Vec<_>>();
+			let is_number =
+				|word: &String| !word.is_empty() && word.bytes().all(|b| b.is_ascii_digit());
+			if index == last && words.len() >= 2 && words[words.len() - 2..].iter().all(is_number) {
+				let decimals = words.pop().unwrap_or_default();
+				if let Some(integer) = words.last_mut() {
+					integer.push('.');
+					integer.push_str(&decimals);
+				}
+			}
+			words.join(" ")
+		})
+		.collect()
+}
+
+/// The chapter number in the last segment of a chapter URL path, like
+/// "manga/chapitre-062-5/" (62.5) or "manga/chapter-177-30-million/" (177).
+pub fn parse_slug_chapter_number<T: AsRef<str>>(slug: T) -> Option<f32> {
+	let segments = slug_segments(slug.as_ref());
+	parse_chapter_number("", segments.last()?)
+}
+
+/// The volume number in a chapter URL path like "manga/volume-10/chapter-62/".
+/// The first segment is skipped when there are others, since it's usually the
+/// manga's own slug.
+pub fn parse_slug_volume_number<T: AsRef<str>>(slug: T) -> Option<f32> {
+	let segments = slug_segments(slug.as_ref());
+	let skip = usize::from(segments.len() > 1);
+	parse_volume_number("", segments[skip..].join(" / "))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn recognizes_chapter_names() {
+		assert_eq!(parse_chapter_number("", "Vol. 3 Ch. 12.5"), Some(12.5));
+		assert_eq!(parse_chapter_number("", "Vol.2-Ch.5"), Some(5.0));
+		assert_eq!(parse_chapter_number("", "Chapter 10"), Some(10.0));
+		assert_eq!(
+			parse_chapter_number("", "Chapter 10-5 - The End"),
+			Some(10.5)
+		);
+		assert_eq!(parse_chapter_number("", "Chapitre 62,5"), Some(62.5));
+		assert_eq!(
+			parse_chapter_number("", "Chapter 177 - 30 Million vs 81 Million"),
+			Some(177.0)
+		);
+		assert_eq!(
+			parse_chapter_number("Kaiju No. 8", "Kaiju No. 8 12"),
+			Some(12.0)
+		);
+		assert_eq!(parse_chapter_number("", "12a"), Some(12.1));
+		assert_eq!(
+			parse_chapter_number("", "Volumen 4 Capítulo 30"),
+			Some(30.0)
+		);
+		assert_eq!(parse_chapter_number("", "Том 2 Глава 15"), Some(15.0));
+		assert_eq!(parse_chapter_number("", "Prologue"), None);
+	}
+
+	#[test]
+	fn recognizes_extras() {
+		assert_eq!(parse_chapter_number("", "Chapter 10 Extra"), Some(10.99));
+		assert_eq!(
+			parse_chapter_number("", "Chapter 120 - Side Story 1"),
+			Some(120.0)
+		);
+		assert_eq!(parse_chapter_number("", "Side Story 2"), None);
+		assert_eq!(parse_chapter_number("", "Extra 3"), None);
+		assert_eq!(parse_chapter_number("", "Vol. 3"), None);
+		assert_eq!(parse_chapter_number("", "Extraordinary 4"), Some(4.0));
+	}
+
+	#[test]
+	fn recognizes_volumes() {
+		assert_eq!(parse_volume_number("", "Vol. 3 Ch. 12.5"), Some(3.0));
+		assert_eq!(parse_volume_number("", "Tome 2 - Chapitre 10"), Some(2.0));
+		assert_eq!(parse_volume_number("", "Season 2 Episode 3"), None);
+		assert_eq!(parse_volume_number("", "Chapter 10"), None);
+		assert_eq!(parse_volume_number("", "Volumen 4 Capítulo 30"), Some(4.0));
+		assert_eq!(parse_volume_number("", "Том 2 Глава 15"), Some(2.0));
+	}
+
+	#[test]
+	fn recognizes_slugs() {
+		let slug = "parasite-chromatique-french/volume-10/chapitre-062-5/";
+		assert_eq!(parse_slug_chapter_number(slug), Some(62.5));
+		assert_eq!(parse_slug_volume_number(slug), Some(10.0));
+
+		let slug = "one-piece/volume-20-showdown/chapter-177-30-million-vs-81-million/";
+		assert_eq!(parse_slug_chapter_number(slug), Some(177.0));
+		assert_eq!(parse_slug_volume_number(slug), Some(20.0));
+
+		assert_eq!(
+			parse_slug_chapter_number("manga/chapter-10-25/"),
+			Some(10.25)
+		);
+		assert_eq!(parse_slug_chapter_number("manga/chapter-12_2/"), Some(12.0));
+		assert_eq!(parse_slug_chapter_number("manga/side-story-3/"), None);
+		assert_eq!(parse_slug_volume_number("volume-manga/chapter-1/"), None);
+	}
+}
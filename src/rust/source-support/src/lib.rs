@@ -0,0 +1,27 @@
+//! Helpers shared by templates and standalone sources.
+//!
+//! Nothing in here talks to the host, so the crate builds and runs its tests
+//! on any target.
+#![cfg_attr(not(test), no_std)]
+extern crate alloc;
+
+pub mod cache;
+pub mod category;
+pub mod challenge;
+pub mod chapter;
+pub mod text;
+pub mod throttle;
+pub mod url;
+
+pub use cache::TtlCache;
+pub use category::{CategoryRules, Layout, Rating};
+pub use challenge::{is_cookie_challenge, solve_cookie_challenge};
+pub use chapter::{
+	parse_chapter_number, parse_slug_chapter_number, parse_slug_volume_number, parse_volume_number,
+};
+pub use text::{
+	extract_f32_from_string, first_f32_from_string, i32_to_string, mark_line_breaks,
+	restore_line_breaks, text_with_newlines, LINE_BREAK_MARKER,
+};
+pub use throttle::{parse_retry_after, RateLimit, TokenBucket};
+pub use url::{append_protocol, encode_uri, img_url_encode, urlencode};
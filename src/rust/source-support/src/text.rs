@@ -0,0 +1,160 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+/// Placeholder that survives `Node::text()`, used to keep line breaks that
+/// would otherwise be collapsed into spaces.
+pub const LINE_BREAK_MARKER: &str = "{{ .LINEBREAK }}";
+
+/// Extracts every number in `text`, after removing `title` from it. A `.` only
+/// counts as a decimal point between two digits, so "Vol.3 Ch.12.5" gives
+/// `[3.0, 12.5]`.
+pub fn extract_f32_from_string<T: AsRef<str>, U: AsRef<str>>(title: T, text: U) -> Vec<f32> {
+	let title = title.as_ref();
+	let text = text.as_ref();
+	let text = if title.is_empty() {
+		String::from(text)
+	} else {
+		text.replace(title, "")
+	};
+
+	let chars = text.chars().collect::<Vec<_>>();
+	let mut numbers = Vec::new();
+	let mut current = String::new();
+	for (i, &c) in chars.iter().enumerate() {
+		let is_decimal_point = c == '.'
+			&& !current.is_empty()
+			&& !current.contains('.')
+			&& chars.get(i + 1).is_some_and(|c| c.is_ascii_digit());
+		if c.is_ascii_digit() || is_decimal_point {
+			current.push(c);
+		} else if !current.is_empty() {
+			numbers.extend(current.parse::<f32>().ok());
+			current.clear();
+		}
+	}
+	if !current.is_empty() {
+		numbers.extend(current.parse::<f32>().ok());
+	}
+	numbers
+}
+
+/// The first number in `text` after removing `title` from it, if any.
+pub fn first_f32_from_string<T: AsRef<str>, U: AsRef<str>>(title: T, text: U) -> Option<f32> {
+	extract_f32_from_string(title, text).first().copied()
+}
+
+pub fn i32_to_string(integer: i32) -> String {
+	let mut string = String::with_capacity(11);
+	write!(string, "{integer}").ok();
+	string
+}
+
+/// Replaces every form of `<br>` in `html` with [`LINE_BREAK_MARKER`].
+pub fn mark_line_breaks<T: AsRef<str>>(html: T) -> String {
+	let html = html.as_ref();
+	let lower = html.to_ascii_lowercase();
+	let mut result = String::with_capacity(html.len());
+	let mut last = 0;
+	let mut search = 0;
+	while let Some(start) = lower[search..].find("<br").map(|i| i + search) {
+		let after = &lower[start + 3..];
+		let is_br = after
+			.chars()
+			.next()
+			.is_some_and(|c| c == '>' || c == '/' || c.is_ascii_whitespace());
+		match after.find('>') {
+			Some(end) if is_br => {
+				result.push_str(&html[last..start]);
+				result.push_str(LINE_BREAK_MARKER);
+				last = start + 3 + end + 1;
+				search = last;
+			}
+			_ => search = start + 3,
+		}
+	}
+	result.push_str(&html[last..]);
+	result
+}
+
+/// Turns the markers left by [`mark_line_breaks`] back into newlines, dropping
+/// the whitespace that text extraction leaves around them.
+pub fn restore_line_breaks<T: AsRef<str>>(text: T) -> String {
+	text.as_ref()
+		.split(LINE_BREAK_MARKER)
+		.map(|line| line.trim())
+		.collect::<Vec<_>>()
+		.join("\n")
+		.trim()
+		.into()
+}
+
+/// The text of `html` with its line breaks kept. Parsing HTML needs the host,
+/// so `text` is given the marked-up fragment and returns its text.
+pub fn text_with_newlines<T, F>(html: T, text: F) -> String
+where
+	T: AsRef<str>,
+	F: FnOnce(&str) -> Option<String>,
+{
+	let html = html.as_ref();
+	if html.trim().is_empty() {
+		return String::new();
+	}
+	text(&mark_line_breaks(html))
+		.map(restore_line_breaks)
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn extracts_numbers() {
+		assert_eq!(extract_f32_from_string("", "Chapter 10.5"), [10.5]);
+		assert_eq!(extract_f32_from_string("", "Vol.3 Ch.12.5"), [3.0, 12.5]);
+		assert_eq!(extract_f32_from_string("", "ch-10+11"), [10.0, 11.0]);
+		assert_eq!(extract_f32_from_string("", "Chapter 7."), [7.0]);
+		assert!(extract_f32_from_string("", "Oneshot").is_empty());
+	}
+
+	#[test]
+	fn removes_title_before_extracting() {
+		assert_eq!(
+			extract_f32_from_string("Kaiju No. 8", "Kaiju No. 8 Chapter 99"),
+			[99.0]
+		);
+		assert_eq!(first_f32_from_string("Title 2", "Title 2 - 3.5"), Some(3.5));
+		assert_eq!(first_f32_from_string("", "Extra"), None);
+	}
+
+	#[test]
+	fn formats_integers() {
+		assert_eq!(i32_to_string(0), "0");
+		assert_eq!(i32_to_string(-42), "-42");
+		assert_eq!(i32_to_string(i32::MIN), "-2147483648");
+	}
+
+	#[test]
+	fn marks_all_br_forms() {
+		assert_eq!(
+			mark_line_breaks("a<br>b<BR/>c<br />d<bride>"),
+			"a{{ .LINEBREAK }}b{{ .LINEBREAK }}c{{ .LINEBREAK }}d<bride>"
+		);
+	}
+
+	#[test]
+	fn restores_line_breaks() {
+		assert_eq!(
+			restore_line_breaks(" a {{ .LINEBREAK }} b{{ .LINEBREAK }}c "),
+			"a\nb\nc"
+		);
+	}
+
+	#[test]
+	fn keeps_line_breaks_in_text() {
+		let strip_tags = |html: &str| Some(html.replace("<p>", "").replace("</p>", ""));
+		assert_eq!(text_with_newlines("<p>a<br>b</p>", strip_tags), "a\nb");
+		assert_eq!(text_with_newlines("  ", strip_tags), "");
+		assert_eq!(text_with_newlines("<p>a</p>", |_| None), "");
+	}
+}
@@ -0,0 +1,190 @@
+//! Token-bucket throttling and backoff, kept free of the host so the
+//! arithmetic can be tested anywhere. `source-net` applies it to requests.
+
+/// How often a source may hit its site, and how long it holds off after the
+/// site answers 429 or 503. Times are in seconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+	/// Names the bucket in the source's defaults, so that limits for different
+	/// endpoints don't share tokens.
+	pub name: &'static str,
+	/// Requests allowed per `period`, which is also the burst size.
+	pub requests: u32,
+	pub period: f64,
+	/// First pause after a 429 or 503, doubled for every one in a row.
+	pub backoff: f64,
+	/// Upper bound for any pause, `Retry-After` included.
+	pub max_pause: f64,
+	/// How many times a request is re-sent after being throttled.
+	pub retries: u32,
+	/// The longest a request waits before being re-sent. Sources can only
+	/// wait by spinning, so longer waits fail the request instead.
+	pub max_wait: f64,
+}
+
+impl RateLimit {
+	pub const fn new(name: &'static str, requests: u32, period: f64) -> Self {
+		Self {
+			name,
+			requests,
+			period,
+			backoff: 1.0,
+			max_pause: 30.0,
+			retries: 2,
+			max_wait: 5.0,
+		}
+	}
+
+	/// Whether a response with this status means the site wants a break.
+	pub fn should_back_off(status: i32) -> bool {
+		status == 429 || status == 503
+	}
+
+	/// How long to hold off after `failures` 429/503 responses in a row before
+	/// this one. A `Retry-After` from the site wins over the backoff, but is
+	/// still capped at `max_pause`.
+	pub fn pause(&self, failures: u32, retry_after: Option<f64>) -> f64 {
+		let backoff = self.backoff * (1u32 << failures.min(16)) as f64;
+		retry_after.unwrap_or(backoff).clamp(0.0, self.max_pause)
+	}
+
+	/// How long to wait before re-sending a request that was throttled
+	/// `attempt` times before, or `None` if it should fail instead.
+	pub fn retry_delay(&self, attempt: u32, wait: f64) -> Option<f64> {
+		(attempt < self.retries && wait <= self.max_wait).then_some(wait.max(0.0))
+	}
+}
+
+/// A token bucket refilled continuously at `requests / period` tokens per
+/// second, holding at most `requests` tokens.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TokenBucket {
+	pub tokens: f64,
+	/// When `tokens` was last brought up to date, or when a pause ends.
+	pub updated: f64,
+}
+
+impl TokenBucket {
+	pub fn full(limit: &RateLimit, now: f64) -> Self {
+		Self {
+			tokens: limit.requests as f64,
+			updated: now,
+		}
+	}
+
+	/// Takes a token if there is one. Sources can't wait for the next one, so
+	/// a request that finds the bucket empty isn't sent at all.
+	pub fn try_take(&mut self, limit: &RateLimit, now: f64) -> bool {
+		if limit.requests == 0 || limit.period <= 0.0 {
+			return true;
+		}
+		let capacity = limit.requests as f64;
+		let rate = capacity / limit.period;
+		let elapsed = (now - self.updated).max(0.0);
+		self.tokens = (self.tokens + elapsed * rate).min(capacity);
+		self.updated = self.updated.max(now);
+
+		if self.tokens >= 1.0 {
+			self.tokens -= 1.0;
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Seconds until the bucket has a token again.
+	pub fn wait(&self, limit: &RateLimit, now: f64) -> f64 {
+		if limit.requests == 0 || limit.period <= 0.0 {
+			return 0.0;
+		}
+		let rate = limit.requests as f64 / limit.period;
+		let paused = (self.updated - now).max(0.0);
+		let elapsed = (now - self.updated).max(0.0);
+		let missing = (1.0 - self.tokens - elapsed * rate).max(0.0);
+		paused + missing / rate
+	}
+
+	/// Empties the bucket and keeps it from refilling for `seconds`.
+	pub fn pause(&mut self, seconds: f64, now: f64) {
+		self.tokens = 0.0;
+		self.updated = self.updated.max(now + seconds);
+	}
+}
+
+/// Reads a `Retry-After` header given in seconds. The HTTP-date form needs
+/// the host's date parsing and is left to the caller.
+pub fn parse_retry_after<T: AsRef<str>>(value: T) -> Option<f64> {
+	value
+		.as_ref()
+		.trim()
+		.parse::<u32>()
+		.ok()
+		.map(|seconds| seconds as f64)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn bucket_allows_bursts_then_refuses() {
+		let limit = RateLimit::new("site", 2, 1.0);
+		let mut bucket = TokenBucket::full(&limit, 100.0);
+		assert!(bucket.try_take(&limit, 100.0));
+		assert!(bucket.try_take(&limit, 100.0));
+		assert!(!bucket.try_take(&limit, 100.0));
+		// A refused request doesn't use up the token refilling meanwhile.
+		assert!(bucket.try_take(&limit, 100.5));
+		assert!(!bucket.try_take(&limit, 100.5));
+		assert!(bucket.try_take(&limit, 110.0));
+	}
+
+	#[test]
+	fn paused_bucket_stays_empty() {
+		let limit = RateLimit::new("site", 2, 1.0);
+		let mut bucket = TokenBucket::full(&limit, 100.0);
+		bucket.pause(10.0, 100.0);
+		assert!(!bucket.try_take(&limit, 105.0));
+		assert!(!bucket.try_take(&limit, 110.0));
+		assert!(bucket.try_take(&limit, 110.5));
+	}
+
+	#[test]
+	fn waits_for_pauses_and_refills() {
+		let limit = RateLimit::new("site", 2, 1.0);
+		let mut bucket = TokenBucket::full(&limit, 100.0);
+		assert_eq!(bucket.wait(&limit, 100.0), 0.0);
+		bucket.try_take(&limit, 100.0);
+		bucket.try_take(&limit, 100.0);
+		assert_eq!(bucket.wait(&limit, 100.0), 0.5);
+		assert_eq!(bucket.wait(&limit, 100.25), 0.25);
+		bucket.pause(10.0, 100.0);
+		assert_eq!(bucket.wait(&limit, 104.0), 6.5);
+	}
+
+	#[test]
+	fn retries_are_bounded() {
+		let limit = RateLimit::new("site", 1, 1.0);
+		assert_eq!(limit.retry_delay(0, 1.5), Some(1.5));
+		assert_eq!(limit.retry_delay(1, 0.0), Some(0.0));
+		assert_eq!(limit.retry_delay(2, 1.0), None);
+		assert_eq!(limit.retry_delay(0, 10.0), None);
+	}
+
+	#[test]
+	fn backoff_doubles_and_is_bounded() {
+		let limit = RateLimit::new("site", 1, 1.0);
+		assert_eq!(limit.pause(0, None), 1.0);
+		assert_eq!(limit.pause(2, None), 4.0);
+		assert_eq!(limit.pause(8, None), 30.0);
+		assert_eq!(limit.pause(0, Some(120.0)), 30.0);
+		assert!(RateLimit::should_back_off(429));
+		assert!(!RateLimit::should_back_off(404));
+	}
+
+	#[test]
+	fn parses_retry_after_seconds() {
+		assert_eq!(parse_retry_after(" 10 "), Some(10.0));
+		assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+	}
+}
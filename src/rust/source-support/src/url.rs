@@ -0,0 +1,109 @@
+use alloc::{string::String, vec::Vec};
+
+const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+fn percent_encode(bytes: &[u8], keep: impl Fn(&[u8], usize) -> bool) -> String {
+	let mut result: Vec<u8> = Vec::with_capacity(bytes.len() * 3);
+	for (i, &byte) in bytes.iter().enumerate() {
+		if keep(bytes, i) {
+			result.push(byte);
+		} else {
+			result.push(b'%');
+			result.push(HEX[byte as usize >> 4]);
+			result.push(HEX[byte as usize & 15]);
+		}
+	}
+	// Only ASCII bytes are kept as-is, so this is always valid UTF-8.
+	String::from_utf8(result).unwrap_or_default()
+}
+
+fn is_unreserved(byte: u8) -> bool {
+	byte.is_ascii_alphanumeric() || b"-_.!~*'()".contains(&byte)
+}
+
+fn is_escape(bytes: &[u8], i: usize) -> bool {
+	bytes[i] == b'%'
+		&& bytes.len() > i + 2
+		&& bytes[i + 1].is_ascii_hexdigit()
+		&& bytes[i + 2].is_ascii_hexdigit()
+}
+
+/// Encodes a query value or path segment, like JavaScript's
+/// `encodeURIComponent`.
+pub fn urlencode<T: AsRef<[u8]>>(string: T) -> String {
+	percent_encode(string.as_ref(), |bytes, i| is_unreserved(bytes[i]))
+}
+
+/// Encodes a full URL, leaving its structure intact, like JavaScript's
+/// `encodeURI`.
+pub fn encode_uri<T: AsRef<[u8]>>(url: T) -> String {
+	percent_encode(url.as_ref(), |bytes, i| {
+		is_unreserved(bytes[i]) || b";,/?:@&=+$#".contains(&bytes[i])
+	})
+}
+
+/// Encodes an image file name (or any other path segment that may already be
+/// partially encoded). Existing `%XX` escapes are left alone so that they
+/// aren't encoded twice.
+pub fn img_url_encode<T: AsRef<[u8]>>(string: T) -> String {
+	percent_encode(string.as_ref(), |bytes, i| {
+		is_unreserved(bytes[i]) || is_escape(bytes, i)
+	})
+}
+
+/// Turns protocol-relative (`//host/path`) and scheme-less (`host/path`) URLs
+/// into `https` ones.
+pub fn append_protocol<T: AsRef<str>>(url: T) -> String {
+	let url = url.as_ref();
+	if url.starts_with("https://") || url.starts_with("http://") {
+		String::from(url)
+	} else if let Some(url) = url.strip_prefix("//") {
+		["https://", url].concat()
+	} else {
+		[
+			"https://",
+			url.trim_start_matches(':').trim_start_matches('/'),
+		]
+		.concat()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn urlencode_encodes_everything_but_unreserved() {
+		assert_eq!(urlencode("a b&c=d/é"), "a%20b%26c%3Dd%2F%C3%A9");
+		assert_eq!(urlencode("A-z_0.9~!*'()"), "A-z_0.9~!*'()");
+	}
+
+	#[test]
+	fn encode_uri_keeps_url_structure() {
+		assert_eq!(
+			encode_uri("https://a.com/p ath?q=ü#x"),
+			"https://a.com/p%20ath?q=%C3%BC#x"
+		);
+	}
+
+	#[test]
+	fn img_url_encode_uses_uppercase_hex_and_keeps_escapes() {
+		assert_eq!(img_url_encode("01 é.jpg"), "01%20%C3%A9.jpg");
+		assert_eq!(img_url_encode("01%20a.jpg"), "01%20a.jpg");
+		assert_eq!(img_url_encode("100%.jpg"), "100%25.jpg");
+	}
+
+	#[test]
+	fn append_protocol_handles_relative_urls() {
+		assert_eq!(
+			append_protocol("//cdn.a.com/x.jpg"),
+			"https://cdn.a.com/x.jpg"
+		);
+		assert_eq!(append_protocol("http://a.com"), "http://a.com");
+		assert_eq!(append_protocol("https://a.com"), "https://a.com");
+		assert_eq!(
+			append_protocol("cdn.a.com/x.jpg"),
+			"https://cdn.a.com/x.jpg"
+		);
+	}
+}
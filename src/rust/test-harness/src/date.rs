@@ -0,0 +1,221 @@
+//! `std.read_date_string`: parses dates with the Unicode (`DateFormatter`)
+//! patterns sources pass to `ValueRef::as_date`.
+//!
+//! Only English month and weekday names are understood, and named time zones
+//! other than UTC/GMT are treated as UTC.
+const MONTHS: [&str; 12] = [
+	"january",
+	"february",
+	"march",
+	"april",
+	"may",
+	"june",
+	"july",
+	"august",
+	"september",
+	"october",
+	"november",
+	"december",
+];
+
+enum Token {
+	Field(char, usize),
+	Literal(String),
+}
+
+fn tokenize(format: &str) -> Vec<Token> {
+	let mut tokens = Vec::new();
+	let mut chars = format.chars().peekable();
+	while let Some(c) = chars.next() {
+		if c == '\'' {
+			let mut literal = String::new();
+			for c in chars.by_ref() {
+				if c == '\'' {
+					break;
+				}
+				literal.push(c);
+			}
+			tokens.push(Token::Literal(literal));
+		} else if c.is_ascii_alphabetic() {
+			let mut count = 1;
+			while chars.next_if_eq(&c).is_some() {
+				count += 1;
+			}
+			tokens.push(Token::Field(c, count));
+		} else {
+			tokens.push(Token::Literal(String::from(c)));
+		}
+	}
+	tokens
+}
+
+struct Cursor<'a> {
+	rest: &'a str,
+}
+
+impl Cursor<'_> {
+	fn number(&mut self, max_digits: usize) -> Option<i64> {
+		let len = self
+			.rest
+			.chars()
+			.take(max_digits)
+			.take_while(|c| c.is_ascii_digit())
+			.count();
+		let (digits, rest) = self.rest.split_at(len);
+		self.rest = rest;
+		digits.parse().ok()
+	}
+
+	fn word(&mut self) -> String {
+		let len = self
+			.rest
+			.find(|c: char| !c.is_alphabetic())
+			.unwrap_or(self.rest.len());
+		let (word, rest) = self.rest.split_at(len);
+		self.rest = rest;
+		word.to_lowercase()
+	}
+
+	fn literal(&mut self, literal: &str) -> Option<()> {
+		for c in literal.chars() {
+			if c.is_whitespace() {
+				self.rest = self.rest.trim_start();
+			} else {
+				let next = self.rest.chars().next()?;
+				if !next.to_lowercase().eq(c.to_lowercase()) {
+					return None;
+				}
+				self.rest = &self.rest[next.len_utf8()..];
+			}
+		}
+		Some(())
+	}
+
+	/// `Z`, `+07`, `+0700` or `+07:00`, in seconds east of UTC.
+	fn offset(&mut self) -> Option<i64> {
+		if let Some(rest) = self.rest.strip_prefix('Z') {
+			self.rest = rest;
+			return Some(0);
+		}
+		let sign = match self.rest.chars().next()? {
+			'+' => 1,
+			'-' => -1,
+			_ => return None,
+		};
+		self.rest = &self.rest[1..];
+		let hours = self.number(2)?;
+		if let Some(rest) = self.rest.strip_prefix(':') {
+			self.rest = rest;
+		}
+		let minutes = self.number(2).unwrap_or(0);
+		Some(sign * (hours * 3600 + minutes * 60))
+	}
+}
+
+fn parse_timezone(timezone: &str) -> i64 {
+	let offset = timezone.trim_start_matches("UTC").trim_start_matches("GMT");
+	Cursor { rest: offset }.offset().unwrap_or(0)
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+	let year = if month <= 2 { year - 1 } else { year };
+	let era = year.div_euclid(400);
+	let year_of_era = year - era * 400;
+	let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+	let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+	era * 146097 + day_of_era - 719468
+}
+
+/// Seconds since the Unix epoch, or `None` if `value` doesn't match `format`.
+pub fn parse(value: &str, format: &str, timezone: Option<&str>) -> Option<f64> {
+	let mut cursor = Cursor { rest: value.trim() };
+	let (mut year, mut month, mut day) = (1970, 1, 1);
+	let (mut hour, mut minute, mut second, mut fraction) = (0, 0, 0, 0.0);
+	let mut pm = None;
+	let mut offset = timezone.map(parse_timezone).unwrap_or(0);
+
+	for token in tokenize(format) {
+		match token {
+			Token::Literal(literal) => cursor.literal(&literal)?,
+			Token::Field('y', 2) => year = 2000 + cursor.number(2)?,
+			Token::Field('y', _) => year = cursor.number(4)?,
+			Token::Field('M' | 'L', count) if count >= 3 => {
+				let word = cursor.word();
+				let index = MONTHS.iter().position(|name| {
+					if count == 3 {
+						word.len() >= 3 && name.starts_with(&word)
+					} else {
+						*name == word
+					}
+				})?;
+				month = index as i64 + 1;
+			}
+			Token::Field('M' | 'L', count) => month = cursor.number(count.max(2))?,
+			Token::Field('d', count) => day = cursor.number(count.max(2))?,
+			Token::Field('H' | 'h' | 'k' | 'K', count) => hour = cursor.number(count.max(2))?,
+			Token::Field('m', count) => minute = cursor.number(count.max(2))?,
+			Token::Field('s', count) => second = cursor.number(count.max(2))?,
+			Token::Field('S', count) => {
+				let digits = cursor.number(count)?;
+				fraction = digits as f64 / 10f64.powi(count as i32);
+			}
+			Token::Field('a', _) => {
+				pm = match cursor.word().as_str() {
+					"am" => Some(false),
+					"pm" => Some(true),
+					_ => return None,
+				}
+			}
+			Token::Field('E', _) => {
+				cursor.word();
+			}
+			Token::Field('Z' | 'X' | 'x', _) => offset = cursor.offset()?,
+			Token::Field(c, count) => cursor.literal(&c.to_string().repeat(count))?,
+		}
+	}
+	if !cursor.rest.is_empty() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+		return None;
+	}
+	match pm {
+		Some(true) if hour < 12 => hour += 12,
+		Some(false) if hour == 12 => hour = 0,
+		_ => {}
+	}
+
+	let days = days_from_civil(year, month, day);
+	let seconds = days * 86400 + hour * 3600 + minute * 60 + second - offset;
+	Some(seconds as f64 + fraction)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_numeric_dates() {
+		assert_eq!(parse("2024-1-2", "yyyy-M-d", None), Some(1704153600.0));
+		assert_eq!(parse("02/01/24", "dd/MM/yy", None), Some(1704153600.0));
+		assert_eq!(
+			parse("2024-01-02T03:04:05.5Z", "yyyy-MM-dd'T'HH:mm:ss.SX", None),
+			Some(1704164645.5)
+		);
+		assert_eq!(parse("2024-01-02", "yyyy/MM/dd", None), None);
+	}
+
+	#[test]
+	fn parses_names_and_zones() {
+		assert_eq!(
+			parse("Tuesday, January 2, 2024", "EEEE, MMMM d, yyyy", None),
+			Some(1704153600.0)
+		);
+		assert_eq!(
+			parse("Jan 2, 2024", "MMM d, yyyy", None),
+			Some(1704153600.0)
+		);
+		assert_eq!(
+			parse("2024-01-02 7:00 pm", "yyyy-MM-dd h:mm a", Some("GMT+07:00")),
+			Some(1704196800.0)
+		);
+	}
+}
@@ -0,0 +1,55 @@
+use std::{fmt, io, path::PathBuf};
+
+#[derive(Debug)]
+pub enum Error {
+	Io(PathBuf, io::Error),
+	Fixture(String),
+	/// The source wasn't built for `wasm32-unknown-unknown` yet.
+	NotBuilt(PathBuf),
+	Wasm(wasmi::Error),
+	MissingExport(&'static str),
+	/// The source returned an error. `unmatched` lists the requests it made
+	/// that had no fixture, which is usually why.
+	Source {
+		function: &'static str,
+		unmatched: Vec<String>,
+	},
+	UnexpectedResult(&'static str),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Io(path, error) => write!(f, "{}: {error}", path.display()),
+			Self::Fixture(message) => write!(f, "invalid fixture: {message}"),
+			Self::NotBuilt(path) => write!(
+				f,
+				"{} doesn't exist, build the source with ./build.sh first",
+				path.display()
+			),
+			Self::Wasm(error) => write!(f, "{error}"),
+			Self::MissingExport(name) => write!(f, "the source doesn't export `{name}`"),
+			Self::Source {
+				function,
+				unmatched,
+			} => {
+				write!(f, "`{function}` returned an error")?;
+				if !unmatched.is_empty() {
+					write!(f, "; requests without a fixture: {}", unmatched.join(", "))?;
+				}
+				Ok(())
+			}
+			Self::UnexpectedResult(function) => {
+				write!(f, "`{function}` returned an unexpected value")
+			}
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<wasmi::Error> for Error {
+	fn from(error: wasmi::Error) -> Self {
+		Self::Wasm(error)
+	}
+}
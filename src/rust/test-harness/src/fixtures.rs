@@ -0,0 +1,169 @@
+//! Recorded responses served in place of the network.
+//!
+//! A fixture directory holds the recorded bodies next to a `fixtures.json`
+//! manifest:
+//!
+//! ```json
+//! [
+//!   { "url": "https://xkcd.com/archive", "file": "archive.html" },
+//!   { "method": "POST", "url": "https://example.com/api", "status": 429, "headers": { "Retry-After": "1" } },
+//!   { "method": "POST", "url": "https://example.com/api", "file": "api.json" }
+//! ]
+//! ```
+//!
+//! `method` defaults to `GET`, `status` to 200 and the body to empty. When
+//! several fixtures match a request they're served in order, and the last one
+//! keeps being served after that.
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+
+use crate::Error;
+
+#[derive(Clone, Debug, Default)]
+pub struct Response {
+	pub status: i32,
+	pub headers: Vec<(String, String)>,
+	pub body: Vec<u8>,
+}
+
+impl Response {
+	pub fn header(&self, name: &str) -> Option<&str> {
+		self.headers
+			.iter()
+			.find(|(key, _)| key.eq_ignore_ascii_case(name))
+			.map(|(_, value)| value.as_str())
+	}
+}
+
+#[derive(Debug)]
+struct Fixture {
+	method: String,
+	url: String,
+	response: Response,
+	served: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Fixtures {
+	fixtures: Vec<Fixture>,
+}
+
+impl Fixtures {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Reads `fixtures.json` and the files it references from `dir`.
+	pub fn load(dir: impl AsRef<Path>) -> Result<Self, Error> {
+		let dir = dir.as_ref();
+		let manifest_path = dir.join("fixtures.json");
+		let manifest = fs::read(&manifest_path).map_err(|e| Error::Io(manifest_path.clone(), e))?;
+		let manifest: serde_json::Value = serde_json::from_slice(&manifest)
+			.map_err(|e| Error::Fixture(format!("{}: {e}", manifest_path.display())))?;
+		let entries = manifest.as_array().ok_or_else(|| {
+			Error::Fixture(format!("{}: expected an array", manifest_path.display()))
+		})?;
+
+		let mut fixtures = Self::new();
+		for entry in entries {
+			let field = |key: &str| entry.get(key).and_then(|v| v.as_str());
+			let url = field("url").ok_or_else(|| {
+				Error::Fixture(format!(
+					"{}: fixture without a url",
+					manifest_path.display()
+				))
+			})?;
+			let body = match field("file") {
+				Some(file) => {
+					let path: PathBuf = dir.join(file);
+					fs::read(&path).map_err(|e| Error::Io(path, e))?
+				}
+				None => Vec::new(),
+			};
+			let headers = entry
+				.get("headers")
+				.and_then(|v| v.as_object())
+				.map(|headers| {
+					headers
+						.iter()
+						.map(|(key, value)| {
+							(key.clone(), value.as_str().unwrap_or_default().into())
+						})
+						.collect()
+				})
+				.unwrap_or_default();
+			fixtures.fixtures.push(Fixture {
+				method: field("method").unwrap_or("GET").to_uppercase(),
+				url: String::from(url),
+				response: Response {
+					status: entry.get("status").and_then(|v| v.as_i64()).unwrap_or(200) as i32,
+					headers,
+					body,
+				},
+				served: false,
+			});
+		}
+		Ok(fixtures)
+	}
+
+	/// Adds a `GET` fixture with an inline body.
+	pub fn with(self, url: &str, body: impl Into<Vec<u8>>) -> Self {
+		self.with_response(
+			"GET",
+			url,
+			Response {
+				status: 200,
+				headers: Vec::new(),
+				body: body.into(),
+			},
+		)
+	}
+
+	pub fn with_response(mut self, method: &str, url: &str, response: Response) -> Self {
+		self.fixtures.push(Fixture {
+			method: method.to_uppercase(),
+			url: String::from(url),
+			response,
+			served: false,
+		});
+		self
+	}
+
+	pub(crate) fn respond(&mut self, method: &str, url: &str) -> Option<Response> {
+		let mut matching = self
+			.fixtures
+			.iter_mut()
+			.filter(|fixture| fixture.method == method && fixture.url == url)
+			.collect::<Vec<_>>();
+		let index = matching
+			.iter()
+			.position(|fixture| !fixture.served)
+			.or_else(|| matching.len().checked_sub(1))?;
+		matching[index].served = true;
+		Some(matching[index].response.clone())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn serves_matching_fixtures_in_order() {
+		let limited = Response {
+			status: 429,
+			..Default::default()
+		};
+		let mut fixtures = Fixtures::new()
+			.with_response("GET", "https://a.com/", limited)
+			.with("https://a.com/", "ok");
+		let statuses = (0..3)
+			.map(|_| fixtures.respond("GET", "https://a.com/").unwrap().status)
+			.collect::<Vec<_>>();
+		assert_eq!(statuses, [429, 200, 200]);
+		assert!(fixtures.respond("POST", "https://a.com/").is_none());
+		assert!(fixtures.respond("GET", "https://a.com/x").is_none());
+	}
+}
@@ -0,0 +1,313 @@
+use std::{
+	collections::BTreeMap,
+	fs,
+	path::{Path, PathBuf},
+};
+
+use wasmi::{Engine, Instance, Module, Store, WasmParams, WasmResults};
+
+use crate::{
+	fixtures::Fixtures,
+	imports::{self, HostState},
+	model::{Chapter, DeepLink, Filter, ImageRequest, Manga, MangaPageResult, Page},
+	value::Value,
+	Error,
+};
+
+/// A request the source sent, for asserting on URLs, headers and bodies.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SentRequest {
+	pub method: String,
+	pub url: String,
+	pub headers: Vec<(String, String)>,
+	pub body: Option<Vec<u8>>,
+}
+
+impl SentRequest {
+	pub fn header(&self, name: &str) -> Option<&str> {
+		self.headers
+			.iter()
+			.find(|(key, _)| key.eq_ignore_ascii_case(name))
+			.map(|(_, value)| value.as_str())
+	}
+}
+
+/// A source module running against fixtures instead of the network.
+pub struct Harness {
+	store: Store<HostState>,
+	instance: Instance,
+}
+
+fn read_json(path: &Path) -> Result<Option<serde_json::Value>, Error> {
+	match fs::read(path) {
+		Ok(data) => serde_json::from_slice(&data)
+			.map(Some)
+			.map_err(|e| Error::Fixture(format!("{}: {e}", path.display()))),
+		Err(_) => Ok(None),
+	}
+}
+
+/// Collects the `default` of every setting, including those in groups.
+fn collect_setting_defaults(items: &serde_json::Value, defaults: &mut BTreeMap<String, Value>) {
+	for item in items.as_array().into_iter().flatten() {
+		if let (Some(key), Some(default)) = (
+			item.get("key").and_then(|v| v.as_str()),
+			item.get("default"),
+		) {
+			defaults.insert(String::from(key), default.clone().into());
+		}
+		if let Some(items) = item.get("items") {
+			collect_setting_defaults(items, defaults);
+		}
+	}
+}
+
+/// The wasm file cargo produces for the crate in `dir`. Template sources are
+/// built into their template's workspace, so parent directories are searched
+/// too.
+fn find_wasm(dir: &Path) -> Result<PathBuf, Error> {
+	let manifest_path = dir.join("Cargo.toml");
+	let manifest = fs::read_to_string(&manifest_path).map_err(|e| Error::Io(manifest_path, e))?;
+	let name = manifest
+		.lines()
+		.find_map(|line| {
+			let (key, value) = line.split_once('=')?;
+			(key.trim() == "name").then(|| value.trim().trim_matches('"').replace('-', "_"))
+		})
+		.unwrap_or_default();
+	let file = Path::new("target/wasm32-unknown-unknown/release").join(format!("{name}.wasm"));
+	dir.ancestors()
+		.take(3)
+		.map(|dir| dir.join(&file))
+		.find(|path| path.exists())
+		.ok_or_else(|| Error::NotBuilt(dir.join(&file)))
+}
+
+impl Harness {
+	pub fn new(wasm: &[u8], fixtures: Fixtures) -> Result<Self, Error> {
+		let engine = Engine::default();
+		let module = Module::new(&engine, wasm)?;
+		let mut store = Store::new(&engine, HostState::new(fixtures));
+		let linker = imports::link(&mut store, &module)?;
+		let instance = linker.instantiate(&mut store, &module)?.start(&mut store)?;
+		Ok(Self { store, instance })
+	}
+
+	pub fn load(path: impl AsRef<Path>, fixtures: Fixtures) -> Result<Self, Error> {
+		let path = path.as_ref();
+		let wasm = fs::read(path).map_err(|_| Error::NotBuilt(path.into()))?;
+		Self::new(&wasm, fixtures)
+	}
+
+	/// Loads a built source from `src/rust`, e.g. `multi.xkcd` or
+	/// `madara/sources/manhuaus`. Settings start at the defaults from its
+	/// `res` folder, and `initialize` is called if the source exports it.
+	pub fn source(path: &str, fixtures: Fixtures) -> Result<Self, Error> {
+		let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+			.parent()
+			.unwrap_or(Path::new(".."))
+			.join(path);
+		let mut harness = Self::load(find_wasm(&dir)?, fixtures)?;
+
+		let res = dir.join("res");
+		let mut defaults = BTreeMap::new();
+		if let Some(settings) = read_json(&res.join("settings.json"))? {
+			collect_setting_defaults(&settings, &mut defaults);
+		}
+		if let Some(source) = read_json(&res.join("source.json"))? {
+			let languages = source
+				.get("languages")
+				.and_then(|v| v.as_array())
+				.into_iter()
+				.flatten()
+				.filter(|language| language.get("default").and_then(|v| v.as_bool()) == Some(true))
+				.filter_map(|language| language.get("code").and_then(|v| v.as_str()))
+				.map(|code| Value::String(code.into()))
+				.collect::<Vec<_>>();
+			if !languages.is_empty() {
+				defaults.insert(String::from("languages"), Value::Array(languages));
+			}
+		}
+		harness.store.data_mut().defaults.extend(defaults);
+
+		harness.initialize()?;
+		Ok(harness)
+	}
+
+	fn call<Params: WasmParams, Results: WasmResults>(
+		&mut self,
+		function: &'static str,
+		params: Params,
+	) -> Result<Results, Error> {
+		let func = self
+			.instance
+			.get_typed_func::<Params, Results>(&self.store, function)
+			.map_err(|_| Error::MissingExport(function))?;
+		self.store.data_mut().unmatched.clear();
+		Ok(func.call(&mut self.store, params)?)
+	}
+
+	fn insert(&mut self, value: Value) -> i32 {
+		self.store.data_mut().values.insert(value)
+	}
+
+	/// Takes the value a function returned. Arguments passed in are released
+	/// too, in case the source didn't do it itself.
+	fn take(
+		&mut self,
+		function: &'static str,
+		rid: i32,
+		arguments: &[i32],
+	) -> Result<Value, Error> {
+		let state = self.store.data_mut();
+		for argument in arguments {
+			state.values.remove(*argument);
+		}
+		if rid < 0 {
+			return Err(Error::Source {
+				function,
+				unmatched: state.unmatched.clone(),
+			});
+		}
+		Ok(state.values.remove(rid).unwrap_or(Value::Null))
+	}
+
+	fn take_array<T>(
+		&mut self,
+		function: &'static str,
+		rid: i32,
+		arguments: &[i32],
+		item: fn(Value) -> Option<T>,
+	) -> Result<Vec<T>, Error> {
+		match self.take(function, rid, arguments)? {
+			Value::Array(values) => values
+				.into_iter()
+				.map(|value| item(value).ok_or(Error::UnexpectedResult(function)))
+				.collect(),
+			_ => Err(Error::UnexpectedResult(function)),
+		}
+	}
+
+	/// Runs the source's `initialize`, if it has one.
+	pub fn initialize(&mut self) -> Result<(), Error> {
+		match self.call::<(), ()>("initialize", ()) {
+			Err(Error::MissingExport(_)) => Ok(()),
+			result => result,
+		}
+	}
+
+	pub fn get_manga_list(
+		&mut self,
+		filters: &[Filter],
+		page: i32,
+	) -> Result<MangaPageResult, Error> {
+		let filters = self.insert(Value::Array(filters.iter().map(Filter::to_value).collect()));
+		let rid = self.call("get_manga_list", (filters, page))?;
+		match self.take("get_manga_list", rid, &[filters])? {
+			Value::MangaPageResult(result) => Ok(result),
+			_ => Err(Error::UnexpectedResult("get_manga_list")),
+		}
+	}
+
+	pub fn get_manga_listing(&mut self, name: &str, page: i32) -> Result<MangaPageResult, Error> {
+		let listing = self.insert(Value::Object(BTreeMap::from([(
+			String::from("name"),
+			Value::String(name.into()),
+		)])));
+		let rid = self.call("get_manga_listing", (listing, page))?;
+		match self.take("get_manga_listing", rid, &[listing])? {
+			Value::MangaPageResult(result) => Ok(result),
+			_ => Err(Error::UnexpectedResult("get_manga_listing")),
+		}
+	}
+
+	fn manga_descriptor(&mut self, id: &str) -> i32 {
+		self.insert(Value::Object(BTreeMap::from([(
+			String::from("id"),
+			Value::String(id.into()),
+		)])))
+	}
+
+	pub fn get_manga_details(&mut self, id: &str) -> Result<Manga, Error> {
+		let manga = self.manga_descriptor(id);
+		let rid = self.call("get_manga_details", manga)?;
+		match self.take("get_manga_details", rid, &[manga])? {
+			Value::Manga(manga) => Ok(manga),
+			_ => Err(Error::UnexpectedResult("get_manga_details")),
+		}
+	}
+
+	pub fn get_chapter_list(&mut self, manga_id: &str) -> Result<Vec<Chapter>, Error> {
+		let manga = self.manga_descriptor(manga_id);
+		let rid = self.call("get_chapter_list", manga)?;
+		self.take_array("get_chapter_list", rid, &[manga], |value| match value {
+			Value::Chapter(chapter) => Some(chapter),
+			_ => None,
+		})
+	}
+
+	pub fn get_page_list(&mut self, manga_id: &str, chapter_id: &str) -> Result<Vec<Page>, Error> {
+		let chapter = self.insert(Value::Object(BTreeMap::from([
+			(String::from("mangaId"), Value::String(manga_id.into())),
+			(String::from("id"), Value::String(chapter_id.into())),
+		])));
+		let rid = self.call("get_page_list", chapter)?;
+		self.take_array("get_page_list", rid, &[chapter], |value| match value {
+			Value::Page(page) => Some(page),
+			_ => None,
+		})
+	}
+
+	pub fn handle_url(&mut self, url: &str) -> Result<DeepLink, Error> {
+		let url = self.insert(Value::String(url.into()));
+		let rid = self.call("handle_url", url)?;
+		match self.take("handle_url", rid, &[url])? {
+			Value::DeepLink(deep_link) => Ok(deep_link),
+			_ => Err(Error::UnexpectedResult("handle_url")),
+		}
+	}
+
+	pub fn handle_notification(&mut self, notification: &str) -> Result<(), Error> {
+		let notification = self.insert(Value::String(notification.into()));
+		self.call::<i32, ()>("handle_notification", notification)?;
+		self.store.data_mut().values.remove(notification);
+		Ok(())
+	}
+
+	/// Lets the source modify the request for an image and returns the result.
+	pub fn modify_image_request(&mut self, url: &str) -> Result<ImageRequest, Error> {
+		let rd = self.store.data_mut().create_request("GET", url);
+		self.call::<i32, ()>("modify_image_request", rd)?;
+		let request = self
+			.store
+			.data_mut()
+			.requests
+			.remove(&rd)
+			.ok_or(Error::UnexpectedResult("modify_image_request"))?;
+		Ok(ImageRequest {
+			url: request.url,
+			headers: request.headers,
+		})
+	}
+
+	pub fn set_default(&mut self, key: &str, value: serde_json::Value) {
+		self.store
+			.data_mut()
+			.defaults
+			.insert(String::from(key), value.into());
+	}
+
+	pub fn default(&self, key: &str) -> Option<serde_json::Value> {
+		self.store.data().defaults.get(key).map(Into::into)
+	}
+
+	/// Every request sent so far, in order.
+	pub fn requests(&self) -> &[SentRequest] {
+		&self.store.data().sent
+	}
+
+	/// Everything the source printed so far.
+	pub fn logs(&self) -> &[String] {
+		&self.store.data().logs
+	}
+}
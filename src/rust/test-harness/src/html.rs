@@ -0,0 +1,546 @@
+//! The app's `html` module (SwiftSoup) on top of `scraper`.
+//!
+//! A descriptor always holds a list of nodes, mirroring SwiftSoup's
+//! `Elements`: single-node accessors read from the first node, text and HTML
+//! accessors join all of them.
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
+
+use ego_tree::{iter::Edge, NodeId, NodeRef, Tree};
+use scraper::{node::Text, ElementRef, Html, Node, Selector};
+
+const BLOCK_TAGS: &[&str] = &[
+	"address",
+	"article",
+	"aside",
+	"blockquote",
+	"dd",
+	"div",
+	"dl",
+	"dt",
+	"fieldset",
+	"figcaption",
+	"figure",
+	"footer",
+	"form",
+	"h1",
+	"h2",
+	"h3",
+	"h4",
+	"h5",
+	"h6",
+	"header",
+	"hr",
+	"li",
+	"main",
+	"nav",
+	"ol",
+	"p",
+	"pre",
+	"section",
+	"table",
+	"tbody",
+	"td",
+	"tfoot",
+	"th",
+	"thead",
+	"tr",
+	"ul",
+];
+
+#[derive(Debug)]
+struct Document {
+	html: Html,
+	base_uri: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Nodes {
+	document: Rc<RefCell<Document>>,
+	ids: Vec<NodeId>,
+}
+
+fn is_block(node: NodeRef<Node>) -> bool {
+	node.value()
+		.as_element()
+		.is_some_and(|element| BLOCK_TAGS.contains(&element.name()))
+}
+
+/// Script and style contents are data, not text.
+fn is_data(node: NodeRef<Node>) -> bool {
+	node.parent()
+		.and_then(|parent| parent.value().as_element().map(|e| e.name()))
+		.is_some_and(|name| name == "script" || name == "style")
+}
+
+fn normalize_whitespace(text: &str) -> String {
+	text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn collect_text(node: NodeRef<Node>, untrimmed: bool) -> String {
+	let mut result = String::new();
+	for edge in node.traverse() {
+		match edge {
+			Edge::Open(node) => match node.value() {
+				Node::Text(text) if !is_data(node) => result.push_str(text),
+				Node::Element(element) if element.name() == "br" => {
+					result.push(if untrimmed { '\n' } else { ' ' })
+				}
+				_ if !untrimmed && is_block(node) => result.push(' '),
+				_ => {}
+			},
+			Edge::Close(node) if !untrimmed && is_block(node) => result.push(' '),
+			Edge::Close(_) => {}
+		}
+	}
+	result
+}
+
+fn clear_children(tree: &mut Tree<Node>, id: NodeId) {
+	let children = tree
+		.get(id)
+		.map(|node| node.children().map(|child| child.id()).collect::<Vec<_>>())
+		.unwrap_or_default();
+	for child in children {
+		if let Some(mut child) = tree.get_mut(child) {
+			child.detach();
+		}
+	}
+}
+
+/// Copies `source` and its children under `parent`.
+fn graft(tree: &mut Tree<Node>, parent: NodeId, source: NodeRef<Node>, prepend: bool) {
+	let Some(mut parent) = tree.get_mut(parent) else {
+		return;
+	};
+	let id = if prepend {
+		parent.prepend(source.value().clone()).id()
+	} else {
+		parent.append(source.value().clone()).id()
+	};
+	for child in source.children() {
+		graft(tree, id, child, false);
+	}
+}
+
+pub fn escape(text: &str) -> String {
+	let mut result = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'&' => result.push_str("&amp;"),
+			'<' => result.push_str("&lt;"),
+			'>' => result.push_str("&gt;"),
+			'"' => result.push_str("&quot;"),
+			'\'' => result.push_str("&#39;"),
+			c => result.push(c),
+		}
+	}
+	result
+}
+
+pub fn unescape(text: &str) -> String {
+	let mut result = String::with_capacity(text.len());
+	let mut rest = text;
+	while let Some(start) = rest.find('&') {
+		result.push_str(&rest[..start]);
+		rest = &rest[start..];
+		let decoded = rest.find(';').and_then(|end| {
+			let entity = &rest[1..end];
+			let c = match entity {
+				"amp" => Some('&'),
+				"lt" => Some('<'),
+				"gt" => Some('>'),
+				"quot" => Some('"'),
+				"apos" => Some('\''),
+				"nbsp" => Some('\u{a0}'),
+				_ => entity
+					.strip_prefix("#x")
+					.or_else(|| entity.strip_prefix("#X"))
+					.and_then(|hex| u32::from_str_radix(hex, 16).ok())
+					.or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+					.and_then(char::from_u32),
+			};
+			c.map(|c| (c, end))
+		});
+		match decoded {
+			Some((c, end)) => {
+				result.push(c);
+				rest = &rest[end + 1..];
+			}
+			None => {
+				result.push('&');
+				rest = &rest[1..];
+			}
+		}
+	}
+	result.push_str(rest);
+	result
+}
+
+impl Nodes {
+	pub fn parse(html: &str, base_uri: &str) -> Self {
+		Self::from_html(Html::parse_document(html), base_uri)
+	}
+
+	pub fn parse_fragment(html: &str, base_uri: &str) -> Self {
+		Self::from_html(Html::parse_fragment(html), base_uri)
+	}
+
+	fn from_html(html: Html, base_uri: &str) -> Self {
+		let root = html.tree.root().id();
+		Self {
+			document: Rc::new(RefCell::new(Document {
+				html,
+				base_uri: String::from(base_uri),
+			})),
+			ids: vec![root],
+		}
+	}
+
+	fn with_ids(&self, ids: Vec<NodeId>) -> Self {
+		Self {
+			document: self.document.clone(),
+			ids,
+		}
+	}
+
+	fn with_nodes<T>(&self, f: impl FnOnce(Vec<NodeRef<Node>>) -> T) -> T {
+		let document = self.document.borrow();
+		let nodes = self
+			.ids
+			.iter()
+			.filter_map(|id| document.html.tree.get(*id))
+			.collect();
+		f(nodes)
+	}
+
+	fn with_elements<T>(&self, f: impl FnOnce(Vec<ElementRef>) -> T) -> T {
+		self.with_nodes(|nodes| f(nodes.into_iter().filter_map(ElementRef::wrap).collect()))
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.ids.is_empty()
+	}
+
+	/// Matches `selector` against the nodes and their descendants. An invalid
+	/// selector matches nothing.
+	pub fn select(&self, selector: &str) -> Self {
+		let Ok(selector) = Selector::parse(selector) else {
+			return self.with_ids(Vec::new());
+		};
+		let ids = self.with_nodes(|nodes| {
+			let mut seen = HashSet::new();
+			nodes
+				.into_iter()
+				.flat_map(|node| node.descendants())
+				.filter(|node| {
+					ElementRef::wrap(*node).is_some_and(|element| selector.matches(&element))
+				})
+				.map(|node| node.id())
+				.filter(|id| seen.insert(*id))
+				.collect()
+		});
+		self.with_ids(ids)
+	}
+
+	/// The attribute of the first node that has it. An `abs:` prefix resolves
+	/// the value against the document's base URI.
+	pub fn attr(&self, name: &str) -> String {
+		let (absolute, name) = match name.strip_prefix("abs:") {
+			Some(name) => (true, name),
+			None => (false, name),
+		};
+		let value = self.with_elements(|elements| {
+			elements
+				.iter()
+				.find_map(|element| element.value().attr(name).map(String::from))
+		});
+		match value {
+			Some(value) if absolute => self.absolute_url(&value),
+			Some(value) => value,
+			None => String::new(),
+		}
+	}
+
+	fn absolute_url(&self, value: &str) -> String {
+		let value = value.trim();
+		url::Url::parse(&self.base_uri())
+			.and_then(|base| base.join(value))
+			.or_else(|_| url::Url::parse(value))
+			.map(String::from)
+			.unwrap_or_default()
+	}
+
+	pub fn first(&self) -> Self {
+		self.with_ids(self.ids.first().copied().into_iter().collect())
+	}
+
+	pub fn last(&self) -> Self {
+		self.with_ids(self.ids.last().copied().into_iter().collect())
+	}
+
+	pub fn next(&self) -> Self {
+		self.sibling(|node| node.next_sibling())
+	}
+
+	pub fn previous(&self) -> Self {
+		self.sibling(|node| node.prev_sibling())
+	}
+
+	/// The closest element sibling of the first node.
+	fn sibling(&self, step: impl Fn(NodeRef<Node>) -> Option<NodeRef<Node>>) -> Self {
+		let id = self.with_nodes(|nodes| {
+			let mut node = nodes.first().copied();
+			loop {
+				node = node.and_then(&step);
+				match node {
+					Some(node) if node.value().is_element() => break Some(node.id()),
+					Some(_) => continue,
+					None => break None,
+				}
+			}
+		});
+		self.with_ids(id.into_iter().collect())
+	}
+
+	pub fn body(&self) -> Self {
+		let id = {
+			let document = self.document.borrow();
+			let root = document.html.tree.root();
+			root.descendants()
+				.find(|node| {
+					node.value()
+						.as_element()
+						.is_some_and(|e| e.name() == "body")
+				})
+				.or_else(|| root.children().find(|node| node.value().is_element()))
+				.map(|node| node.id())
+		};
+		self.with_ids(id.into_iter().collect())
+	}
+
+	pub fn base_uri(&self) -> String {
+		self.document.borrow().base_uri.clone()
+	}
+
+	pub fn text(&self) -> String {
+		self.with_nodes(|nodes| {
+			nodes
+				.into_iter()
+				.map(|node| normalize_whitespace(&collect_text(node, false)))
+				.filter(|text| !text.is_empty())
+				.collect::<Vec<_>>()
+				.join(" ")
+		})
+	}
+
+	pub fn untrimmed_text(&self) -> String {
+		self.with_nodes(|nodes| {
+			nodes
+				.into_iter()
+				.map(|node| collect_text(node, true))
+				.collect()
+		})
+	}
+
+	pub fn own_text(&self) -> String {
+		self.with_nodes(|nodes| {
+			let text = nodes
+				.into_iter()
+				.flat_map(|node| node.children())
+				.filter_map(|node| match node.value() {
+					Node::Text(text) => Some(text.to_string()),
+					Node::Element(element) if element.name() == "br" => Some(String::from(" ")),
+					_ => None,
+				})
+				.collect::<String>();
+			normalize_whitespace(&text)
+		})
+	}
+
+	/// The contents of script and style elements.
+	pub fn data(&self) -> String {
+		self.with_nodes(|nodes| {
+			nodes
+				.into_iter()
+				.flat_map(|node| node.descendants())
+				.filter(|node| is_data(*node))
+				.filter_map(|node| node.value().as_text().map(|text| text.to_string()))
+				.collect()
+		})
+	}
+
+	pub fn html(&self) -> String {
+		self.serialize(|element| element.inner_html())
+	}
+
+	pub fn outer_html(&self) -> String {
+		self.serialize(|element| element.html())
+	}
+
+	fn serialize(&self, f: impl Fn(ElementRef) -> String) -> String {
+		let document = self.document.borrow();
+		self.ids
+			.iter()
+			.filter_map(|id| document.html.tree.get(*id))
+			.map(|node| match ElementRef::wrap(node) {
+				Some(element) => f(element),
+				None => document.html.html(),
+			})
+			.collect::<Vec<_>>()
+			.join("\n")
+	}
+
+	pub fn array(&self) -> Vec<Self> {
+		self.ids.iter().map(|id| self.with_ids(vec![*id])).collect()
+	}
+
+	pub fn id(&self) -> String {
+		self.attr("id")
+	}
+
+	pub fn tag_name(&self) -> String {
+		self.with_elements(|elements| {
+			elements
+				.first()
+				.map(|element| String::from(element.value().name()))
+				.unwrap_or_default()
+		})
+	}
+
+	pub fn class_name(&self) -> String {
+		self.attr("class")
+	}
+
+	pub fn has_class(&self, class: &str) -> bool {
+		self.with_elements(|elements| {
+			elements
+				.iter()
+				.any(|element| element.value().classes().any(|c| c == class))
+		})
+	}
+
+	pub fn has_attr(&self, name: &str) -> bool {
+		let name = name.strip_prefix("abs:").unwrap_or(name);
+		self.with_elements(|elements| {
+			elements
+				.iter()
+				.any(|element| element.value().attr(name).is_some())
+		})
+	}
+
+	pub fn set_text(&self, text: &str) {
+		let mut document = self.document.borrow_mut();
+		let tree = &mut document.html.tree;
+		for id in &self.ids {
+			clear_children(tree, *id);
+			if let Some(mut node) = tree.get_mut(*id) {
+				node.append(Node::Text(Text { text: text.into() }));
+			}
+		}
+	}
+
+	pub fn set_html(&self, html: &str) {
+		{
+			let mut document = self.document.borrow_mut();
+			for id in &self.ids {
+				clear_children(&mut document.html.tree, *id);
+			}
+		}
+		self.append(html);
+	}
+
+	pub fn append(&self, html: &str) {
+		self.insert_fragment(html, false);
+	}
+
+	pub fn prepend(&self, html: &str) {
+		self.insert_fragment(html, true);
+	}
+
+	fn insert_fragment(&self, html: &str, prepend: bool) {
+		let fragment = Html::parse_fragment(html);
+		let mut roots = fragment.root_element().children().collect::<Vec<_>>();
+		if prepend {
+			roots.reverse();
+		}
+		let mut document = self.document.borrow_mut();
+		for id in &self.ids {
+			for node in &roots {
+				graft(&mut document.html.tree, *id, *node, prepend);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const PAGE: &str = r#"<html><head><script>var x = 1;</script></head><body>
+		<div id="list" class="chapters wide">
+			<a href="/c/2" title="2024-1-2">Chapter <b>2</b></a>
+			<a href="https://cdn.example.com/c/1">Chapter 1</a>
+		</div>
+		<p>one<br>two</p>
+	</body></html>"#;
+
+	#[test]
+	fn selects_and_reads_attributes() {
+		let document = Nodes::parse(PAGE, "https://example.com/manga/");
+		let links = document.select("#list > a");
+		assert_eq!(links.array().len(), 2);
+		assert_eq!(links.attr("href"), "/c/2");
+		assert_eq!(links.attr("abs:href"), "https://example.com/c/2");
+		assert_eq!(links.last().attr("abs:href"), "https://cdn.example.com/c/1");
+		assert_eq!(links.first().next().text(), "Chapter 1");
+		assert_eq!(links.last().previous().own_text(), "Chapter");
+		assert!(links.first().next().next().is_empty());
+		assert!(document.select("[[").is_empty());
+	}
+
+	#[test]
+	fn reads_text_like_swiftsoup() {
+		let document = Nodes::parse(PAGE, "");
+		assert_eq!(document.select("#list a").text(), "Chapter 2 Chapter 1");
+		assert_eq!(document.select("p").text(), "one two");
+		assert_eq!(document.select("p").untrimmed_text(), "one\ntwo");
+		assert_eq!(document.select("script").data(), "var x = 1;");
+		assert!(!document.body().text().contains("var x"));
+		assert_eq!(document.select("b").outer_html(), "<b>2</b>");
+	}
+
+	#[test]
+	fn reads_element_metadata() {
+		let list = Nodes::parse(PAGE, "").select("div");
+		assert_eq!(list.id(), "list");
+		assert_eq!(list.tag_name(), "div");
+		assert!(list.has_class("wide"));
+		assert!(!list.has_class("chapter"));
+		assert!(list.select("a").has_attr("title"));
+	}
+
+	#[test]
+	fn modifies_the_document() {
+		let document = Nodes::parse(PAGE, "");
+		let paragraph = document.select("p");
+		paragraph.set_text("replaced");
+		assert_eq!(paragraph.html(), "replaced");
+		paragraph.append("<i>after</i>");
+		paragraph.prepend("<i>1</i><i>2</i>");
+		assert_eq!(paragraph.html(), "<i>1</i><i>2</i>replaced<i>after</i>");
+		paragraph.set_html("<span>new</span>");
+		assert_eq!(document.select("p span").text(), "new");
+	}
+
+	#[test]
+	fn escapes_entities() {
+		assert_eq!(
+			escape(r#"<a href="x">&"#),
+			"&lt;a href=&quot;x&quot;&gt;&amp;"
+		);
+		assert_eq!(
+			unescape("&lt;b&gt; &#39;&#x41;&amp;c &bogus;"),
+			"<b> 'A&c &bogus;"
+		);
+	}
+}
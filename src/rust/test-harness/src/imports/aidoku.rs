@@ -0,0 +1,150 @@
+//! `aidoku`: the structs sources return.
+use wasmi::{core::ValType, Caller, Error, FuncType, Val};
+
+use super::{read_bytes, read_string, HostState, Imports};
+use crate::{
+	model::{Chapter, DeepLink, Manga, MangaPageResult, Page},
+	value::Value,
+};
+
+/// Reads `count` little-endian `usize`s (32 bits on wasm32).
+fn read_usizes(caller: &Caller<HostState>, ptr: i32, count: i32) -> Result<Vec<i32>, Error> {
+	let bytes = read_bytes(caller, ptr, count.max(0) * 4)?;
+	Ok(bytes
+		.chunks_exact(4)
+		.map(|chunk| i32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+		.collect())
+}
+
+fn create_manga(
+	caller: Caller<HostState>,
+	params: &[Val],
+	results: &mut [Val],
+) -> Result<(), Error> {
+	let arg = |i: usize| params[i].i32().unwrap_or_default();
+	let string = |i: usize| read_string(&caller, arg(i), arg(i + 1));
+
+	let tag_pointers = read_usizes(&caller, arg(14), arg(16))?;
+	let tag_lengths = read_usizes(&caller, arg(15), arg(16))?;
+	let categories = tag_pointers
+		.into_iter()
+		.zip(tag_lengths)
+		.map(|(ptr, len)| read_string(&caller, ptr, len))
+		.collect::<Result<_, _>>()?;
+
+	let manga = Manga {
+		id: string(0)?,
+		cover: string(2)?,
+		title: string(4)?,
+		author: string(6)?,
+		artist: string(8)?,
+		description: string(10)?,
+		url: string(12)?,
+		categories,
+		status: arg(17).into(),
+		nsfw: arg(18).into(),
+		viewer: arg(19).into(),
+	};
+	let mut caller = caller;
+	results[0] = Val::I32(caller.data_mut().values.insert(Value::Manga(manga)));
+	Ok(())
+}
+
+pub(super) fn register(imports: &mut Imports) {
+	imports
+		.func_new(
+			"create_manga",
+			FuncType::new([ValType::I32; 20], [ValType::I32]),
+			create_manga,
+		)
+		.func(
+			"create_manga_result",
+			|mut caller: Caller<HostState>, rid: i32, has_more: i32| {
+				let manga = match caller.data().values.get(rid) {
+					Value::Array(values) => values
+						.iter()
+						.filter_map(|value| match value {
+							Value::Manga(manga) => Some(manga.clone()),
+							_ => None,
+						})
+						.collect(),
+					_ => Vec::new(),
+				};
+				let result = MangaPageResult {
+					manga,
+					has_more: has_more != 0,
+				};
+				caller
+					.data_mut()
+					.values
+					.insert(Value::MangaPageResult(result))
+			},
+		)
+		.func(
+			"create_chapter",
+			|mut caller: Caller<HostState>,
+			 id: i32,
+			 id_len: i32,
+			 title: i32,
+			 title_len: i32,
+			 volume: f32,
+			 chapter: f32,
+			 date_updated: f64,
+			 scanlator: i32,
+			 scanlator_len: i32,
+			 url: i32,
+			 url_len: i32,
+			 lang: i32,
+			 lang_len: i32|
+			 -> Result<i32, Error> {
+				let chapter = Chapter {
+					id: read_string(&caller, id, id_len)?,
+					title: read_string(&caller, title, title_len)?,
+					volume,
+					chapter,
+					date_updated,
+					scanlator: read_string(&caller, scanlator, scanlator_len)?,
+					url: read_string(&caller, url, url_len)?,
+					lang: read_string(&caller, lang, lang_len)?,
+				};
+				Ok(caller.data_mut().values.insert(Value::Chapter(chapter)))
+			},
+		)
+		.func(
+			"create_page",
+			|mut caller: Caller<HostState>,
+			 index: i32,
+			 url: i32,
+			 url_len: i32,
+			 base64: i32,
+			 base64_len: i32,
+			 text: i32,
+			 text_len: i32|
+			 -> Result<i32, Error> {
+				let page = Page {
+					index,
+					url: read_string(&caller, url, url_len)?,
+					base64: read_string(&caller, base64, base64_len)?,
+					text: read_string(&caller, text, text_len)?,
+				};
+				Ok(caller.data_mut().values.insert(Value::Page(page)))
+			},
+		)
+		.func(
+			"create_deeplink",
+			|mut caller: Caller<HostState>, manga: i32, chapter: i32| {
+				let values = &caller.data().values;
+				let deep_link = DeepLink {
+					manga: match values.get(manga) {
+						Value::Manga(manga) => Some(manga.clone()),
+						_ => None,
+					},
+					chapter: match values.get(chapter) {
+						Value::Chapter(chapter) => Some(chapter.clone()),
+						_ => None,
+					},
+				};
+				caller.data_mut().values.insert(Value::DeepLink(deep_link))
+			},
+		);
+}
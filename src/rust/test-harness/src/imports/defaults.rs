@@ -0,0 +1,25 @@
+//! `defaults`: source settings.
+use wasmi::{Caller, Error};
+
+use super::{read_string, HostState, Imports};
+
+pub(super) fn register(imports: &mut Imports) {
+	imports
+		.func(
+			"get",
+			|mut caller: Caller<HostState>, ptr: i32, len: i32| -> Result<i32, Error> {
+				let key = read_string(&caller, ptr, len)?;
+				let value = caller.data().defaults.get(&key).cloned();
+				Ok(value.map_or(-1, |value| caller.data_mut().values.insert(value)))
+			},
+		)
+		.func(
+			"set",
+			|mut caller: Caller<HostState>, ptr: i32, len: i32, rid: i32| -> Result<(), Error> {
+				let key = read_string(&caller, ptr, len)?;
+				let value = caller.data().values.get(rid).clone();
+				caller.data_mut().defaults.insert(key, value);
+				Ok(())
+			},
+		);
+}
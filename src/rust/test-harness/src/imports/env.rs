@@ -0,0 +1,20 @@
+//! `env`: logging and panics.
+use wasmi::{Caller, Error};
+
+use super::{read_string, HostState, Imports};
+
+pub(super) fn register(imports: &mut Imports) {
+	imports
+		.func(
+			"print",
+			|mut caller: Caller<HostState>, ptr: i32, len: i32| -> Result<(), Error> {
+				let message = read_string(&caller, ptr, len)?;
+				eprintln!("{message}");
+				caller.data_mut().logs.push(message);
+				Ok(())
+			},
+		)
+		.func("abort", |_: Caller<HostState>| -> Result<(), Error> {
+			Err(Error::new("the source panicked"))
+		});
+}
@@ -0,0 +1,153 @@
+//! `html`: parsing and querying documents.
+use wasmi::{Caller, Error};
+
+use super::{read_string, HostState, Imports};
+use crate::{
+	html::{self, Nodes},
+	value::Value,
+};
+
+fn nodes(caller: &Caller<HostState>, rid: i32) -> Option<Nodes> {
+	match caller.data().values.get(rid) {
+		Value::Node(nodes) => Some(nodes.clone()),
+		_ => None,
+	}
+}
+
+fn insert(caller: &mut Caller<HostState>, value: Option<Value>) -> i32 {
+	value.map_or(-1, |value| caller.data_mut().values.insert(value))
+}
+
+/// Registers `name` and its `_with_uri` variant.
+fn parser(
+	imports: &mut Imports,
+	name: &'static str,
+	name_with_uri: &'static str,
+	parse: fn(&str, &str) -> Nodes,
+) {
+	imports.func(
+		name,
+		move |mut caller: Caller<HostState>, ptr: i32, len: i32| -> Result<i32, Error> {
+			let html = read_string(&caller, ptr, len)?;
+			Ok(insert(&mut caller, Some(Value::Node(parse(&html, "")))))
+		},
+	);
+	imports.func(
+		name_with_uri,
+		move |mut caller: Caller<HostState>,
+		      ptr: i32,
+		      len: i32,
+		      uri: i32,
+		      uri_len: i32|
+		      -> Result<i32, Error> {
+			let html = read_string(&caller, ptr, len)?;
+			let uri = read_string(&caller, uri, uri_len)?;
+			Ok(insert(&mut caller, Some(Value::Node(parse(&html, &uri)))))
+		},
+	);
+}
+
+/// For getters that return a single element, which is `-1` when there's none.
+fn node_getter(imports: &mut Imports, name: &'static str, getter: fn(&Nodes) -> Nodes) {
+	imports.func(name, move |mut caller: Caller<HostState>, rid: i32| {
+		let value = nodes(&caller, rid)
+			.map(|nodes| getter(&nodes))
+			.filter(|nodes| !nodes.is_empty())
+			.map(Value::Node);
+		insert(&mut caller, value)
+	});
+}
+
+fn string_getter(imports: &mut Imports, name: &'static str, getter: fn(&Nodes) -> String) {
+	imports.func(name, move |mut caller: Caller<HostState>, rid: i32| {
+		let value = nodes(&caller, rid).map(|nodes| Value::String(getter(&nodes)));
+		insert(&mut caller, value)
+	});
+}
+
+fn string_query(imports: &mut Imports, name: &'static str, query: fn(&Nodes, &str) -> Value) {
+	imports.func(
+		name,
+		move |mut caller: Caller<HostState>, rid: i32, ptr: i32, len: i32| -> Result<i32, Error> {
+			let argument = read_string(&caller, ptr, len)?;
+			let value = nodes(&caller, rid).map(|nodes| query(&nodes, &argument));
+			Ok(insert(&mut caller, value))
+		},
+	);
+}
+
+fn predicate(imports: &mut Imports, name: &'static str, predicate: fn(&Nodes, &str) -> bool) {
+	imports.func(
+		name,
+		move |caller: Caller<HostState>, rid: i32, ptr: i32, len: i32| -> Result<i32, Error> {
+			let argument = read_string(&caller, ptr, len)?;
+			Ok(nodes(&caller, rid).is_some_and(|nodes| predicate(&nodes, &argument)) as i32)
+		},
+	);
+}
+
+/// Returns 0 on success and -1 if the descriptor isn't a node.
+fn mutator(imports: &mut Imports, name: &'static str, mutate: fn(&Nodes, &str)) {
+	imports.func(
+		name,
+		move |caller: Caller<HostState>, rid: i32, ptr: i32, len: i32| -> Result<i32, Error> {
+			let argument = read_string(&caller, ptr, len)?;
+			Ok(match nodes(&caller, rid) {
+				Some(nodes) => {
+					mutate(&nodes, &argument);
+					0
+				}
+				None => -1,
+			})
+		},
+	);
+}
+
+pub(super) fn register(imports: &mut Imports) {
+	parser(imports, "parse", "parse_with_uri", Nodes::parse);
+	parser(
+		imports,
+		"parse_fragment",
+		"parse_fragment_with_uri",
+		Nodes::parse_fragment,
+	);
+
+	string_query(imports, "select", |nodes, selector| {
+		Value::Node(nodes.select(selector))
+	});
+	string_query(imports, "attr", |nodes, name| {
+		Value::String(nodes.attr(name))
+	});
+	predicate(imports, "has_class", Nodes::has_class);
+	predicate(imports, "has_attr", Nodes::has_attr);
+
+	mutator(imports, "set_text", Nodes::set_text);
+	mutator(imports, "set_html", Nodes::set_html);
+	mutator(imports, "prepend", Nodes::prepend);
+	mutator(imports, "append", Nodes::append);
+
+	node_getter(imports, "first", Nodes::first);
+	node_getter(imports, "last", Nodes::last);
+	node_getter(imports, "next", Nodes::next);
+	node_getter(imports, "previous", Nodes::previous);
+	node_getter(imports, "body", Nodes::body);
+
+	string_getter(imports, "base_uri", Nodes::base_uri);
+	string_getter(imports, "text", Nodes::text);
+	string_getter(imports, "untrimmed_text", Nodes::untrimmed_text);
+	string_getter(imports, "own_text", Nodes::own_text);
+	string_getter(imports, "data", Nodes::data);
+	string_getter(imports, "html", Nodes::html);
+	string_getter(imports, "outer_html", Nodes::outer_html);
+	string_getter(imports, "escape", |nodes| html::escape(&nodes.text()));
+	string_getter(imports, "unescape", |nodes| html::unescape(&nodes.html()));
+	string_getter(imports, "id", Nodes::id);
+	string_getter(imports, "tag_name", Nodes::tag_name);
+	string_getter(imports, "class_name", Nodes::class_name);
+
+	imports.func("array", |mut caller: Caller<HostState>, rid: i32| {
+		let value = nodes(&caller, rid)
+			.map(|nodes| Value::Array(nodes.array().into_iter().map(Value::Node).collect()));
+		insert(&mut caller, value)
+	});
+}
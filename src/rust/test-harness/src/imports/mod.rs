@@ -0,0 +1,198 @@
+//! Host implementations of the modules a source imports.
+//!
+//! Imports the harness doesn't implement (or whose signature doesn't match)
+//! are still linked, but trap with a message naming them when called, so a
+//! source only fails if it actually relies on them.
+use std::collections::{BTreeMap, HashMap};
+
+use wasmi::{Caller, Error, Extern, Func, FuncType, IntoFunc, Linker, Module, Store, Val};
+
+use crate::{
+	fixtures::{Fixtures, Response},
+	value::{Value, Values},
+	SentRequest,
+};
+
+mod aidoku;
+mod defaults;
+mod env;
+mod html;
+mod net;
+mod values;
+
+pub(crate) struct Request {
+	pub method: &'static str,
+	pub url: String,
+	pub headers: Vec<(String, String)>,
+	pub body: Option<Vec<u8>>,
+	pub response: Option<Response>,
+}
+
+#[derive(Default)]
+pub(crate) struct HostState {
+	pub values: Values,
+	pub requests: HashMap<i32, Request>,
+	next_request: i32,
+	pub fixtures: Fixtures,
+	pub defaults: BTreeMap<String, Value>,
+	pub sent: Vec<SentRequest>,
+	/// Requests without a fixture since the last call into the source.
+	pub unmatched: Vec<String>,
+	pub logs: Vec<String>,
+}
+
+impl HostState {
+	pub fn new(fixtures: Fixtures) -> Self {
+		Self {
+			fixtures,
+			..Default::default()
+		}
+	}
+
+	pub fn create_request(&mut self, method: &'static str, url: &str) -> i32 {
+		self.next_request += 1;
+		self.requests.insert(
+			self.next_request,
+			Request {
+				method,
+				url: String::from(url),
+				headers: Vec::new(),
+				body: None,
+				response: None,
+			},
+		);
+		self.next_request
+	}
+
+	/// Sends the request if it hasn't been sent yet and returns its response.
+	pub fn response(&mut self, rd: i32) -> Option<&Response> {
+		let request = self.requests.get_mut(&rd)?;
+		if request.response.is_none() {
+			self.sent.push(SentRequest {
+				method: String::from(request.method),
+				url: request.url.clone(),
+				headers: request.headers.clone(),
+				body: request.body.clone(),
+			});
+			let response = self.fixtures.respond(request.method, &request.url);
+			if response.is_none() {
+				self.unmatched
+					.push(format!("{} {}", request.method, request.url));
+			}
+			request.response = Some(response.unwrap_or_default());
+		}
+		request.response.as_ref()
+	}
+}
+
+pub(crate) fn read_bytes(caller: &Caller<HostState>, ptr: i32, len: i32) -> Result<Vec<u8>, Error> {
+	let mut buffer = vec![0; len.max(0) as usize];
+	if !buffer.is_empty() {
+		memory(caller)?.read(caller, ptr as usize, &mut buffer)?;
+	}
+	Ok(buffer)
+}
+
+pub(crate) fn read_string(caller: &Caller<HostState>, ptr: i32, len: i32) -> Result<String, Error> {
+	Ok(String::from_utf8_lossy(&read_bytes(caller, ptr, len)?).into_owned())
+}
+
+pub(crate) fn write_bytes(
+	caller: &mut Caller<HostState>,
+	ptr: i32,
+	bytes: &[u8],
+) -> Result<(), Error> {
+	memory(caller)?.write(caller, ptr as usize, bytes)?;
+	Ok(())
+}
+
+fn memory(caller: &Caller<HostState>) -> Result<wasmi::Memory, Error> {
+	caller
+		.get_export("memory")
+		.and_then(Extern::into_memory)
+		.ok_or_else(|| Error::new("the source doesn't export its memory"))
+}
+
+/// Collects host functions for one import module at a time.
+pub(crate) struct Imports<'a> {
+	store: &'a mut Store<HostState>,
+	module: &'static str,
+	funcs: HashMap<(&'static str, &'static str), Func>,
+}
+
+impl Imports<'_> {
+	fn module(&mut self, module: &'static str) -> &mut Self {
+		self.module = module;
+		self
+	}
+
+	fn func<Params, Results>(
+		&mut self,
+		name: &'static str,
+		func: impl IntoFunc<HostState, Params, Results>,
+	) -> &mut Self {
+		let func = Func::wrap(&mut *self.store, func);
+		self.funcs.insert((self.module, name), func);
+		self
+	}
+
+	/// For functions with more parameters than [`Func::wrap`] supports.
+	fn func_new(
+		&mut self,
+		name: &'static str,
+		ty: FuncType,
+		func: impl Fn(Caller<HostState>, &[Val], &mut [Val]) -> Result<(), Error>
+			+ Send
+			+ Sync
+			+ 'static,
+	) -> &mut Self {
+		let func = Func::new(&mut *self.store, ty, func);
+		self.funcs.insert((self.module, name), func);
+		self
+	}
+}
+
+pub(crate) fn link(
+	store: &mut Store<HostState>,
+	module: &Module,
+) -> Result<Linker<HostState>, Error> {
+	let mut imports = Imports {
+		store: &mut *store,
+		module: "",
+		funcs: HashMap::new(),
+	};
+	values::register(imports.module("std"));
+	net::register(imports.module("net"));
+	html::register(imports.module("html"));
+	defaults::register(imports.module("defaults"));
+	aidoku::register(imports.module("aidoku"));
+	env::register(imports.module("env"));
+	let funcs = imports.funcs;
+
+	let mut linker = Linker::new(store.engine());
+	for import in module.imports() {
+		let Some(ty) = import.ty().func() else {
+			continue;
+		};
+		let func = match funcs.get(&(import.module(), import.name())) {
+			Some(func) if func.ty(&*store) == *ty => *func,
+			known => {
+				let message = format!(
+					"{}::{} {}",
+					import.module(),
+					import.name(),
+					if known.is_some() {
+						"has a different signature in the test harness"
+					} else {
+						"isn't implemented by the test harness"
+					}
+				);
+				Func::new(&mut *store, ty.clone(), move |_, _, _| {
+					Err(Error::new(message.clone()))
+				})
+			}
+		};
+		linker.define(import.module(), import.name(), func)?;
+	}
+	Ok(linker)
+}
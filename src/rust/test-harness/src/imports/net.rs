@@ -0,0 +1,126 @@
+//! `net`: requests, answered from the fixtures.
+use wasmi::{Caller, Error};
+
+use super::{read_bytes, read_string, write_bytes, HostState, Imports};
+use crate::{html::Nodes, value::Value};
+
+const METHODS: [&str; 5] = ["GET", "POST", "HEAD", "PUT", "DELETE"];
+
+fn body(caller: &mut Caller<HostState>, rd: i32) -> Vec<u8> {
+	caller
+		.data_mut()
+		.response(rd)
+		.map(|response| response.body.clone())
+		.unwrap_or_default()
+}
+
+pub(super) fn register(imports: &mut Imports) {
+	imports
+		.func("init", |mut caller: Caller<HostState>, method: i32| {
+			let method = usize::try_from(method)
+				.ok()
+				.and_then(|i| METHODS.get(i))
+				.unwrap_or(&"GET");
+			caller.data_mut().create_request(method, "")
+		})
+		// Like the host, sending again re-sends the request.
+		.func("send", |mut caller: Caller<HostState>, rd: i32| {
+			if let Some(request) = caller.data_mut().requests.get_mut(&rd) {
+				request.response = None;
+			}
+			caller.data_mut().response(rd);
+		})
+		.func("close", |mut caller: Caller<HostState>, rd: i32| {
+			caller.data_mut().requests.remove(&rd);
+		})
+		.func(
+			"set_url",
+			|mut caller: Caller<HostState>, rd: i32, ptr: i32, len: i32| -> Result<(), Error> {
+				let url = read_string(&caller, ptr, len)?;
+				if let Some(request) = caller.data_mut().requests.get_mut(&rd) {
+					request.url = url;
+				}
+				Ok(())
+			},
+		)
+		.func(
+			"set_header",
+			|mut caller: Caller<HostState>,
+			 rd: i32,
+			 key: i32,
+			 key_len: i32,
+			 value: i32,
+			 value_len: i32|
+			 -> Result<(), Error> {
+				let key = read_string(&caller, key, key_len)?;
+				let value = read_string(&caller, value, value_len)?;
+				if let Some(request) = caller.data_mut().requests.get_mut(&rd) {
+					request.headers.retain(|(k, _)| !k.eq_ignore_ascii_case(&key));
+					request.headers.push((key, value));
+				}
+				Ok(())
+			},
+		)
+		.func(
+			"set_body",
+			|mut caller: Caller<HostState>, rd: i32, ptr: i32, len: i32| -> Result<(), Error> {
+				let body = read_bytes(&caller, ptr, len)?;
+				if let Some(request) = caller.data_mut().requests.get_mut(&rd) {
+					request.body = Some(body);
+				}
+				Ok(())
+			},
+		)
+		// Fixtures are served instantly, so there's nothing to limit.
+		.func("set_rate_limit", |_: Caller<HostState>, _: i32| {})
+		.func("set_rate_limit_period", |_: Caller<HostState>, _: i32| {})
+		.func("get_url", |mut caller: Caller<HostState>, rd: i32| {
+			let url = caller.data().requests.get(&rd).map(|r| r.url.clone());
+			url.map_or(-1, |url| caller.data_mut().values.insert(Value::String(url)))
+		})
+		.func("get_data_size", |mut caller: Caller<HostState>, rd: i32| {
+			caller
+				.data_mut()
+				.response(rd)
+				.map_or(-1, |response| response.body.len() as i32)
+		})
+		.func(
+			"get_data",
+			|mut caller: Caller<HostState>, rd: i32, ptr: i32, size: i32| -> Result<(), Error> {
+				let body = body(&mut caller, rd);
+				let len = body.len().min(size.max(0) as usize);
+				write_bytes(&mut caller, ptr, &body[..len])
+			},
+		)
+		.func(
+			"get_header",
+			|mut caller: Caller<HostState>, rd: i32, ptr: i32, len: i32| -> Result<i32, Error> {
+				let name = read_string(&caller, ptr, len)?;
+				let value = caller
+					.data_mut()
+					.response(rd)
+					.and_then(|response| response.header(&name))
+					.map(String::from);
+				Ok(value.map_or(-1, |value| caller.data_mut().values.insert(Value::String(value))))
+			},
+		)
+		.func("get_status_code", |mut caller: Caller<HostState>, rd: i32| {
+			caller
+				.data_mut()
+				.response(rd)
+				.map_or(-1, |response| response.status)
+		})
+		.func("json", |mut caller: Caller<HostState>, rd: i32| {
+			let body = body(&mut caller, rd);
+			match serde_json::from_slice::<serde_json::Value>(&body) {
+				Ok(json) => caller.data_mut().values.insert(json.into()),
+				Err(_) => -1,
+			}
+		})
+		.func("html", |mut caller: Caller<HostState>, rd: i32| {
+			let body = body(&mut caller, rd);
+			let url = caller.data().requests.get(&rd).map(|r| r.url.clone());
+			let nodes = Nodes::parse(&String::from_utf8_lossy(&body), &url.unwrap_or_default());
+			caller.data_mut().values.insert(Value::Node(nodes))
+		});
+}
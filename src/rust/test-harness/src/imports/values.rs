@@ -0,0 +1,213 @@
+//! `std`: value descriptors.
+use std::{
+	collections::BTreeMap,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use wasmi::{Caller, Error};
+
+use super::{read_string, write_bytes, HostState, Imports};
+use crate::{date, value::Value};
+
+fn now() -> f64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs_f64())
+		.unwrap_or_default()
+}
+
+fn insert(caller: &mut Caller<HostState>, value: Value) -> i32 {
+	caller.data_mut().values.insert(value)
+}
+
+fn get(caller: &Caller<HostState>, rid: i32) -> Value {
+	caller.data().values.get(rid).clone()
+}
+
+fn optional_string(
+	caller: &Caller<HostState>,
+	ptr: i32,
+	len: i32,
+) -> Result<Option<String>, Error> {
+	let value = read_string(caller, ptr, len)?;
+	Ok(Some(value).filter(|v| !v.is_empty()))
+}
+
+pub(super) fn register(imports: &mut Imports) {
+	imports
+		.func("copy", |mut caller: Caller<HostState>, rid: i32| {
+			let value = get(&caller, rid);
+			insert(&mut caller, value)
+		})
+		.func("destroy", |mut caller: Caller<HostState>, rid: i32| {
+			caller.data_mut().values.remove(rid);
+		})
+		.func("create_null", |mut caller: Caller<HostState>| {
+			insert(&mut caller, Value::Null)
+		})
+		.func("create_int", |mut caller: Caller<HostState>, value: i64| {
+			insert(&mut caller, Value::Int(value))
+		})
+		.func("create_float", |mut caller: Caller<HostState>, value: f64| {
+			insert(&mut caller, Value::Float(value))
+		})
+		.func(
+			"create_string",
+			|mut caller: Caller<HostState>, ptr: i32, len: i32| -> Result<i32, Error> {
+				let value = read_string(&caller, ptr, len)?;
+				Ok(insert(&mut caller, Value::String(value)))
+			},
+		)
+		.func("create_bool", |mut caller: Caller<HostState>, value: i32| {
+			insert(&mut caller, Value::Bool(value != 0))
+		})
+		.func("create_object", |mut caller: Caller<HostState>| {
+			insert(&mut caller, Value::Object(BTreeMap::new()))
+		})
+		.func("create_array", |mut caller: Caller<HostState>| {
+			insert(&mut caller, Value::Array(Vec::new()))
+		})
+		// A negative date means "now".
+		.func("create_date", |mut caller: Caller<HostState>, value: f64| {
+			let value = if value < 0.0 { now() } else { value };
+			insert(&mut caller, Value::Date(value))
+		})
+		.func("typeof", |caller: Caller<HostState>, rid: i32| {
+			caller.data().values.get(rid).kind()
+		})
+		.func("string_len", |caller: Caller<HostState>, rid: i32| {
+			caller.data().values.get(rid).as_str().len() as i32
+		})
+		.func(
+			"read_string",
+			|mut caller: Caller<HostState>, rid: i32, ptr: i32, len: i32| -> Result<(), Error> {
+				let value = get(&caller, rid);
+				let bytes = value.as_str().as_bytes();
+				let len = bytes.len().min(len.max(0) as usize);
+				write_bytes(&mut caller, ptr, &bytes[..len])
+			},
+		)
+		.func("read_int", |caller: Caller<HostState>, rid: i32| {
+			caller.data().values.get(rid).as_int()
+		})
+		.func("read_float", |caller: Caller<HostState>, rid: i32| {
+			caller.data().values.get(rid).as_float()
+		})
+		.func("read_bool", |caller: Caller<HostState>, rid: i32| {
+			caller.data().values.get(rid).as_bool() as i32
+		})
+		.func("read_date", |caller: Caller<HostState>, rid: i32| {
+			match caller.data().values.get(rid) {
+				Value::Date(value) => *value,
+				_ => -1.0,
+			}
+		})
+		.func(
+			"read_date_string",
+			|caller: Caller<HostState>,
+			 rid: i32,
+			 format: i32,
+			 format_len: i32,
+			 _locale: i32,
+			 _locale_len: i32,
+			 timezone: i32,
+			 timezone_len: i32|
+			 -> Result<f64, Error> {
+				let format = read_string(&caller, format, format_len)?;
+				let timezone = optional_string(&caller, timezone, timezone_len)?;
+				let value = get(&caller, rid);
+				Ok(date::parse(value.as_str(), &format, timezone.as_deref()).unwrap_or(-1.0))
+			},
+		)
+		.func("object_len", |caller: Caller<HostState>, rid: i32| {
+			match caller.data().values.get(rid) {
+				Value::Object(object) => object.len() as i32,
+				_ => 0,
+			}
+		})
+		.func(
+			"object_get",
+			|mut caller: Caller<HostState>, rid: i32, key: i32, len: i32| -> Result<i32, Error> {
+				let key = read_string(&caller, key, len)?;
+				let value = match caller.data().values.get(rid) {
+					Value::Object(object) => object.get(&key).cloned(),
+					_ => None,
+				};
+				Ok(value.map_or(-1, |value| insert(&mut caller, value)))
+			},
+		)
+		.func(
+			"object_set",
+			|mut caller: Caller<HostState>, rid: i32, key: i32, len: i32, value: i32| -> Result<(), Error> {
+				let key = read_string(&caller, key, len)?;
+				let value = get(&caller, value);
+				if let Some(Value::Object(object)) = caller.data_mut().values.get_mut(rid) {
+					object.insert(key, value);
+				}
+				Ok(())
+			},
+		)
+		.func(
+			"object_remove",
+			|mut caller: Caller<HostState>, rid: i32, key: i32, len: i32| -> Result<(), Error> {
+				let key = read_string(&caller, key, len)?;
+				if let Some(Value::Object(object)) = caller.data_mut().values.get_mut(rid) {
+					object.remove(&key);
+				}
+				Ok(())
+			},
+		)
+		.func("object_keys", |mut caller: Caller<HostState>, rid: i32| {
+			let keys = match caller.data().values.get(rid) {
+				Value::Object(object) => object.keys().cloned().map(Value::String).collect(),
+				_ => Vec::new(),
+			};
+			insert(&mut caller, Value::Array(keys))
+		})
+		.func("object_values", |mut caller: Caller<HostState>, rid: i32| {
+			let values = match caller.data().values.get(rid) {
+				Value::Object(object) => object.values().cloned().collect(),
+				_ => Vec::new(),
+			};
+			insert(&mut caller, Value::Array(values))
+		})
+		.func("array_len", |caller: Caller<HostState>, rid: i32| {
+			match caller.data().values.get(rid) {
+				Value::Array(array) => array.len() as i32,
+				_ => 0,
+			}
+		})
+		.func("array_get", |mut caller: Caller<HostState>, rid: i32, index: i32| {
+			let value = match caller.data().values.get(rid) {
+				Value::Array(array) => usize::try_from(index).ok().and_then(|i| array.get(i)).cloned(),
+				_ => None,
+			};
+			value.map_or(-1, |value| insert(&mut caller, value))
+		})
+		.func(
+			"array_set",
+			|mut caller: Caller<HostState>, rid: i32, index: i32, value: i32| {
+				let value = get(&caller, value);
+				if let Some(Value::Array(array)) = caller.data_mut().values.get_mut(rid) {
+					if let Some(slot) = usize::try_from(index).ok().and_then(|i| array.get_mut(i)) {
+						*slot = value;
+					}
+				}
+			},
+		)
+		.func("array_remove", |mut caller: Caller<HostState>, rid: i32, index: i32| {
+			if let Some(Value::Array(array)) = caller.data_mut().values.get_mut(rid) {
+				if let Ok(index) = usize::try_from(index) {
+					if index < array.len() {
+						array.remove(index);
+					}
+				}
+			}
+		})
+		.func("array_append", |mut caller: Caller<HostState>, rid: i32, value: i32| {
+			let value = get(&caller, value);
+			if let Some(Value::Array(array)) = caller.data_mut().values.get_mut(rid) {
+				array.push(value);
+			}
+		});
+}
@@ -0,0 +1,29 @@
+//! Runs sources offline, for regression tests.
+//!
+//! A source's wasm module is loaded into an interpreter, and the modules it
+//! imports from the app (`std`, `net`, `html`, `defaults`, `aidoku`) are
+//! implemented on the host. Requests are answered from recorded fixtures
+//! instead of the network, so tests are deterministic and run anywhere.
+//!
+//! ```ignore
+//! let fixtures = Fixtures::load("tests/fixtures/multi.xkcd")?;
+//! let mut source = Harness::source("multi.xkcd", fixtures)?;
+//! let chapters = source.get_chapter_list("multi.xkcd.en")?;
+//! assert_eq!(chapters[0].chapter, 2000.0);
+//! ```
+mod date;
+mod error;
+mod fixtures;
+mod harness;
+mod html;
+mod imports;
+mod model;
+mod value;
+
+pub use error::Error;
+pub use fixtures::{Fixtures, Response};
+pub use harness::{Harness, SentRequest};
+pub use model::{
+	Chapter, DeepLink, Filter, FilterType, ImageRequest, Manga, MangaContentRating,
+	MangaPageResult, MangaStatus, MangaViewer, Page,
+};
@@ -0,0 +1,194 @@
+//! Host-side copies of the structs a source hands back to Aidoku.
+use std::collections::BTreeMap;
+
+use crate::value::Value;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MangaStatus {
+	#[default]
+	Unknown,
+	Ongoing,
+	Completed,
+	Cancelled,
+	Hiatus,
+}
+
+impl From<i32> for MangaStatus {
+	fn from(value: i32) -> Self {
+		match value {
+			1 => Self::Ongoing,
+			2 => Self::Completed,
+			3 => Self::Cancelled,
+			4 => Self::Hiatus,
+			_ => Self::Unknown,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MangaContentRating {
+	#[default]
+	Safe,
+	Suggestive,
+	Nsfw,
+}
+
+impl From<i32> for MangaContentRating {
+	fn from(value: i32) -> Self {
+		match value {
+			1 => Self::Suggestive,
+			2 => Self::Nsfw,
+			_ => Self::Safe,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MangaViewer {
+	#[default]
+	Default,
+	Rtl,
+	Ltr,
+	Vertical,
+	Scroll,
+}
+
+impl From<i32> for MangaViewer {
+	fn from(value: i32) -> Self {
+		match value {
+			1 => Self::Rtl,
+			2 => Self::Ltr,
+			3 => Self::Vertical,
+			4 => Self::Scroll,
+			_ => Self::Default,
+		}
+	}
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Manga {
+	pub id: String,
+	pub cover: String,
+	pub title: String,
+	pub author: String,
+	pub artist: String,
+	pub description: String,
+	pub url: String,
+	pub categories: Vec<String>,
+	pub status: MangaStatus,
+	pub nsfw: MangaContentRating,
+	pub viewer: MangaViewer,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MangaPageResult {
+	pub manga: Vec<Manga>,
+	pub has_more: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Chapter {
+	pub id: String,
+	pub title: String,
+	pub volume: f32,
+	pub chapter: f32,
+	pub date_updated: f64,
+	pub scanlator: String,
+	pub url: String,
+	pub lang: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Page {
+	pub index: i32,
+	pub url: String,
+	pub base64: String,
+	pub text: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeepLink {
+	pub manga: Option<Manga>,
+	pub chapter: Option<Chapter>,
+}
+
+/// The image request a source returned from `modify_image_request`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImageRequest {
+	pub url: String,
+	pub headers: Vec<(String, String)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterType {
+	Base,
+	Group,
+	Text,
+	Check,
+	Select,
+	Sort,
+	SortSelection,
+	Title,
+	Author,
+	Genre,
+}
+
+/// A filter passed to `get_manga_list`, laid out the way the app sends it.
+#[derive(Clone, Debug)]
+pub struct Filter {
+	pub kind: FilterType,
+	pub name: String,
+	pub(crate) value: Value,
+}
+
+impl Filter {
+	fn new(kind: FilterType, name: &str, value: Value) -> Self {
+		Self {
+			kind,
+			name: String::from(name),
+			value,
+		}
+	}
+
+	pub fn title(query: &str) -> Self {
+		Self::new(FilterType::Title, "Title", Value::String(query.into()))
+	}
+
+	pub fn author(query: &str) -> Self {
+		Self::new(FilterType::Author, "Author", Value::String(query.into()))
+	}
+
+	pub fn text(name: &str, value: &str) -> Self {
+		Self::new(FilterType::Text, name, Value::String(value.into()))
+	}
+
+	/// `state` is 0 for unset, 1 for included and 2 for excluded.
+	pub fn check(name: &str, state: i64) -> Self {
+		Self::new(FilterType::Check, name, Value::Int(state))
+	}
+
+	/// `state` is 0 for unset, 1 for included and 2 for excluded.
+	pub fn genre(name: &str, state: i64) -> Self {
+		Self::new(FilterType::Genre, name, Value::Int(state))
+	}
+
+	pub fn select(name: &str, index: i64) -> Self {
+		Self::new(FilterType::Select, name, Value::Int(index))
+	}
+
+	pub fn sort(name: &str, index: i64, ascending: bool) -> Self {
+		let value = BTreeMap::from([
+			(String::from("index"), Value::Int(index)),
+			(String::from("ascending"), Value::Bool(ascending)),
+		]);
+		Self::new(FilterType::Sort, name, Value::Object(value))
+	}
+
+	pub(crate) fn to_value(&self) -> Value {
+		Value::Object(BTreeMap::from([
+			(String::from("type"), Value::Int(self.kind as i64)),
+			(String::from("name"), Value::String(self.name.clone())),
+			(String::from("value"), self.value.clone()),
+		]))
+	}
+}
@@ -0,0 +1,150 @@
+//! Values handed to the source by descriptor, like the app's `std` module.
+use std::collections::{BTreeMap, HashMap};
+
+use crate::html::Nodes;
+use crate::model::{Chapter, DeepLink, Manga, MangaPageResult, Page};
+
+#[derive(Clone, Debug)]
+pub enum Value {
+	Null,
+	Int(i64),
+	Float(f64),
+	String(String),
+	Bool(bool),
+	Array(Vec<Value>),
+	Object(BTreeMap<String, Value>),
+	Date(f64),
+	Node(Nodes),
+	Manga(Manga),
+	MangaPageResult(MangaPageResult),
+	Chapter(Chapter),
+	Page(Page),
+	DeepLink(DeepLink),
+}
+
+impl Value {
+	/// The kind reported by `std.typeof`.
+	pub fn kind(&self) -> i32 {
+		match self {
+			Self::Null => 0,
+			Self::Int(_) => 1,
+			Self::Float(_) => 2,
+			Self::String(_) => 3,
+			Self::Bool(_) => 4,
+			Self::Array(_) => 5,
+			Self::Object(_) => 6,
+			Self::Date(_) => 7,
+			Self::Node(_) => 8,
+			_ => 9,
+		}
+	}
+
+	pub fn as_int(&self) -> i64 {
+		match self {
+			Self::Int(value) => *value,
+			Self::Float(value) | Self::Date(value) => *value as i64,
+			Self::Bool(value) => *value as i64,
+			Self::String(value) => {
+				let value = value.trim();
+				value
+					.parse::<i64>()
+					.ok()
+					.or_else(|| value.parse::<f64>().ok().map(|v| v as i64))
+					.unwrap_or(0)
+			}
+			_ => 0,
+		}
+	}
+
+	pub fn as_float(&self) -> f64 {
+		match self {
+			Self::Float(value) | Self::Date(value) => *value,
+			Self::String(value) => value.trim().parse().unwrap_or(0.0),
+			_ => self.as_int() as f64,
+		}
+	}
+
+	pub fn as_bool(&self) -> bool {
+		match self {
+			Self::Bool(value) => *value,
+			Self::Int(value) => *value != 0,
+			Self::String(value) => value == "true",
+			_ => false,
+		}
+	}
+
+	pub fn as_str(&self) -> &str {
+		match self {
+			Self::String(value) => value,
+			_ => "",
+		}
+	}
+}
+
+impl From<serde_json::Value> for Value {
+	fn from(value: serde_json::Value) -> Self {
+		match value {
+			serde_json::Value::Null => Self::Null,
+			serde_json::Value::Bool(value) => Self::Bool(value),
+			serde_json::Value::Number(value) => match value.as_i64() {
+				Some(value) => Self::Int(value),
+				None => Self::Float(value.as_f64().unwrap_or_default()),
+			},
+			serde_json::Value::String(value) => Self::String(value),
+			serde_json::Value::Array(values) => {
+				Self::Array(values.into_iter().map(Self::from).collect())
+			}
+			serde_json::Value::Object(values) => Self::Object(
+				values
+					.into_iter()
+					.map(|(key, value)| (key, Self::from(value)))
+					.collect(),
+			),
+		}
+	}
+}
+
+impl From<&Value> for serde_json::Value {
+	fn from(value: &Value) -> Self {
+		match value {
+			Value::Int(value) => (*value).into(),
+			Value::Float(value) | Value::Date(value) => (*value).into(),
+			Value::String(value) => value.as_str().into(),
+			Value::Bool(value) => (*value).into(),
+			Value::Array(values) => values.iter().map(Self::from).collect(),
+			Value::Object(values) => values
+				.iter()
+				.map(|(key, value)| (key.clone(), Self::from(value)))
+				.collect(),
+			_ => Self::Null,
+		}
+	}
+}
+
+/// Descriptor table. Unknown descriptors read as [`Value::Null`], the way
+/// the app treats `-1`.
+#[derive(Default)]
+pub struct Values {
+	values: HashMap<i32, Value>,
+	next: i32,
+}
+
+impl Values {
+	pub fn insert(&mut self, value: Value) -> i32 {
+		self.next += 1;
+		self.values.insert(self.next, value);
+		self.next
+	}
+
+	pub fn get(&self, rid: i32) -> &Value {
+		self.values.get(&rid).unwrap_or(&Value::Null)
+	}
+
+	pub fn get_mut(&mut self, rid: i32) -> Option<&mut Value> {
+		self.values.get_mut(&rid)
+	}
+
+	pub fn remove(&mut self, rid: i32) -> Option<Value> {
+		self.values.remove(&rid)
+	}
+}
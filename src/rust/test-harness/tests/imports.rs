@@ -0,0 +1,194 @@
+//! Drives the host imports through a hand-written module that calls them the
+//! way aidoku-rs does, so the harness itself is tested without building a
+//! source for wasm32.
+use test_harness::{Error, Fixtures, Harness, MangaStatus, MangaViewer};
+
+const SOURCE: &str = r#"
+(module
+	(import "std" "string_len" (func $string_len (param i32) (result i32)))
+	(import "std" "read_string" (func $read_string (param i32 i32 i32)))
+	(import "std" "read_date_string" (func $read_date_string (param i32 i32 i32 i32 i32 i32 i32) (result f64)))
+	(import "std" "create_array" (func $create_array (result i32)))
+	(import "std" "array_len" (func $array_len (param i32) (result i32)))
+	(import "std" "array_get" (func $array_get (param i32 i32) (result i32)))
+	(import "std" "array_append" (func $array_append (param i32 i32)))
+	(import "std" "object_get" (func $object_get (param i32 i32 i32) (result i32)))
+	(import "net" "init" (func $init (param i32) (result i32)))
+	(import "net" "set_url" (func $set_url (param i32 i32 i32)))
+	(import "net" "send" (func $send (param i32)))
+	(import "net" "close" (func $close (param i32)))
+	(import "net" "json" (func $json (param i32) (result i32)))
+	(import "net" "html" (func $html (param i32) (result i32)))
+	(import "net" "not_a_real_import" (func $bogus (param i32)))
+	(import "html" "select" (func $select (param i32 i32 i32) (result i32)))
+	(import "html" "array" (func $array (param i32) (result i32)))
+	(import "html" "attr" (func $attr (param i32 i32 i32) (result i32)))
+	(import "html" "text" (func $text (param i32) (result i32)))
+	(import "aidoku" "create_manga" (func $create_manga
+		(param i32 i32 i32 i32 i32 i32 i32 i32 i32 i32 i32 i32 i32 i32 i32 i32 i32 i32 i32 i32)
+		(result i32)))
+	(import "aidoku" "create_chapter" (func $create_chapter
+		(param i32 i32 i32 i32 f32 f32 f64 i32 i32 i32 i32 i32 i32)
+		(result i32)))
+
+	(memory (export "memory") 1)
+	(data (i32.const 0) "https://example.com/manga")
+	(data (i32.const 32) "li.chapter a")
+	(data (i32.const 48) "abs:href")
+	(data (i32.const 64) "en")
+	(data (i32.const 80) "https://example.com/api/manga")
+	(data (i32.const 112) "title")
+	(data (i32.const 128) "Action")
+	(data (i32.const 136) "Comedy")
+	(data (i32.const 160) "\80\00\00\00\88\00\00\00")
+	(data (i32.const 168) "\06\00\00\00\06\00\00\00")
+	(data (i32.const 176) "id")
+	(data (i32.const 192) "https://example.com/missing")
+	(data (i32.const 224) "yyyy-MM-dd")
+
+	;; Reads the string behind `rid` into memory at `ptr`, returning its length.
+	(func $read (param $rid i32) (param $ptr i32) (result i32)
+		(local $len i32)
+		(local.set $len (call $string_len (local.get $rid)))
+		(call $read_string (local.get $rid) (local.get $ptr) (local.get $len))
+		(local.get $len))
+
+	(func (export "get_chapter_list") (param $manga i32) (result i32)
+		(local $rd i32) (local $links i32) (local $link i32) (local $result i32)
+		(local $i i32) (local $count i32) (local $url_len i32) (local $title_len i32)
+		(local.set $rd (call $init (i32.const 0)))
+		(call $set_url (local.get $rd) (i32.const 0) (i32.const 25))
+		(local.set $links (call $array
+			(call $select (call $html (local.get $rd)) (i32.const 32) (i32.const 12))))
+		(call $close (local.get $rd))
+		(local.set $count (call $array_len (local.get $links)))
+		(local.set $result (call $create_array))
+		(block $done
+			(loop $next
+				(br_if $done (i32.ge_s (local.get $i) (local.get $count)))
+				(local.set $link (call $array_get (local.get $links) (local.get $i)))
+				(local.set $url_len
+					(call $read (call $attr (local.get $link) (i32.const 48) (i32.const 8)) (i32.const 1024)))
+				(local.set $title_len (call $read (call $text (local.get $link)) (i32.const 2048)))
+				(call $array_append (local.get $result)
+					(call $create_chapter
+						(i32.const 1024) (local.get $url_len)
+						(i32.const 2048) (local.get $title_len)
+						(f32.const -1)
+						(f32.convert_i32_s (i32.sub (local.get $count) (local.get $i)))
+						(call $read_date_string
+							(call $attr (local.get $link) (i32.const 112) (i32.const 5))
+							(i32.const 224) (i32.const 10)
+							(i32.const 0) (i32.const 0)
+							(i32.const 0) (i32.const 0))
+						(i32.const 0) (i32.const 0)
+						(i32.const 1024) (local.get $url_len)
+						(i32.const 64) (i32.const 2)))
+				(local.set $i (i32.add (local.get $i) (i32.const 1)))
+				(br $next)))
+		(local.get $result))
+
+	(func (export "get_manga_details") (param $manga i32) (result i32)
+		(local $rd i32) (local $json i32) (local $id_len i32) (local $title_len i32)
+		(local.set $id_len
+			(call $read (call $object_get (local.get $manga) (i32.const 176) (i32.const 2)) (i32.const 3584)))
+		(local.set $rd (call $init (i32.const 0)))
+		(call $set_url (local.get $rd) (i32.const 80) (i32.const 29))
+		(local.set $json (call $json (local.get $rd)))
+		(call $close (local.get $rd))
+		(local.set $title_len
+			(call $read (call $object_get (local.get $json) (i32.const 112) (i32.const 5)) (i32.const 3072)))
+		(call $create_manga
+			(i32.const 3584) (local.get $id_len)
+			(i32.const 0) (i32.const 0)
+			(i32.const 3072) (local.get $title_len)
+			(i32.const 0) (i32.const 0)
+			(i32.const 0) (i32.const 0)
+			(i32.const 0) (i32.const 0)
+			(i32.const 0) (i32.const 25)
+			(i32.const 160) (i32.const 168) (i32.const 2)
+			(i32.const 1) (i32.const 0) (i32.const 4)))
+
+	(func (export "handle_url") (param $url i32) (result i32)
+		(local $rd i32)
+		(local.set $rd (call $init (i32.const 0)))
+		(call $set_url (local.get $rd) (i32.const 192) (i32.const 27))
+		(call $send (local.get $rd))
+		(call $close (local.get $rd))
+		(i32.const -1))
+
+	(func (export "handle_notification") (param $notification i32)
+		(call $bogus (i32.const 0)))
+)
+"#;
+
+const MANGA_PAGE: &str = r#"<ul>
+	<li class="chapter"><a href="/manga/2" title="2024-01-02"> Chapter 2 </a></li>
+	<li class="chapter"><a href="https://cdn.example.com/manga/1" title="bad date">Chapter <b>1</b></a></li>
+</ul>"#;
+
+fn harness() -> Harness {
+	let wasm = wat::parse_str(SOURCE).unwrap();
+	let fixtures = Fixtures::new()
+		.with("https://example.com/manga", MANGA_PAGE)
+		.with("https://example.com/api/manga", r#"{"title":"Example"}"#);
+	Harness::new(&wasm, fixtures).unwrap()
+}
+
+#[test]
+fn parses_html_fixtures() {
+	let mut source = harness();
+	let chapters = source.get_chapter_list("example").unwrap();
+	assert_eq!(chapters.len(), 2);
+	assert_eq!(chapters[0].url, "https://example.com/manga/2");
+	assert_eq!(chapters[0].title, "Chapter 2");
+	assert_eq!(chapters[0].chapter, 2.0);
+	assert_eq!(chapters[0].date_updated, 1704153600.0);
+	assert_eq!(chapters[0].lang, "en");
+	assert_eq!(chapters[1].url, "https://cdn.example.com/manga/1");
+	assert_eq!(chapters[1].title, "Chapter 1");
+	assert_eq!(chapters[1].date_updated, -1.0);
+}
+
+#[test]
+fn parses_json_fixtures() {
+	let mut source = harness();
+	let manga = source.get_manga_details("example").unwrap();
+	assert_eq!(manga.id, "example");
+	assert_eq!(manga.title, "Example");
+	assert_eq!(manga.url, "https://example.com/manga");
+	assert_eq!(manga.categories, ["Action", "Comedy"]);
+	assert_eq!(manga.status, MangaStatus::Ongoing);
+	assert_eq!(manga.viewer, MangaViewer::Scroll);
+	assert_eq!(source.requests().len(), 1);
+	assert_eq!(source.requests()[0].url, "https://example.com/api/manga");
+}
+
+#[test]
+fn reports_requests_without_fixtures() {
+	let error = harness().handle_url("https://example.com").unwrap_err();
+	match error {
+		Error::Source {
+			function,
+			unmatched,
+		} => {
+			assert_eq!(function, "handle_url");
+			assert_eq!(unmatched, ["GET https://example.com/missing"]);
+		}
+		error => panic!("unexpected error: {error}"),
+	}
+}
+
+#[test]
+fn traps_on_missing_imports_only_when_called() {
+	let error = harness().handle_notification("login").unwrap_err();
+	assert!(error
+		.to_string()
+		.contains("net::not_a_real_import isn't implemented by the test harness"));
+}
+
+#[test]
+fn reports_unbuilt_sources() {
+	let error = Harness::source("multi.xkcd/does-not-exist", Fixtures::new());
+	assert!(matches!(error, Err(Error::Io(..))));
+}
@@ -0,0 +1,53 @@
+//! Regression tests for the madara template, through 3asq and NovelMic.
+//! Build them first with `cargo build --release -p aasq -p novelmic` in
+//! `src/rust/madara`, then run `cargo test -- --ignored`.
+use test_harness::{Fixtures, Harness};
+
+fn source() -> Harness {
+	let fixtures = Fixtures::load("tests/fixtures/ar.aasq").unwrap();
+	Harness::source("madara/sources/aasq", fixtures).unwrap()
+}
+
+fn novelmic() -> Harness {
+	let fixtures = Fixtures::load("tests/fixtures/en.novelmic").unwrap();
+	Harness::source("madara/sources/novelmic", fixtures).unwrap()
+}
+
+#[test]
+#[ignore = "needs madara/sources/aasq built for wasm32"]
+fn parses_ajax_chapter_list() {
+	let mut source = source();
+	let chapters = source.get_chapter_list("one-piece").unwrap();
+	assert_eq!(chapters.len(), 2);
+	assert_eq!(chapters[0].id, "one-piece/1100/");
+	assert_eq!(chapters[0].title, "Elegia");
+	assert_eq!(chapters[0].chapter, 1100.0);
+	assert_eq!(chapters[0].volume, -1.0);
+	assert_eq!(chapters[0].date_updated, 1701561600.0);
+	assert_eq!(chapters[0].url, "https://3asq.org/manga/one-piece/1100/");
+	assert!(chapters[0].scanlator.is_empty());
+	assert_eq!(chapters[1].id, "one-piece/1099.5/");
+	assert_eq!(chapters[1].chapter, 1099.5);
+	assert_eq!(chapters[1].volume, 105.0);
+	assert_eq!(chapters[1].date_updated, 1700956800.0);
+	assert_eq!(chapters[1].scanlator, "Team Arab");
+
+	// 3asq serves the list from the manga's own ajax endpoint, so the post
+	// id never has to be looked up.
+	let requests = source.requests();
+	assert_eq!(requests.len(), 1);
+	assert_eq!(requests[0].method, "POST");
+}
+
+#[test]
+#[ignore = "needs madara/sources/novelmic built for wasm32"]
+fn reads_novel_text_without_ads() {
+	let pages = novelmic()
+		.get_page_list("the-novels-extra", "the-novels-extra/chapter-1/")
+		.unwrap();
+	assert_eq!(pages.len(), 1);
+	assert_eq!(
+		pages[0].text,
+		"It was a dark night.\n\nKim Hajin closed the novel.\nThen he slept."
+	);
+}
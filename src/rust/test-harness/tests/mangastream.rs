@@ -0,0 +1,41 @@
+//! Regression tests for the mangastream template, through WestManga. Build it
+//! first with `cargo build --release -p westmanga` in `src/rust/mangastream`,
+//! then run `cargo test -- --ignored`.
+use test_harness::{Fixtures, Harness};
+
+fn source() -> Harness {
+	let fixtures = Fixtures::load("tests/fixtures/id.westmanga").unwrap();
+	Harness::source("mangastream/sources/westmanga", fixtures).unwrap()
+}
+
+#[test]
+#[ignore = "needs mangastream/sources/westmanga built for wasm32"]
+fn parses_chapter_list() {
+	let chapters = source().get_chapter_list("one-piece").unwrap();
+	assert_eq!(chapters.len(), 2);
+	assert_eq!(chapters[0].id, "one-piece-chapter-1100-bahasa-indonesia");
+	assert_eq!(chapters[0].title, "Elegia");
+	assert_eq!(chapters[0].chapter, 1100.0);
+	assert_eq!(chapters[0].date_updated, 1701561600.0);
+	assert_eq!(
+		chapters[0].url,
+		"https://westmanga.fun/one-piece-chapter-1100-bahasa-indonesia/"
+	);
+	assert_eq!(chapters[1].id, "one-piece-chapter-1099-5-bahasa-indonesia");
+	assert!(chapters[1].title.is_empty());
+	assert_eq!(chapters[1].chapter, 1099.5);
+	assert_eq!(chapters[1].date_updated, 1700956800.0);
+}
+
+#[test]
+#[ignore = "needs mangastream/sources/westmanga built for wasm32"]
+fn handles_post_id_chapter_urls() {
+	let fixtures = Fixtures::load("tests/fixtures/id.westmanga").unwrap().with(
+		"https://westmanga.fun/?p=4521",
+		r#"<ol class="ts-breadcrumb"><li><a href="https://westmanga.fun/">Home</a></li><li><a href="https://westmanga.fun/manga/one-piece/">One Piece</a></li></ol>"#,
+	);
+	let mut source = Harness::source("mangastream/sources/westmanga", fixtures).unwrap();
+	let deep_link = source.handle_url("https://westmanga.fun/?p=4521").unwrap();
+	assert_eq!(deep_link.manga.unwrap().id, "one-piece");
+	assert_eq!(deep_link.chapter.unwrap().id, "4521");
+}
@@ -0,0 +1,44 @@
+//! Regression tests for zh.manhuagui. Build the source first with
+//! `cargo build --release` in `src/rust/zh.manhuagui`, then run
+//! `cargo test -- --ignored`.
+use test_harness::{Chapter, Fixtures, Harness};
+
+fn source(fixtures: Fixtures) -> Harness {
+	Harness::source("zh.manhuagui", fixtures).unwrap()
+}
+
+fn assert_one_piece(chapters: &[Chapter]) {
+	assert_eq!(chapters.len(), 3);
+	assert_eq!(chapters[0].id, "1100");
+	assert_eq!(chapters[0].title, "第1100话 (17p)");
+	assert_eq!(chapters[0].chapter, 1100.0);
+	assert_eq!(chapters[0].volume, -1.0);
+	assert_eq!(chapters[0].scanlator, "单话");
+	assert_eq!(chapters[0].url, "/comic/1128/1100.html");
+	assert_eq!(chapters[1].id, "1099");
+	assert_eq!(chapters[1].title, "第1099.5话");
+	assert_eq!(chapters[1].chapter, 1099.5);
+	assert_eq!(chapters[2].id, "105");
+	assert_eq!(chapters[2].chapter, -1.0);
+	assert_eq!(chapters[2].volume, 105.0);
+	assert_eq!(chapters[2].scanlator, "单行本");
+}
+
+#[test]
+#[ignore = "needs zh.manhuagui built for wasm32"]
+fn parses_chapter_list() {
+	let fixtures = Fixtures::load("tests/fixtures/zh.manhuagui").unwrap();
+	let chapters = source(fixtures).get_chapter_list("1128").unwrap();
+	assert_one_piece(&chapters);
+}
+
+/// Adult series ship their chapter list LZString-compressed in a hidden
+/// input instead.
+#[test]
+#[ignore = "needs zh.manhuagui built for wasm32"]
+fn parses_hidden_chapter_list() {
+	let html = std::fs::read("tests/fixtures/zh.manhuagui/1128-hidden.html").unwrap();
+	let fixtures = Fixtures::new().with("https://www.manhuagui.com/comic/1128", html);
+	let chapters = source(fixtures).get_chapter_list("1128").unwrap();
+	assert_one_piece(&chapters);
+}
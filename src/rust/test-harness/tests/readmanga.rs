@@ -0,0 +1,30 @@
+//! Regression tests for ru.readmanga. Build the source first with
+//! `cargo build --release` in `src/rust/ru.readmanga`, then run
+//! `cargo test -- --ignored`.
+use test_harness::{Fixtures, Harness};
+
+fn source() -> Harness {
+	let fixtures = Fixtures::load("tests/fixtures/ru.readmanga").unwrap();
+	Harness::source("ru.readmanga", fixtures).unwrap()
+}
+
+#[test]
+#[ignore = "needs ru.readmanga built for wasm32"]
+fn parses_chapter_list() {
+	let chapters = source().get_chapter_list("one_piece").unwrap();
+	// Announced chapters without a release date are left out.
+	assert_eq!(chapters.len(), 2);
+	assert_eq!(chapters[0].id, "vol105/1100");
+	assert_eq!(chapters[0].title, "Элегия");
+	assert_eq!(chapters[0].volume, 105.0);
+	assert_eq!(chapters[0].chapter, 1100.0);
+	assert_eq!(chapters[0].date_updated, 1701598500.0);
+	assert_eq!(chapters[0].scanlator, "Team One Piece");
+	assert_eq!(
+		chapters[0].url,
+		"https://3.readmanga.ru/one_piece/vol105/1100?mtr=true"
+	);
+	assert_eq!(chapters[1].id, "vol104/1099");
+	assert_eq!(chapters[1].title, "104 - 1099");
+	assert_eq!(chapters[1].date_updated, 1700989200.0);
+}
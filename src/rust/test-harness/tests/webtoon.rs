@@ -0,0 +1,69 @@
+//! Regression tests for multi.webtoon. Build the source first with
+//! `cargo build --release` in `src/rust/multi.webtoon`, then run
+//! `cargo test -- --ignored`.
+use test_harness::{Fixtures, Harness};
+
+fn source() -> Harness {
+	let fixtures = Fixtures::load("tests/fixtures/multi.webtoon").unwrap();
+	Harness::source("multi.webtoon", fixtures).unwrap()
+}
+
+#[test]
+#[ignore = "needs multi.webtoon built for wasm32"]
+fn marks_locked_episodes() {
+	let chapters = source().get_chapter_list("95").unwrap();
+	assert_eq!(chapters.len(), 3);
+	assert_eq!(chapters[0].id, "3");
+	assert_eq!(chapters[0].title, "The Test");
+	assert_eq!(chapters[1].id, "2");
+	assert_eq!(chapters[1].title, "🔒 The Second Floor");
+	assert_eq!(chapters[1].chapter, 2.0);
+	assert_eq!(chapters[1].date_updated, 1404604800.0);
+	assert_eq!(
+		chapters[1].url,
+		"https://m.webtoons.com/en/fantasy/tower-of-god/season-1-ep-2/viewer?title_no=95&episode_no=2"
+	);
+	assert_eq!(chapters[2].id, "1");
+	assert_eq!(chapters[2].title, "The Beginning");
+}
+
+#[test]
+#[ignore = "needs multi.webtoon built for wasm32"]
+fn hides_locked_episodes() {
+	let mut source = source();
+	source.set_default("lockedEpisodes", serde_json::Value::Bool(false));
+	let chapters = source.get_chapter_list("95").unwrap();
+	assert_eq!(chapters.len(), 2);
+	assert_eq!(chapters[0].id, "3");
+	assert_eq!(chapters[1].id, "1");
+}
+
+#[test]
+#[ignore = "needs multi.webtoon built for wasm32"]
+fn parses_viewer_images() {
+	let pages = source().get_page_list("95", "1").unwrap();
+	assert_eq!(pages.len(), 2);
+	assert_eq!(
+		pages[0].url,
+		"https://webtoon-phinf.pstatic.net/20140630_1/001.jpg?type=q90"
+	);
+	assert_eq!(pages[1].index, 1);
+}
+
+#[test]
+#[ignore = "needs multi.webtoon built for wasm32"]
+fn explains_locked_episode_pages() {
+	let pages = source().get_page_list("95", "2").unwrap();
+	assert_eq!(pages.len(), 1);
+	assert!(pages[0].url.is_empty());
+	assert!(pages[0].text.contains("locked behind Daily Pass"));
+	assert!(pages[0]
+		.text
+		.ends_with("https://www.webtoons.com/viewer?titleNo=95&episodeNo=2"));
+}
+
+#[test]
+#[ignore = "needs multi.webtoon built for wasm32"]
+fn fails_unlocked_episodes_without_images() {
+	assert!(source().get_page_list("95", "3").is_err());
+}
@@ -0,0 +1,89 @@
+//! Regression tests for multi.xkcd. Build the source first with
+//! `./build.sh` in `src/rust/multi.xkcd`, then run `cargo test -- --ignored`.
+use test_harness::{Fixtures, Harness};
+
+fn source() -> Harness {
+	let fixtures = Fixtures::load("tests/fixtures/multi.xkcd").unwrap();
+	Harness::source("multi.xkcd", fixtures).unwrap()
+}
+
+#[test]
+#[ignore = "needs multi.xkcd built for wasm32"]
+fn lists_default_languages() {
+	let result = source().get_manga_list(&[], 1).unwrap();
+	let ids = result
+		.manga
+		.iter()
+		.map(|m| m.id.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(ids, ["multi.xkcd.en"]);
+	assert!(!result.has_more);
+}
+
+#[test]
+#[ignore = "needs multi.xkcd built for wasm32"]
+fn parses_archive() {
+	let chapters = source().get_chapter_list("multi.xkcd.en").unwrap();
+	assert_eq!(chapters.len(), 2);
+	assert_eq!(chapters[0].id, "353");
+	assert_eq!(chapters[0].title, "Python");
+	assert_eq!(chapters[0].chapter, 353.0);
+	assert_eq!(chapters[0].date_updated, 1196812800.0);
+	assert_eq!(chapters[0].url, "https://xkcd.com/353/");
+	assert_eq!(chapters[1].id, "1");
+	assert_eq!(chapters[1].date_updated, 1136073600.0);
+}
+
+#[test]
+#[ignore = "needs multi.xkcd built for wasm32"]
+fn parses_comic_page() {
+	let pages = source().get_page_list("multi.xkcd.en", "353").unwrap();
+	assert_eq!(pages.len(), 2);
+	assert_eq!(pages[0].url, "https://imgs.xkcd.com/comics/python.png");
+	assert_eq!(pages[1].index, 1);
+	assert!(pages[1].url.is_empty());
+	assert_eq!(
+		pages[1].text,
+		"Python\n\nI wrote 20 short programs in Python yesterday.  It was wonderful.  Perl, I'm leaving you."
+	);
+}
+
+#[test]
+#[ignore = "needs multi.xkcd built for wasm32"]
+fn handles_comic_urls() {
+	let deep_link = source().handle_url("https://xkcd.com/353/").unwrap();
+	assert_eq!(deep_link.manga.unwrap().id, "multi.xkcd.en");
+	assert_eq!(deep_link.chapter.unwrap().id, "353");
+}
+
+#[test]
+#[ignore = "needs multi.xkcd built for wasm32"]
+fn appends_explanation_page() {
+	let fixtures = Fixtures::load("tests/fixtures/multi.xkcd")
+		.unwrap()
+		.with(
+			"https://www.explainxkcd.com/wiki/api.php?action=parse&format=json&formatversion=2&redirects=1&prop=text&section=1&page=353",
+			r#"{"parse":{"title":"353: Python","text":"<div class=\"mw-parser-output\"><h2><span class=\"mw-headline\" id=\"Explanation\">Explanation</span></h2>\n<p>Cueball is flying after importing antigravity.\n</p>\n<p>\n</p></div>"}}"#,
+		);
+	let mut source = Harness::source("multi.xkcd", fixtures).unwrap();
+	source.set_default("explanation", serde_json::Value::Bool(true));
+	let pages = source.get_page_list("multi.xkcd.en", "353").unwrap();
+	assert_eq!(pages.len(), 3);
+	assert_eq!(pages[2].index, 2);
+	assert_eq!(
+		pages[2].text,
+		"Explanation\n\nCueball is flying after importing antigravity."
+	);
+}
+
+#[test]
+#[ignore = "needs multi.xkcd built for wasm32"]
+fn handles_translation_urls() {
+	let deep_link = source()
+		.handle_url("https://xkcd.lapin.org/index.php?number=353")
+		.unwrap();
+	assert_eq!(deep_link.manga.unwrap().id, "multi.xkcd.fr");
+	assert_eq!(deep_link.chapter.unwrap().id, "353");
+	let deep_link = source().handle_url("https://xkcd.com/archive").unwrap();
+	assert!(deep_link.chapter.is_none());
+}
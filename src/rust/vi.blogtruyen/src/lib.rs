@@ -2,7 +2,7 @@
 mod helper;
 use crate::helper::{
 	category_parser, extract_f32_from_string, genre_map, status_from_string, text_with_newlines,
-	urlencode,
+	urlencode, CATEGORY_RULES,
 };
 use aidoku::{
 	error::Result,
@@ -13,7 +13,8 @@ use aidoku::{
 		net::{HttpMethod, Request},
 		String, Vec,
 	},
-	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, Page,
+	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaViewer,
+	Page,
 };
 
 static mut CACHED_MANGA_ID: Option<String> = None;
@@ -161,7 +162,12 @@ fn get_manga_list(filters: Vec<Filter>, page: i32) -> Result<MangaPageResult> {
 				.array()
 				.map(|category| category.as_node().expect("node array").attr("title").read())
 				.collect::<Vec<String>>();
-			let (nsfw, viewer) = category_parser(&categories);
+			let (nsfw, viewer) = category_parser(
+				&CATEGORY_RULES,
+				&categories,
+				MangaContentRating::Safe,
+				MangaViewer::Rtl,
+			);
 			manga_arr.push(Manga {
 				id,
 				cover,
@@ -210,7 +216,12 @@ fn get_manga_details(id: String) -> Result<Manga> {
 			.text()
 			.read(),
 	);
-	let (mut nsfw, viewer) = category_parser(&categories);
+	let (mut nsfw, viewer) = category_parser(
+		&CATEGORY_RULES,
+		&categories,
+		MangaContentRating::Safe,
+		MangaViewer::Rtl,
+	);
 	if html
 		.select("div.modal-header:contains(Cảnh báo độ tuổi)")
 		.array()
@@ -1,5 +1,4 @@
 #![no_std]
-mod helper;
 use aidoku::{
 	error::{AidokuError, Result},
 	prelude::*,
@@ -11,9 +10,8 @@ use aidoku::{
 	Chapter, DeepLink, Filter, FilterType, Manga, MangaContentRating, MangaPageResult, MangaStatus,
 	MangaViewer, Page,
 };
-use helper::{
-	append_protocol, category_parser, extract_f32_from_string, text_with_newlines, urlencode,
-};
+use source_aidoku::{category_parser, text_with_newlines};
+use source_support::{append_protocol, extract_f32_from_string, urlencode, CategoryRules};
 
 static mut CACHED_MANGA_ID: Option<String> = None;
 static mut CACHED_MANGA: Option<Vec<u8>> = None;
@@ -220,7 +218,12 @@ fn get_manga_details(id: String) -> Result<Manga> {
 		"Tạm ngưng" => MangaStatus::Hiatus,
 		_ => MangaStatus::Unknown,
 	};
-	let (mut nsfw, viewer) = category_parser(&categories);
+	let (mut nsfw, viewer) = category_parser(
+		&CategoryRules::DEFAULT,
+		&categories,
+		MangaContentRating::Safe,
+		MangaViewer::Rtl,
+	);
 	nsfw = if html
 		.select("a[itemprop=contentRating][href='http://truyentranh86.com/DoTuoi/18/']")
 		.array()
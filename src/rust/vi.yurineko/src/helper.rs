@@ -1,8 +1,7 @@
-use aidoku::{
-	prelude::format,
-	std::{html::Node, String, Vec},
-	MangaStatus,
-};
+use aidoku::{prelude::format, std::String, MangaStatus};
+use source_support::LINE_BREAK_MARKER;
+
+pub use source_support::{i32_to_string, urlencode};
 
 // MARK: Mappings
 pub fn get_tag_id(genre: i64) -> String {
@@ -206,60 +205,6 @@ pub fn status_map(status: i64) -> MangaStatus {
 	}
 }
 
-pub fn i32_to_string(mut integer: i32) -> String {
-	if integer == 0 {
-		return String::from("0");
-	}
-	let mut string = String::with_capacity(11);
-	let pos = if integer < 0 {
-		string.insert(0, '-');
-		1
-	} else {
-		0
-	};
-	while integer != 0 {
-		let mut digit = integer % 10;
-		if pos == 1 {
-			digit *= -1;
-		}
-		string.insert(pos, char::from_u32((digit as u32) + ('0' as u32)).unwrap());
-		integer /= 10;
-	}
-	string
-}
-
-pub fn urlencode(string: String) -> String {
-	let mut result: Vec<u8> = Vec::with_capacity(string.len() * 3);
-	let hex = "0123456789abcdef".as_bytes();
-	let bytes = string.as_bytes();
-
-	for byte in bytes {
-		let curr = *byte;
-		if curr.is_ascii_lowercase() || curr.is_ascii_uppercase() || curr.is_ascii_digit() {
-			result.push(curr);
-		} else {
-			result.push(b'%');
-			result.push(hex[curr as usize >> 4]);
-			result.push(hex[curr as usize & 15]);
-		}
-	}
-
-	String::from_utf8(result).unwrap_or_default()
-}
-
-pub fn extract_f32_from_string(title: String, text: String) -> f32 {
-	text.replace(&title, " ")
-		.chars()
-		.filter(|a| (*a >= '0' && *a <= '9') || *a == ' ' || *a == '.')
-		.collect::<String>()
-		.split(' ')
-		.collect::<Vec<&str>>()
-		.into_iter()
-		.map(|a| a.parse::<f32>().unwrap_or(0.0))
-		.find(|a| *a > 0.0)
-		.unwrap_or(0.0)
-}
-
 pub fn get_search_url(base_url: String, query: String, tag: String, page: i32) -> String {
 	if !query.is_empty() {
 		format!("{base_url}/search?query={query}&page={page}")
@@ -270,18 +215,11 @@ pub fn get_search_url(base_url: String, query: String, tag: String, page: i32) -
 	}
 }
 
-pub fn text_with_newlines(html: String) -> Option<String> {
-	if !String::from(html.trim()).is_empty() {
-		if let Ok(node) = Node::new_fragment(
-			html.replace("<br>", "{{ .LINEBREAK }}")
-				.replace("</p><p>", "{{ .LINEBREAK }}")
-				.as_bytes(),
-		) {
-			Some(node.text().read().replace("{{ .LINEBREAK }}", "\n"))
-		} else {
-			None
-		}
-	} else {
-		None
-	}
+/// The title is replaced with a space so that it still separates numbers.
+pub fn extract_f32_from_string(title: String, text: String) -> f32 {
+	source_support::first_f32_from_string("", text.replace(&title, " ")).unwrap_or(0.0)
+}
+
+pub fn text_with_newlines(html: String) -> String {
+	source_aidoku::html_text_with_newlines(html.replace("</p><p>", LINE_BREAK_MARKER))
 }
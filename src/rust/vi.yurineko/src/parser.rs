@@ -23,7 +23,7 @@ pub fn parse_manga(manga_object: ObjectRef) -> Result<Manga> {
 		.join(", ");
 
 	let description_html = manga_object.get("description").as_string()?.read();
-	let description = text_with_newlines(description_html).unwrap_or_default();
+	let description = text_with_newlines(description_html);
 
 	let tags = manga_object.get("tag").as_array()?;
 	let couples = manga_object.get("couple").as_array()?;
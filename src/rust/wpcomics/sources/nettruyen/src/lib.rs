@@ -5,9 +5,10 @@ use aidoku::{
 	error::Result, prelude::*, std::net::Request, std::String, std::Vec, Chapter, DeepLink, Filter,
 	FilterType, Listing, Manga, MangaPageResult, MangaViewer, Page,
 };
-use wpcomics_template::{helper::urlencode, template, template::WPComicsSource};
+use wpcomics_template::{helper::urlencode, template::WPComicsSource};
 
 const BASE_URL: &str = "https://nettruyenvia.com";
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/101.0.4951.54 Safari/537.36 Edg/101.0.1210.39";
 
 fn get_instance() -> WPComicsSource {
 	WPComicsSource {
@@ -25,6 +26,8 @@ fn get_instance() -> WPComicsSource {
 		status_mapping: status_map,
 		time_converter: convert_time,
 		manga_viewer_page_attr: "data-src",
+		vinahost_protection: true,
+		user_agent: Some(USER_AGENT),
 		..Default::default()
 	}
 }
@@ -104,11 +107,7 @@ fn get_page_list(_manga_id: String, chapter_id: String) -> Result<Vec<Page>> {
 
 #[modify_image_request]
 fn modify_image_request(request: Request) {
-	template::modify_image_request(
-		String::from(BASE_URL),
-		String::from("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/101.0.4951.54 Safari/537.36 Edg/101.0.1210.39"),
-		request,
-	)
+	get_instance().modify_image_request(request)
 }
 
 #[handle_url]
@@ -0,0 +1,76 @@
+//! Anti-bot interstitials that set a cookie from a script and reload. The
+//! solved cookie is stored in defaults and sent with every request, images
+//! included, until the site serves a new challenge.
+use aidoku::{
+	error::{AidokuError, AidokuErrorKind, NodeError, Result},
+	std::{
+		defaults::{defaults_get, defaults_set},
+		net::{HttpMethod, Request},
+		String, StringRef, Vec,
+	},
+};
+use source_support::{is_cookie_challenge, solve_cookie_challenge};
+
+/// The cookie from the last solved challenge, kept across launches since the
+/// sites only rotate their scripts every so often.
+const COOKIE_KEY: &str = "challenge.cookie";
+
+pub fn stored_cookie() -> Option<String> {
+	defaults_get(COOKIE_KEY)
+		.and_then(|value| value.as_string())
+		.map(|value| value.read())
+		.ok()
+		.filter(|cookie| !cookie.is_empty())
+}
+
+fn store_cookie(cookie: &str) {
+	defaults_set(COOKIE_KEY, StringRef::from(cookie).0);
+}
+
+pub fn request(url: &str, user_agent: Option<&str>) -> Request {
+	let mut request = Request::new(url, HttpMethod::Get);
+	if let Some(user_agent) = user_agent {
+		request = request.header("User-Agent", user_agent);
+	}
+	if let Some(cookie) = stored_cookie() {
+		request = request.header("Cookie", &cookie);
+	}
+	request
+}
+
+/// Fetches `url` with the stored challenge cookie. When the site answers with
+/// a challenge instead (no cookie yet, or it expired), the challenge is solved,
+/// its cookie stored and the request sent again.
+pub fn fetch(url: &str, user_agent: Option<&str>) -> Result<Vec<u8>> {
+	let data = request(url, user_agent).data();
+	let html = String::from_utf8_lossy(&data);
+	if !is_cookie_challenge(&html) {
+		return Ok(data);
+	}
+	let Some(cookie) = solve_cookie_challenge(&html) else {
+		return Err(unknown_challenge());
+	};
+	store_cookie(&cookie);
+
+	let data = request(url, user_agent).data();
+	if is_cookie_challenge(String::from_utf8_lossy(&data)) {
+		store_cookie("");
+		return Err(cookie_rejected());
+	}
+	Ok(data)
+}
+
+/// The site served a challenge the solver doesn't know how to read.
+fn unknown_challenge() -> AidokuError {
+	AidokuError {
+		reason: AidokuErrorKind::Unimplemented,
+	}
+}
+
+/// The site served the challenge again after it was solved, so the cookie
+/// wasn't what it wanted.
+fn cookie_rejected() -> AidokuError {
+	AidokuError {
+		reason: AidokuErrorKind::NodeError(NodeError::ParseError),
+	}
+}
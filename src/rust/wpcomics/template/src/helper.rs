@@ -1,6 +1,7 @@
-use aidoku::{
-	prelude::format, std::html::Node, std::String, std::Vec, MangaContentRating, MangaViewer,
-};
+use aidoku::std::String;
+use source_support::CategoryRules;
+
+pub use source_support::{append_protocol, extract_f32_from_string, urlencode};
 
 pub fn trunc_trailing_comic(title: String) -> String {
 	let temp = title.chars().rev().collect::<String>();
@@ -15,46 +16,6 @@ pub fn trunc_trailing_comic(title: String) -> String {
 	}
 }
 
-pub fn extract_f32_from_string(title: String, text: String) -> Vec<f32> {
-	text.replace(&title, "")
-		.chars()
-		.filter(|a| (*a >= '0' && *a <= '9') || *a == ' ' || *a == '.' || *a == '+')
-		.collect::<String>()
-		.split(' ')
-		.collect::<Vec<&str>>()
-		.into_iter()
-		.map(|a| a.parse::<f32>().unwrap_or(-1.0))
-		.filter(|a| *a >= 0.0)
-		.collect::<Vec<f32>>()
-}
-
-pub fn append_protocol(url: String) -> String {
-	if !url.starts_with("http") {
-		format!("{}{}", "https:", url)
-	} else {
-		url
-	}
-}
-
-pub fn urlencode(string: String) -> String {
-	let mut result: Vec<u8> = Vec::with_capacity(string.len() * 3);
-	let hex = "0123456789abcdef".as_bytes();
-	let bytes = string.as_bytes();
-
-	for byte in bytes {
-		let curr = *byte;
-		if curr.is_ascii_alphanumeric() {
-			result.push(curr);
-		} else {
-			result.push(b'%');
-			result.push(hex[curr as usize >> 4]);
-			result.push(hex[curr as usize & 15]);
-		}
-	}
-
-	String::from_utf8(result).unwrap_or_default()
-}
-
 pub fn get_tag_id(genre: i64) -> String {
 	String::from(match genre {
 		1 => "marvel",
@@ -115,43 +76,9 @@ pub fn get_tag_id(genre: i64) -> String {
 	})
 }
 
-pub fn text_with_newlines(node: Node) -> String {
-	let html = node.html().read();
-	if !String::from(html.trim()).is_empty() {
-		if let Ok(node) = Node::new_fragment(
-			node.html()
-				.read()
-				.replace("<br>", "{{ .LINEBREAK }}")
-				.as_bytes(),
-		) {
-			node.text().read().replace("{{ .LINEBREAK }}", "\n")
-		} else {
-			String::new()
-		}
-	} else {
-		String::new()
-	}
-}
-
-pub fn category_parser(
-	categories: &Vec<String>,
-	default_nsfw: MangaContentRating,
-	default_viewer: MangaViewer,
-) -> (MangaContentRating, MangaViewer) {
-	let mut nsfw = default_nsfw;
-	let mut viewer = default_viewer;
-	for category in categories {
-		match category.as_str() {
-			"Smut" | "Mature" | "18+" => nsfw = MangaContentRating::Nsfw,
-			"Ecchi" | "16+" => {
-				nsfw = match nsfw {
-					MangaContentRating::Nsfw => MangaContentRating::Nsfw,
-					_ => MangaContentRating::Suggestive,
-				}
-			}
-			"Webtoon" | "Manhwa" | "Manhua" => viewer = MangaViewer::Scroll,
-			_ => continue,
-		}
-	}
-	(nsfw, viewer)
-}
+/// "Adult" is also used by sites that aren't explicit, so it isn't taken as
+/// NSFW here.
+pub const CATEGORY_RULES: CategoryRules = CategoryRules {
+	nsfw: &["Smut", "Mature", "18+"],
+	..CategoryRules::DEFAULT
+};
@@ -4,7 +4,11 @@ use aidoku::{
 	MangaPageResult, MangaStatus, MangaViewer, Page,
 };
 
-use crate::helper::{append_protocol, extract_f32_from_string, text_with_newlines};
+use crate::{
+	challenge,
+	helper::{append_protocol, extract_f32_from_string, CATEGORY_RULES},
+};
+use source_aidoku::text_with_newlines;
 
 pub struct WPComicsSource {
 	pub base_url: String,
@@ -46,6 +50,7 @@ pub struct WPComicsSource {
 	pub manga_viewer_page_url_suffix: &'static str,
 	pub page_url_transformer: fn(String) -> String,
 
+	/// Get past Vinahost-style cookie challenges, see [`crate::challenge`].
 	pub vinahost_protection: bool,
 
 	pub user_agent: Option<&'static str>,
@@ -53,110 +58,60 @@ pub struct WPComicsSource {
 
 static mut CACHED_MANGA_ID: Option<String> = None;
 static mut CACHED_MANGA: Option<Vec<u8>> = None;
-static mut VINAHOST_COOKIE: Option<String> = None;
 
-fn cache_manga_page(data: &WPComicsSource, url: &str) {
+fn cache_manga_page(data: &WPComicsSource, url: &str) -> Result<()> {
 	if unsafe { CACHED_MANGA_ID.is_some() } && unsafe { CACHED_MANGA_ID.clone().unwrap() } == url {
-		return;
+		return Ok(());
 	}
 
+	let page = data.fetch(url)?;
 	unsafe {
-		if data.vinahost_protection {
-			CACHED_MANGA = Some(data.request_vinahost(url).data());
-		} else {
-			let mut req = Request::new(url, HttpMethod::Get);
-			if let Some(user_agent) = data.user_agent {
-				req = req.header("User-Agent", user_agent);
-			}
-			CACHED_MANGA = Some(req.data());
-		}
+		CACHED_MANGA = Some(page);
 		CACHED_MANGA_ID = Some(String::from(url));
 	};
+	Ok(())
 }
 
 impl WPComicsSource {
-	fn request_vinahost(&self, url: &str) -> Request {
-		if unsafe { VINAHOST_COOKIE.is_some() } {
-			let mut req = Request::new(url, HttpMethod::Get).header(
-				"Cookie",
-				unsafe { VINAHOST_COOKIE.clone().unwrap() }.as_str(),
-			);
-			if let Some(user_agent) = self.user_agent {
-				req = req.header("User-Agent", user_agent);
-			}
-			req
-		} else if self.vinahost_protection {
-			let mut req = Request::new(url, HttpMethod::Get);
-			if let Some(user_agent) = self.user_agent {
-				req = req.header("User-Agent", user_agent);
-			}
-			if let Ok(blocked_html) = req.html() {
-				let script = blocked_html.select("script").html().read();
-				let cookie = script
-					.replace("document.cookie=\"", "")
-					.replace("\";window.location.reload(true);", "")
-					.replace("\"+\"", "");
-				unsafe {
-					VINAHOST_COOKIE = Some(cookie);
-				};
-				let mut req = Request::new(url, HttpMethod::Get).header(
-					"Cookie",
-					unsafe { VINAHOST_COOKIE.clone().unwrap() }.as_str(),
-				);
-				if let Some(user_agent) = self.user_agent {
-					req = req.header("User-Agent", user_agent);
-				}
-				req
-			} else {
-				let mut req = Request::new(url, HttpMethod::Get);
-				if let Some(user_agent) = self.user_agent {
-					req = req.header("User-Agent", user_agent);
-				}
-				req
-			}
+	/// Fetches `url`, getting past the site's anti-bot challenge if it has one.
+	fn fetch(&self, url: &str) -> Result<Vec<u8>> {
+		if self.vinahost_protection {
+			challenge::fetch(url, self.user_agent)
 		} else {
 			let mut req = Request::new(url, HttpMethod::Get);
 			if let Some(user_agent) = self.user_agent {
 				req = req.header("User-Agent", user_agent);
 			}
-			req
+			Ok(req.data())
 		}
 	}
 
+	fn fetch_html(&self, url: &str) -> Result<Node> {
+		let data = self.fetch(url)?;
+		Node::new_with_uri(String::from_utf8_lossy(&data).as_ref(), url)
+	}
+
 	fn category_parser(&self, categories: &Vec<String>) -> (MangaContentRating, MangaViewer) {
 		#[allow(clippy::needless_match)]
-		let mut nsfw = match self.nsfw {
+		let nsfw = match self.nsfw {
 			MangaContentRating::Safe => MangaContentRating::Safe,
 			MangaContentRating::Suggestive => MangaContentRating::Suggestive,
 			MangaContentRating::Nsfw => MangaContentRating::Nsfw,
 		};
 		#[allow(clippy::needless_match)]
-		let mut viewer = match self.viewer {
+		let viewer = match self.viewer {
 			MangaViewer::Rtl => MangaViewer::Rtl,
 			MangaViewer::Ltr => MangaViewer::Ltr,
 			MangaViewer::Scroll => MangaViewer::Scroll,
 			MangaViewer::Vertical => MangaViewer::Vertical,
 			_ => MangaViewer::Rtl,
 		};
-		for category in categories {
-			match category.as_str() {
-				"Smut" | "Mature" | "18+" => nsfw = MangaContentRating::Nsfw,
-				"Ecchi" | "16+" => {
-					nsfw = match nsfw {
-						MangaContentRating::Nsfw => MangaContentRating::Nsfw,
-						_ => MangaContentRating::Suggestive,
-					}
-				}
-				"Webtoon" | "Manhwa" | "Manhua" => viewer = MangaViewer::Scroll,
-				_ => continue,
-			}
-		}
-		(nsfw, viewer)
+		source_aidoku::category_parser(&CATEGORY_RULES, categories, nsfw, viewer)
 	}
 
 	pub fn get_manga_list(&self, search_url: String) -> Result<MangaPageResult> {
 		let mut has_next_page = !self.next_page.is_empty();
-		let html = self.request_vinahost(&search_url).html()?;
+		let html = self.fetch_html(&search_url)?;
 		let node = html.select(self.manga_cell);
 		let elems = node.array();
 		let mut mangas: Vec<Manga> = Vec::with_capacity(elems.len());
@@ -218,7 +173,7 @@ impl WPComicsSource {
 	}
 
 	pub fn get_manga_details(&self, id: String) -> Result<Manga> {
-		cache_manga_page(self, id.as_str());
+		cache_manga_page(self, id.as_str())?;
 		let details = unsafe { Node::new(&CACHED_MANGA.clone().unwrap())? };
 		let title = details.select(self.manga_details_title).text().read();
 		let cover = append_protocol(details.select(self.manga_details_cover).attr("src").read());
@@ -268,7 +223,7 @@ impl WPComicsSource {
 	pub fn get_chapter_list(&self, id: String) -> Result<Vec<Chapter>> {
 		let mut skipped_first = false;
 		let mut chapters: Vec<Chapter> = Vec::new();
-		cache_manga_page(self, id.as_str());
+		cache_manga_page(self, id.as_str())?;
 		let html = unsafe { Node::new(&CACHED_MANGA.clone().unwrap())? };
 		let title_untrimmed = (self.manga_details_title_transformer)(
 			html.select(self.manga_details_title).text().read(),
@@ -347,7 +302,7 @@ impl WPComicsSource {
 	pub fn get_page_list(&self, chapter_id: String) -> Result<Vec<Page>> {
 		let mut pages: Vec<Page> = Vec::new();
 		let url = format!("{}{}", &chapter_id, self.manga_viewer_page_url_suffix);
-		let html = self.request_vinahost(&url).html()?;
+		let html = self.fetch_html(&url)?;
 		for (at, page) in html.select(self.manga_viewer_page).array().enumerate() {
 			let page_node = page.as_node().expect("node array");
 			let mut page_url = page_node.attr(self.manga_viewer_page_attr).read();
@@ -365,7 +320,7 @@ impl WPComicsSource {
 	}
 
 	pub fn handle_url(&self, url: String) -> Result<DeepLink> {
-		cache_manga_page(self, url.as_str());
+		cache_manga_page(self, url.as_str())?;
 		let html = unsafe { Node::new(CACHED_MANGA.clone().unwrap())? };
 		if html.select(self.manga_viewer_page).array().is_empty() {
 			let node = html.select(".breadcrumb li");
@@ -405,6 +360,11 @@ impl WPComicsSource {
 		if let Some(user_agent) = self.user_agent {
 			request = request.header("User-Agent", user_agent);
 		}
+		if self.vinahost_protection {
+			if let Some(cookie) = challenge::stored_cookie() {
+				request = request.header("Cookie", &cookie);
+			}
+		}
 		request.header("Referer", &format!("{}/", self.base_url));
 	}
 }
@@ -1,4 +1,6 @@
-use aidoku::{std::net::HttpMethod, std::net::Request, std::String, std::Vec};
+use aidoku::{std::net::HttpMethod, std::net::Request, std::Vec};
+
+pub use source_support::{encode_uri, i32_to_string};
 
 use prost::bytes::Bytes;
 
@@ -10,62 +12,6 @@ pub mod protobuf {
 	include!(concat!(env!("OUT_DIR"), "/dmzj.comic_detail.rs"));
 }
 
-pub fn encode_uri(string: &String) -> String {
-	let mut result: Vec<u8> = Vec::with_capacity(string.len() * 3);
-	let hex = "0123456789abcdef".as_bytes();
-	let bytes = string.as_bytes();
-
-	for byte in bytes {
-		let curr = *byte;
-		if curr.is_ascii_lowercase()
-			|| curr.is_ascii_uppercase()
-			|| curr.is_ascii_digit()
-			|| (curr == b';'
-				|| curr == b',' || curr == b'/'
-				|| curr == b'?' || curr == b':'
-				|| curr == b'@' || curr == b'&'
-				|| curr == b'=' || curr == b'+'
-				|| curr == b'$')
-			|| (curr == b'-'
-				|| curr == b'_' || curr == b'.'
-				|| curr == b'!' || curr == b'~'
-				|| curr == b'*' || curr == b'\''
-				|| curr == b'(' || curr == b')')
-			|| (curr == b'#')
-		{
-			result.push(curr);
-		} else {
-			result.push(b'%');
-			result.push(hex[curr as usize >> 4]);
-			result.push(hex[curr as usize & 15]);
-		}
-	}
-
-	String::from_utf8(result).unwrap_or_default()
-}
-
-pub fn i32_to_string(mut integer: i32) -> String {
-	if integer == 0 {
-		return String::from("0");
-	}
-	let mut string = String::with_capacity(11);
-	let pos = if integer < 0 {
-		string.insert(0, '-');
-		1
-	} else {
-		0
-	};
-	while integer != 0 {
-		let mut digit = integer % 10;
-		if pos == 1 {
-			digit *= -1;
-		}
-		string.insert(pos, char::from_u32((digit as u32) + ('0' as u32)).unwrap());
-		integer /= 10;
-	}
-	string
-}
-
 pub fn get(url: &str) -> Request {
 	Request::new(url, HttpMethod::Get)
     .header("Referer", "https://www.dmzj.com/")